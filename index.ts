@@ -1,5 +1,6 @@
 #!/usr/bin/env node
 
+import crypto from "crypto";
 import fs from "fs";
 import path from "path";
 import chalk from "chalk";
@@ -17,47 +18,1174 @@ global.log_with_color = (message, color) => {
   process.stdout.write(chalk[color](message));
 };
 // @ts-ignore
-global.read_file = (file) => fs.readFileSync(file, "utf8");
+global.read_file = (file) => {
+  // Editors that save by replacing the file can leave it briefly unreadable, the generator
+  // reports it as an error instead of throwing
+  try {
+    return fs.readFileSync(file, "utf8");
+  } catch {
+    return undefined;
+  }
+};
 // @ts-ignore
 global.canonicalize = (file) => path.resolve(file);
 // @ts-ignore
 global.file_exists = (file) => fs.existsSync(file);
 // @ts-ignore
-global.create_dir_all = (dir) => fs.mkdirSync(dir, { recursive: true });
+global.create_dir_all = (dir) => {
+  if (!program.opts().dryRun) fs.mkdirSync(dir, { recursive: true });
+};
 // @ts-ignore
-global.write_file = (file, content) => fs.writeFileSync(file, content);
+global.now = () => performance.now();
+// @ts-ignore
+global.write_file = (file, content) => {
+  // With --dry-run the generated files are printed instead of written
+  if (program.opts().dryRun) {
+    process.stdout.write(chalk.gray(`// ${file}\n`));
+    process.stdout.write(content.endsWith("\n") ? content : `${content}\n`);
+    return;
+  }
+
+  fs.writeFileSync(file, content);
+};
 
 program
-  .option("-i, --input <input>", "Input directory", "./shaders")
+  .option("-i, --input <input...>", "Input directories", ["./shaders"])
   .option("-o, --output <output>", "Output directory", "./output")
   .option("-f, --file <file>", "File to process")
-  .option("-w, --watch", "Watch for changes", false);
+  .option(
+    "--language-output <language=folder...>",
+    "Folder the files of a language are written to instead of --output, e.g. rs=src/generated"
+  )
+  .option(
+    "-l, --language <language>",
+    "Output languages, comma separated (ts, ts-neon, ts-edgedb, ts-xata, ts-fauna, ts-mongo, ts-dynamodb, ts-redis-json, ts-kafka, ts-mqtt, ts-websocket, ts-grpc, ts-socket-io, ts-webrtc-datachannel, ts-shared-arraybuffer, ts-wasm-memory, ts-webgpu-buffer, ts-pixi, rs, json, ts-webgpu-layout, py, c, wgsl)",
+    "ts"
+  )
+  .option("-w, --watch", "Watch for changes", false)
+  .option(
+    "--once",
+    "Generate every shader in the input directory once and exit",
+    false
+  )
+  .option("-c, --config <config>", "Config file", ".glsltypes.toml")
+  .option(
+    "--emit-interfaces",
+    "Emit a TypeScript interface for the uniforms (ts only)",
+    false
+  )
+  .option("--emit-zod", "Emit a zod schema for the uniforms (ts only)", false)
+  .option(
+    "--emit-named-exports",
+    "Emit a constant for the name of every uniform and attribute, e.g. WAVE_UNIFORM_TIME (ts only)",
+    false
+  )
+  .option(
+    "--emit-setters",
+    "Emit a function that sets the uniforms on a WebGL2 program (ts only)",
+    false
+  )
+  .option(
+    "--emit-location-cache",
+    "Emit a function that looks up the location of every uniform (ts only)",
+    false
+  )
+  .option(
+    "--emit-threejs",
+    "Emit a function that creates a Three.js material for the shader (ts only)",
+    false
+  )
+  .option(
+    "--emit-docs",
+    "Write a Markdown table of the uniforms next to each TypeScript file (ts only)",
+    false
+  )
+  .option(
+    "--emit-json-schema",
+    "Write a JSON Schema of the shader object next to each TypeScript file (ts only)",
+    false
+  )
+  .option(
+    "--template <path>",
+    "Render each shader with a Handlebars template instead of the language backend, e.g. types.ts.hbs"
+  )
+  .option(
+    "--strip-comments",
+    "Strip comments from the shader source embedded in the output",
+    false
+  )
+  .option(
+    "--minify-source",
+    "Minify the shader source embedded in the output",
+    false
+  )
+  .option(
+    "--source-map",
+    "Write a source map of the embedded shader source next to each TypeScript file",
+    false
+  )
+  .option(
+    "--naming-convention <style>",
+    "Case of the exported constant names: pascal, camel or screaming-snake",
+    "pascal"
+  )
+  .option(
+    "--output-extension <extension>",
+    "Extension of the TypeScript files: .ts, .mts, .cts, or .d.ts, .d.mts and .d.cts for declarations only",
+    ".ts"
+  )
+  .option(
+    "--export-prefix <prefix>",
+    "Prefix of the exported names of shaders whose file name starts with a digit",
+    "_"
+  )
+  .option(
+    "--verbosity <level>",
+    "Least severe messages to print: debug, info, warn or error",
+    "info"
+  )
+  .option(
+    "--warn-unused",
+    "Warn about uniforms and attributes that the shader never reads",
+    false
+  )
+  .option(
+    "--json-errors",
+    "Report warnings and errors as newline-delimited JSON",
+    false
+  )
+  .option(
+    "--include <glob...>",
+    "Only generate shaders whose path in the input folder matches one of the globs"
+  )
+  .option(
+    "--exclude <glob...>",
+    "Skip shaders whose path in the input folder matches one of the globs"
+  )
+  .option(
+    "--mirror-structure",
+    "Mirror the input folder hierarchy in the output folder",
+    false
+  )
+  .option(
+    "--emit-index",
+    "Write an index file re-exporting every generated shader to each output folder (ts, rs)",
+    false
+  )
+  .option(
+    "--emit-dependency-graph",
+    "Write shader-deps.json with the files each shader imports to the output folder",
+    false
+  )
+  .option(
+    "--emit-dependency-graph-dot",
+    "Write shader-deps.dot, a Graphviz graph of the imports, to the output folder",
+    false
+  )
+  .option(
+    "--emit-vite-plugin",
+    "Write glsl-types.plugin.ts, a Vite plugin that generates the shaders without the CLI, to the output folder",
+    false
+  )
+  .option(
+    "--no-cache",
+    "Regenerate every shader with --once or at the start of --watch instead of skipping the unchanged ones"
+  )
+  .option(
+    "--dry-run",
+    "Print the generated files to stdout instead of writing them",
+    false
+  )
+  .option(
+    "--debounce-ms <ms>",
+    "Milliseconds to wait for a file to settle before regenerating it in watch mode",
+    (value: string) => parseInt(value, 10),
+    50
+  )
+  .option(
+    "--poll",
+    "Watch by checking modification times at an interval, for file systems without change events",
+    false
+  )
+  .option(
+    "--poll-interval-ms <ms>",
+    "Milliseconds between checks with --poll",
+    (value: string) => parseInt(value, 10),
+    500
+  );
 
 program.parse();
 
-const SHADER_EXTENSIONS = [".vert", ".frag", ".vs", ".fs"];
+const SHADER_EXTENSIONS = [
+  ".vert",
+  ".frag",
+  ".vs",
+  ".fs",
+  ".geom",
+  ".gs",
+  ".comp",
+  ".tesc",
+  ".tese",
+];
 
 const options = program.opts();
 
-if (options.watch) {
-  process.stdout.write(chalk.green("Watching for changes\n"));
-  fs.watch(options.input, { recursive: true }, (eventType, filename) => {
-    console.log("File change detected");
-    if (!filename) return;
-    filename = path.resolve(options.input, filename);
+function loadConfig() {
+  if (!fs.existsSync(options.config)) {
+    if (program.getOptionValueSource("config") !== "default") {
+      console.error(`Config file ${options.config} does not exist`);
+      process.exit(1);
+    }
 
-    if (SHADER_EXTENSIONS.includes(path.extname(filename))) {
-      const start = performance.now();
-      glslTypes.start_cli(filename, options.input, options.output);
-      const end = performance.now();
+    return glslTypes.parse_config("");
+  }
 
-      process.stdout.write(chalk.green("[INFO]\t"));
+  try {
+    return glslTypes.parse_config(fs.readFileSync(options.config, "utf8"));
+  } catch (error) {
+    console.error(`Invalid config file ${options.config}: ${error}`);
+    process.exit(1);
+  }
+}
+
+const config = loadConfig();
+
+// Flags given on the command line take precedence over the config file
+for (const [option, value] of [
+  ["input", config.input_folder && [config.input_folder]],
+  ["output", config.output_folder],
+  ["language", config.language],
+  ["debounceMs", config.debounce_ms],
+]) {
+  if (value !== undefined && program.getOptionValueSource(option) === "default") {
+    options[option] = value;
+  }
+}
+
+const generatorOptions = config.options();
+for (const languageOutput of options.languageOutput ?? []) {
+  const separator = languageOutput.indexOf("=");
+
+  if (separator <= 0) {
+    console.error(`Invalid --language-output ${languageOutput}, expected language=folder`);
+    process.exit(1);
+  }
+
+  generatorOptions.set_language_output_folder(
+    languageOutput.slice(0, separator),
+    languageOutput.slice(separator + 1)
+  );
+}
+generatorOptions.set_emit_interfaces(options.emitInterfaces);
+generatorOptions.set_emit_zod(options.emitZod);
+generatorOptions.set_emit_named_exports(options.emitNamedExports);
+generatorOptions.set_emit_setters(options.emitSetters);
+generatorOptions.set_emit_location_cache(options.emitLocationCache);
+generatorOptions.set_emit_threejs(options.emitThreejs);
+generatorOptions.set_warn_unused(options.warnUnused);
+generatorOptions.set_emit_docs(options.emitDocs);
+generatorOptions.set_dry_run(options.dryRun);
+generatorOptions.set_emit_json_schema(options.emitJsonSchema);
+generatorOptions.set_json_errors(options.jsonErrors);
+if (program.getOptionValueSource("stripComments") !== "default") {
+  generatorOptions.set_strip_comments(options.stripComments);
+}
+generatorOptions.set_minify_source(options.minifySource);
+generatorOptions.set_source_map(options.sourceMap);
+if (
+  program.getOptionValueSource("namingConvention") !== "default" &&
+  !generatorOptions.set_naming_convention(options.namingConvention)
+) {
+  console.error(`Invalid naming convention ${options.namingConvention}`);
+  process.exit(1);
+}
+if (
+  program.getOptionValueSource("outputExtension") !== "default" &&
+  !generatorOptions.set_output_extension(options.outputExtension)
+) {
+  console.error(`Unsupported output extension ${options.outputExtension}`);
+  process.exit(1);
+}
+if (
+  program.getOptionValueSource("exportPrefix") !== "default" &&
+  !generatorOptions.set_export_prefix(options.exportPrefix)
+) {
+  console.error(`Invalid export prefix ${options.exportPrefix}`);
+  process.exit(1);
+}
+if (options.template !== undefined) {
+  if (!fs.existsSync(options.template)) {
+    console.error(`Template ${options.template} does not exist`);
+    process.exit(1);
+  }
+
+  // `types.ts.hbs` generates `.ts` files
+  const extension = path.extname(path.basename(options.template, ".hbs")).slice(1);
+  generatorOptions.set_template(
+    fs.readFileSync(options.template, "utf8"),
+    extension || "txt"
+  );
+}
+if (!generatorOptions.set_verbosity(options.verbosity)) {
+  console.error(`Invalid verbosity ${options.verbosity}`);
+  process.exit(1);
+}
+if (program.getOptionValueSource("mirrorStructure") !== "default") {
+  generatorOptions.set_mirror_structure(options.mirrorStructure);
+}
+SHADER_EXTENSIONS.push(
+  ...generatorOptions.extensions().map((extension: string) => `.${extension}`)
+);
+
+const VERBOSITY_LEVELS = ["debug", "info", "warn", "error"];
+
+// Whether messages of `level` are printed with the --verbosity given
+function shouldLog(level: string): boolean {
+  return VERBOSITY_LEVELS.indexOf(level) >= VERBOSITY_LEVELS.indexOf(options.verbosity);
+}
+
+function findShaders(directory: string): string[] {
+  const shaders: string[] = [];
+
+  for (const entry of fs.readdirSync(directory, { withFileTypes: true })) {
+    const entryPath = path.resolve(directory, entry.name);
+
+    if (entry.isDirectory()) {
+      shaders.push(...findShaders(entryPath));
+    } else if (SHADER_EXTENSIONS.includes(path.extname(entry.name))) {
+      shaders.push(entryPath);
+    }
+  }
+
+  return shaders;
+}
+
+// Modification time of every file under `directory`, keyed by its path relative to it
+function modificationTimes(directory: string, prefix = ""): Map<string, number> {
+  const times = new Map<string, number>();
+
+  for (const entry of fs.readdirSync(path.join(directory, prefix), { withFileTypes: true })) {
+    const name = path.join(prefix, entry.name);
+
+    // Entries can disappear while the folder is being listed
+    try {
+      if (entry.isDirectory()) {
+        for (const [file, time] of modificationTimes(directory, name)) times.set(file, time);
+      } else {
+        times.set(name, fs.statSync(path.join(directory, name)).mtimeMs);
+      }
+    } catch {}
+  }
+
+  return times;
+}
+
+// Calls `listener` like fs.watch does, by comparing modification times every `interval` ms.
+// fs.watch relies on file system events, which network file systems and Docker bind mounts
+// do not deliver
+function pollDirectory(
+  directory: string,
+  interval: number,
+  listener: (eventType: string, filename: string) => void
+) {
+  let times = modificationTimes(directory);
+
+  const timer = setInterval(() => {
+    const current = modificationTimes(directory);
+
+    for (const [file, time] of current) {
+      const previous = times.get(file);
+
+      if (previous === undefined) listener("rename", file);
+      else if (previous !== time) listener("change", file);
+    }
+
+    for (const file of times.keys()) {
+      if (!current.has(file)) listener("rename", file);
+    }
+
+    times = current;
+  }, interval);
+
+  return { close: () => clearInterval(timer) };
+}
+
+// Supports `**`, `*`, `?` and `{a,b}`, matched against `/` separated paths
+function globToRegExp(glob: string): RegExp {
+  let source = "";
+  let braces = 0;
+
+  for (let i = 0; i < glob.length; i++) {
+    const char = glob[i];
+
+    if (glob.startsWith("**/", i)) {
+      source += "(?:.*/)?";
+      i += 2;
+    } else if (glob.startsWith("**", i)) {
+      source += ".*";
+      i += 1;
+    } else if (char === "*") {
+      source += "[^/]*";
+    } else if (char === "?") {
+      source += "[^/]";
+    } else if (char === "{") {
+      source += "(?:";
+      braces++;
+    } else if (char === "}" && braces > 0) {
+      source += ")";
+      braces--;
+    } else if (char === "," && braces > 0) {
+      source += "|";
+    } else {
+      source += char.replace(/[.+^$()|[\]\\]/g, "\\$&");
+    }
+  }
+
+  return new RegExp(`^${source}$`);
+}
+
+const includeGlobs = (options.include ?? []).map(globToRegExp);
+const excludeGlobs = (options.exclude ?? []).map(globToRegExp);
+
+// Whether --include and --exclude select the shader, `name` is relative to its input folder
+function isSelected(name: string): boolean {
+  const normalized = name.split(path.sep).join("/");
+
+  return (
+    (includeGlobs.length === 0 ||
+      includeGlobs.some((glob: RegExp) => glob.test(normalized))) &&
+    !excludeGlobs.some((glob: RegExp) => glob.test(normalized))
+  );
+}
+
+const routes = config.routes.map((route) => ({
+  glob: globToRegExp(route.pattern),
+  language: route.language ?? options.language,
+  output: route.output_folder ?? options.output,
+}));
+
+// Language and output folder of the first `[[routes]]` entry matching the shader, or the
+// global ones. `name` is relative to its input folder
+function routeFor(name: string): { language: string; output: string } {
+  const normalized = name.split(path.sep).join("/");
+
+  return (
+    routes.find((route) => route.glob.test(normalized)) ?? {
+      language: options.language,
+      output: options.output,
+    }
+  );
+}
+
+// Input folder a file given with --file belongs to, output paths are mirrored relative to it
+function inputRoot(filename: string): string {
+  return (
+    options.input.find(
+      (input: string) => !path.relative(input, filename).startsWith("..")
+    ) ?? options.input[0]
+  );
+}
+
+// Shader -> every file it imports directly or through other imports
+const dependencies = new Map<string, string[]>();
+const emitDependencyGraph =
+  options.emitDependencyGraph || options.emitDependencyGraphDot;
+
+function updateDependencies(shader: string) {
+  shader = path.resolve(shader);
+  const imports = new Set<string>();
+  const visit = (file: string) => {
+    for (const imported of glslTypes.direct_imports(file)) {
+      if (!imports.has(imported)) {
+        imports.add(imported);
+        visit(imported);
+      }
+    }
+  };
+
+  visit(shader);
+  // A shader that imports itself through a cycle is not its own dependency
+  imports.delete(shader);
+  dependencies.set(shader, [...imports]);
+}
+
+// Paths are relative to the working directory
+function writeDependencyGraph() {
+  const name = (file: string) =>
+    path.relative(process.cwd(), file).split(path.sep).join("/");
+  const shaders = [...dependencies.entries()].sort(([a], [b]) => a.localeCompare(b));
+
+  // @ts-ignore
+  global.create_dir_all(options.output);
+
+  if (options.emitDependencyGraph) {
+    const graph = Object.fromEntries(
+      shaders.map(([shader, imports]) => [name(shader), imports.map(name)])
+    );
+    // @ts-ignore
+    global.write_file(
+      path.join(options.output, "shader-deps.json"),
+      `${JSON.stringify(graph, null, 2)}\n`
+    );
+  }
+
+  if (options.emitDependencyGraphDot) {
+    // Edges are the direct imports, so a file shared by several shaders appears once
+    const edges = new Set<string>();
+    for (const [shader, imports] of shaders) {
+      for (const file of [shader, ...imports]) {
+        for (const imported of glslTypes.direct_imports(file)) {
+          edges.add(`  "${name(file)}" -> "${name(imported)}";\n`);
+        }
+      }
+    }
+
+    const nodes = shaders.map(([shader]) => `  "${name(shader)}" [shape=box];\n`);
+    // @ts-ignore
+    global.write_file(
+      path.join(options.output, "shader-deps.dot"),
+      `digraph shaders {\n${nodes.join("")}${[...edges].sort().join("")}}\n`
+    );
+  }
+}
+
+// Only files starting with it are indexed, the output folder can hold other files
+const GENERATED_HEADER = "// DO NOT EDIT THIS FILE\n// This file is generated by glsl-types\n";
+
+// Files of `folder`, relative to it. Subfolders are only searched when the structure is
+// mirrored, as the generated files are not written to them otherwise
+function outputFiles(folder: string, prefix = ""): string[] {
+  return fs.readdirSync(path.join(folder, prefix), { withFileTypes: true }).flatMap((entry) => {
+    const name = path.join(prefix, entry.name);
+
+    if (entry.isDirectory()) {
+      return generatorOptions.mirror_structure ? outputFiles(folder, name) : [];
+    }
+    return [name];
+  });
+}
+
+// `index.ts` re-exports the shader constant of every generated TypeScript file and `index.rs`
+// declares a module for every generated Rust file. They are rewritten after each generation,
+// so they follow the shaders that are added in watch mode
+function writeIndex() {
+  const folders = new Set<string>([
+    options.output,
+    ...routes.map((route) => route.output),
+    ...(options.languageOutput ?? []).map((languageOutput: string) =>
+      languageOutput.slice(languageOutput.indexOf("=") + 1)
+    ),
+  ]);
+  const extension = generatorOptions.output_extension;
+  const typeScriptIndex = `index${extension}`;
+
+  for (const folder of folders) {
+    if (!fs.existsSync(folder)) continue;
+
+    const exports: string[] = [];
+    const modules: string[] = [];
+
+    for (const file of outputFiles(folder).sort()) {
+      if (file === typeScriptIndex || file === "index.rs") continue;
+
+      const contents = fs.readFileSync(path.join(folder, file), "utf8");
+      if (!contents.startsWith(GENERATED_HEADER)) continue;
+
+      const name = file.split(path.sep).join("/");
+      const typeScript = /^(.*?)(\.d)?\.([mc]?)ts$/.exec(name);
+
+      if (typeScript) {
+        // The first constant is the shader object, the named exports come after it
+        const constant = /^export (?:declare )?const (\w+)/m.exec(contents);
+        // ES modules import `.mts` and `.cts` files by the extension of their output
+        const specifier = typeScript[3] ? `${typeScript[1]}.${typeScript[3]}js` : typeScript[1];
+
+        if (constant) exports.push(`export { ${constant[1]} } from "./${specifier}";\n`);
+      } else if (name.endsWith(".rs")) {
+        const module = name
+          .slice(0, -".rs".length)
+          .replace(/\W/g, "_")
+          .replace(/^(?=\d)/, "_");
+        modules.push(`pub mod ${module} {\n  include!("${name}");\n}\n`);
+      }
+    }
+
+    if (exports.length > 0) {
+      // @ts-ignore
+      global.write_file(
+        path.join(folder, typeScriptIndex),
+        `${GENERATED_HEADER}\n${exports.join("")}`
+      );
+    }
+    if (modules.length > 0) {
+      // @ts-ignore
+      global.write_file(path.join(folder, "index.rs"), `${GENERATED_HEADER}\n${modules.join("\n")}`);
+    }
+  }
+}
+
+// Setter calls that give the plugin the options of this run, on top of the config file
+function vitePluginOptionCalls(): string[] {
+  const calls = (options.languageOutput ?? []).map((languageOutput: string) => {
+    const separator = languageOutput.indexOf("=");
+
+    return `set_language_output_folder(${JSON.stringify(
+      languageOutput.slice(0, separator)
+    )}, ${JSON.stringify(languageOutput.slice(separator + 1))})`;
+  });
+
+  calls.push(
+    `set_emit_interfaces(${options.emitInterfaces})`,
+    `set_emit_zod(${options.emitZod})`,
+    `set_emit_named_exports(${options.emitNamedExports})`,
+    `set_emit_setters(${options.emitSetters})`,
+    `set_emit_location_cache(${options.emitLocationCache})`,
+    `set_emit_threejs(${options.emitThreejs})`,
+    `set_warn_unused(${options.warnUnused})`,
+    `set_emit_docs(${options.emitDocs})`,
+    `set_emit_json_schema(${options.emitJsonSchema})`,
+    `set_minify_source(${options.minifySource})`,
+    `set_source_map(${options.sourceMap})`,
+    `set_verbosity(${JSON.stringify(options.verbosity)})`
+  );
+
+  if (program.getOptionValueSource("stripComments") !== "default") {
+    calls.push(`set_strip_comments(${options.stripComments})`);
+  }
+  if (program.getOptionValueSource("namingConvention") !== "default") {
+    calls.push(`set_naming_convention(${JSON.stringify(options.namingConvention)})`);
+  }
+  if (program.getOptionValueSource("outputExtension") !== "default") {
+    calls.push(`set_output_extension(${JSON.stringify(options.outputExtension)})`);
+  }
+  if (program.getOptionValueSource("exportPrefix") !== "default") {
+    calls.push(`set_export_prefix(${JSON.stringify(options.exportPrefix)})`);
+  }
+  if (program.getOptionValueSource("mirrorStructure") !== "default") {
+    calls.push(`set_mirror_structure(${options.mirrorStructure})`);
+  }
+  if (options.template !== undefined) {
+    const extension = path.extname(path.basename(options.template, ".hbs")).slice(1);
+    calls.push(
+      `set_template(${JSON.stringify(fs.readFileSync(options.template, "utf8"))}, ${JSON.stringify(
+        extension || "txt"
+      )})`
+    );
+  }
+
+  return calls;
+}
+
+// Writes glsl-types.plugin.ts, a Vite plugin that generates the shaders of this run from
+// inside Vite through the wasm API. Paths are relative to the working directory, which is
+// expected to be the root of the Vite project
+function writeVitePlugin() {
+  const relative = (file: string) =>
+    path.relative(process.cwd(), path.resolve(file)).split(path.sep).join("/") || ".";
+  const pluginRoutes = routes.map(
+    (route) =>
+      `  { glob: /${route.glob.source}/, language: ${JSON.stringify(
+        route.language
+      )}, output: ${JSON.stringify(relative(route.output))} },\n`
+  );
+  const optionCalls = vitePluginOptionCalls().map((call) => `  options.${call};\n`);
+
+  const plugin = `// DO NOT EDIT THIS FILE
+// This file is generated by glsl-types
+
+import fs from "fs";
+import path from "path";
+import { createRequire } from "module";
+import type { Plugin } from "vite";
+
+const require = createRequire(import.meta.url);
+
+const INPUTS: string[] = ${JSON.stringify(options.input.map(relative))};
+const OUTPUT = ${JSON.stringify(relative(options.output))};
+const LANGUAGE = ${JSON.stringify(options.language)};
+const CONFIG = ${JSON.stringify(relative(options.config))};
+const EXTENSIONS: string[] = ${JSON.stringify(SHADER_EXTENSIONS)};
+const ROUTES: { glob: RegExp; language: string; output: string }[] = [
+${pluginRoutes.join("")}];
+
+// The generator calls these to reach the file system and the console
+Object.assign(globalThis, {
+  logln: (message: string) => console.log(message),
+  log: (message: string) => process.stdout.write(message),
+  log_with_color: (message: string) => process.stdout.write(message),
+  read_file: (file: string) => {
+    try {
+      return fs.readFileSync(file, "utf8");
+    } catch {
+      return undefined;
+    }
+  },
+  canonicalize: (file: string) => path.resolve(file),
+  file_exists: (file: string) => fs.existsSync(file),
+  create_dir_all: (dir: string) => fs.mkdirSync(dir, { recursive: true }),
+  write_file: (file: string, content: string) => fs.writeFileSync(file, content),
+  now: () => performance.now(),
+});
+
+const glslTypes = require("glsl-types/dist/pkg/glsl_types.cjs");
+
+function createOptions() {
+  const config = glslTypes.parse_config(
+    fs.existsSync(CONFIG) ? fs.readFileSync(CONFIG, "utf8") : ""
+  );
+  const options = config.options();
+${optionCalls.join("")}
+  for (const extension of options.extensions()) EXTENSIONS.push(\`.\${extension}\`);
+
+  return options;
+}
+
+function findShaders(directory: string): string[] {
+  if (!fs.existsSync(directory)) return [];
+
+  return fs.readdirSync(directory, { withFileTypes: true }).flatMap((entry) => {
+    const entryPath = path.resolve(directory, entry.name);
+
+    if (entry.isDirectory()) return findShaders(entryPath);
+    return EXTENSIONS.includes(path.extname(entry.name)) ? [entryPath] : [];
+  });
+}
+
+export default function glslTypesPlugin(): Plugin {
+  const options = createOptions();
+
+  const inputOf = (file: string) =>
+    INPUTS.find((input) => !path.relative(input, file).startsWith(".."));
+
+  const generate = (file: string) => {
+    const input = inputOf(file);
+    if (input === undefined) return;
+
+    const name = path.relative(input, file).split(path.sep).join("/");
+    const route = ROUTES.find((route) => route.glob.test(name));
+    glslTypes.start_cli(
+      file,
+      input,
+      route?.output ?? OUTPUT,
+      route?.language ?? LANGUAGE,
+      options
+    );
+  };
+
+  const generateAll = () =>
+    INPUTS.flatMap((input) => findShaders(input)).forEach(generate);
+
+  const onChange = (file: string) => {
+    if (inputOf(file) === undefined) return;
+
+    glslTypes.invalidate_import_cache(file);
+
+    // Any other file in an input folder can be imported by the shaders
+    if (EXTENSIONS.includes(path.extname(file))) {
+      generate(file);
+    } else {
+      generateAll();
+    }
+  };
+
+  return {
+    name: "glsl-types",
+    buildStart() {
+      generateAll();
+    },
+    configureServer(server) {
+      server.watcher.add(INPUTS.map((input) => path.resolve(input)));
+      server.watcher.on("add", onChange);
+      server.watcher.on("change", onChange);
+    },
+  };
+}
+`;
+
+  // @ts-ignore
+  global.create_dir_all(options.output);
+  // @ts-ignore
+  global.write_file(path.join(options.output, "glsl-types.plugin.ts"), plugin);
+}
+
+if (options.emitVitePlugin) writeVitePlugin();
+
+// Bumped when the format of the cache file changes
+const CACHE_FORMAT = 1;
+
+type FileState = { mtimeMs: number; hash: string };
+
+function fileState(file: string): FileState {
+  return {
+    mtimeMs: fs.statSync(file).mtimeMs,
+    hash: crypto.createHash("sha256").update(fs.readFileSync(file)).digest("hex"),
+  };
+}
+
+// Whether a file still has the recorded content, the hash is only computed when the
+// modification time differs
+function isUnchanged(file: string, state: FileState): boolean {
+  try {
+    return fs.statSync(file).mtimeMs === state.mtimeMs || fileState(file).hash === state.hash;
+  } catch {
+    return false;
+  }
+}
+
+// The flags, the config and the template change what is generated, a cache written with
+// other settings or by another version of the generator is ignored
+function cacheSettings(): string {
+  const hash = crypto.createHash("sha256");
+  hash.update(JSON.stringify(process.argv.slice(2)));
+  if (fs.existsSync(options.config)) hash.update(fs.readFileSync(options.config));
+  if (options.template !== undefined) hash.update(fs.readFileSync(options.template));
+  return `${CACHE_FORMAT}:${glslTypes.version()}:${hash.digest("hex")}`;
+}
+
+// Generates every selected shader of the input folders, skipping the ones the cache knows
+// are unchanged, and prints a summary. Returns the number of shaders that failed
+function generateAll(): number {
+  for (const input of options.input) {
+    if (!fs.existsSync(input)) {
+      console.error(`Input directory ${input} does not exist`);
+      process.exit(1);
+    }
+  }
+
+  // Every shader is generated even when one fails, the errors are summarised at the end
+  const batchStart = performance.now();
+  const shaders = options.input.flatMap((input: string) =>
+    findShaders(input)
+      .filter((filename) => isSelected(path.relative(input, filename)))
+      .map((filename) => ({ input, filename }))
+  );
+  // Shaders with the same name write the same output file, e.g. `grass.vert` and
+  // `grass.frag`, or `terrain/grass.vert` and `ui/grass.vert` unless the structure is mirrored
+  const outputs = new Map<string, string>();
+  for (const { input, filename } of shaders) {
+    const name = path.relative(input, filename);
+    const stem = path.basename(name, path.extname(name));
+    const output = path.join(
+      routeFor(name).output,
+      generatorOptions.mirror_structure ? path.join(path.dirname(name), stem) : stem
+    );
+    const previous = outputs.get(output);
+
+    if (previous !== undefined) {
+      if (!shouldLog("warn")) continue;
+
+      process.stdout.write(chalk.yellow("[WARN]\t"));
       process.stdout.write(
-        `File processed ${chalk.blue(path.relative(options.input, filename))}`
+        `${chalk.blue(previous)} and ${chalk.blue(name)} generate the same output file\n`
       );
-      process.stdout.write(chalk.gray(` (${(end - start).toFixed(2)}ms)\n`));
+    } else {
+      outputs.set(output, name);
     }
+  }
+
+  // Shader -> state of the shader and the files it imports when it was last generated. The
+  // output of --dry-run is not written, so it neither uses nor updates the cache
+  const useCache = options.cache && !options.dryRun;
+  const cacheFile = path.join(options.output, ".glsltypes-cache.json");
+  const settings = cacheSettings();
+  let cached: Record<string, Record<string, FileState>> = {};
+
+  if (useCache && fs.existsSync(cacheFile)) {
+    try {
+      const cache = JSON.parse(fs.readFileSync(cacheFile, "utf8"));
+      if (cache.settings === settings) cached = cache.shaders;
+    } catch {}
+  }
+
+  const cache: Record<string, Record<string, FileState>> = {};
+
+  const results = shaders.map(({ input, filename }) => {
+    const name = path.relative(input, filename);
+    const key = path.relative(process.cwd(), filename);
+    const inputs = cached[key];
+
+    if (
+      useCache &&
+      inputs !== undefined &&
+      Object.entries(inputs).every(([file, state]) => isUnchanged(file, state))
+    ) {
+      cache[key] = inputs;
+      return { name, succeeded: true, cached: true, duration: 0 };
+    }
+
+    const route = routeFor(name);
+    const start = performance.now();
+    const succeeded = glslTypes.start_cli(
+      filename,
+      input,
+      route.output,
+      route.language,
+      generatorOptions
+    );
+    const duration = performance.now() - start;
+
+    // Failed shaders are not cached, so they are retried on the next run
+    if (succeeded && useCache) {
+      updateDependencies(filename);
+      cache[key] = Object.fromEntries(
+        [filename, ...(dependencies.get(path.resolve(filename)) ?? [])].map((file) => [
+          path.relative(process.cwd(), file),
+          fileState(file),
+        ])
+      );
+    }
+
+    return { name, succeeded, cached: false, duration };
   });
+
+  if (useCache) {
+    fs.mkdirSync(options.output, { recursive: true });
+    fs.writeFileSync(
+      cacheFile,
+      `${JSON.stringify({ settings, shaders: cache }, null, 2)}\n`
+    );
+  }
+
+  // Cached shaders are checked too, a conflict is reported until it is fixed
+  glslTypes.report_conflicts(
+    shaders.map(({ filename }) => path.relative(process.cwd(), filename)),
+    generatorOptions
+  );
+
+  if (emitDependencyGraph) {
+    shaders.forEach(({ filename }) => updateDependencies(filename));
+    writeDependencyGraph();
+  }
+
+  if (options.emitIndex) writeIndex();
+
+  const failed = results.filter((result) => !result.succeeded);
+  const nameWidth = Math.max(
+    0,
+    ...results.map(({ name }) => name.length)
+  );
+
+  if (shouldLog("info")) {
+    process.stdout.write("\n");
+    for (const { name, succeeded, cached, duration } of results) {
+      process.stdout.write(succeeded ? chalk.green("  ok    ") : chalk.red("  fail  "));
+      process.stdout.write(chalk.blue(name.padEnd(nameWidth)));
+      process.stdout.write(
+        chalk.gray(cached ? "  (cached)\n" : `  ${duration.toFixed(2)}ms\n`)
+      );
+    }
+    process.stdout.write("\n");
+  }
+
+  const elapsed = (performance.now() - batchStart).toFixed(0);
+  const summary = `Generated ${results.length - failed.length} of ${results.length} ${
+    results.length === 1 ? "shader" : "shaders"
+  } in ${elapsed}ms.`;
+
+  if (failed.length === 0) {
+    if (shouldLog("info")) {
+      process.stdout.write(chalk.green("[OK]\t"));
+      process.stdout.write(`${summary}\n`);
+    }
+  } else {
+    process.stdout.write(chalk.red("[ERROR]\t"));
+    process.stdout.write(
+      `${summary} ${failed.length} ${failed.length === 1 ? "error" : "errors"} (${failed
+        .map(({ name }) => chalk.blue(name))
+        .join(", ")}).\n`
+    );
+  }
+
+  return failed.length;
+}
+
+if (options.watch) {
+  if (!Number.isInteger(options.debounceMs) || options.debounceMs < 0) {
+    console.error(`Invalid debounce duration ${options.debounceMs}`);
+    process.exit(1);
+  }
+
+  // `setInterval` runs a zero, negative or NaN interval every millisecond, rescanning the tree
+  if (!Number.isInteger(options.pollIntervalMs) || options.pollIntervalMs <= 0) {
+    console.error(`Invalid poll interval ${options.pollIntervalMs}`);
+    process.exit(1);
+  }
+
+  // Editors often write a file in several steps, so each file is only processed once it
+  // has stopped changing for the debounce duration
+  const pending = new Map<string, ReturnType<typeof setTimeout>>();
+  // Editors also save files that did not change, e.g. on focus out. Regenerating them would
+  // touch the output and retrigger downstream watchers, so the last content hash is kept
+  const fingerprints = new Map<string, string>();
+
+  // Shaders regenerated because a file they import changed, even if they did not
+  const forced = new Set<string>();
+
+  // Every shader's imports are known from the start, so a change to an imported file can
+  // regenerate the shaders using it. They are updated each time a shader is regenerated
+  for (const input of options.input) {
+    for (const filename of findShaders(input)) {
+      if (isSelected(path.relative(input, filename))) updateDependencies(filename);
+    }
+  }
+
+  if (emitDependencyGraph) writeDependencyGraph();
+
+  // Like `tsc --watch`, the shaders are generated once before waiting for changes, so a fresh
+  // checkout does not need a save of every shader
+  generateAll();
+
+  if (shouldLog("info")) {
+    process.stdout.write(chalk.green("Watching for changes\n"));
+  }
+
+  const warnUnprocessed = (name: string, error: unknown) => {
+    if (!shouldLog("warn")) return;
+
+    process.stdout.write(chalk.yellow("[WARN]\t"));
+    process.stdout.write(`Could not process ${chalk.blue(name)}: ${error}\n`);
+  };
+
+  const regenerate = (input: string, filename: string) => {
+    const force = forced.delete(filename);
+
+    if (!fs.existsSync(filename)) {
+      if (dependencies.delete(filename) && emitDependencyGraph) {
+        writeDependencyGraph();
+      }
+      return;
+    }
+
+    // Reading can still fail if the editor replaces the file right now, the next save
+    // triggers another event
+    let contents: Buffer;
+    try {
+      contents = fs.readFileSync(filename);
+    } catch (error) {
+      warnUnprocessed(path.relative(input, filename), error);
+      return;
+    }
+
+    const fingerprint = crypto.createHash("sha256").update(contents).digest("hex");
+
+    if (!force && fingerprints.get(filename) === fingerprint) {
+      if (shouldLog("debug")) {
+        process.stdout.write(chalk.gray("[DEBUG]\t"));
+        process.stdout.write(
+          chalk.gray(`Skipped unchanged ${path.relative(input, filename)}\n`)
+        );
+      }
+      return;
+    }
+
+    fingerprints.set(filename, fingerprint);
+    if (shouldLog("info")) {
+      console.log(force ? "Imported file change detected" : "File change detected");
+    }
+
+    const route = routeFor(path.relative(input, filename));
+    const start = performance.now();
+    let succeeded = false;
+    try {
+      succeeded = glslTypes.start_cli(
+        filename,
+        input,
+        route.output,
+        route.language,
+        generatorOptions
+      );
+    } catch (error) {
+      // An unexpected failure of one shader should not stop the watcher
+      warnUnprocessed(path.relative(input, filename), error);
+    }
+    const end = performance.now();
+
+    // A failed shader is retried on the next save even if it did not change
+    if (!succeeded) {
+      fingerprints.delete(filename);
+    }
+
+    updateDependencies(filename);
+    if (emitDependencyGraph) writeDependencyGraph();
+    if (succeeded && options.emitIndex) writeIndex();
+
+    if (shouldLog("info")) {
+      process.stdout.write(chalk.green("[INFO]\t"));
+      process.stdout.write(`File processed ${chalk.blue(path.relative(input, filename))}`);
+      process.stdout.write(chalk.gray(` (${(end - start).toFixed(2)}ms)\n`));
+    }
+  };
+
+  const schedule = (input: string, filename: string) => {
+    clearTimeout(pending.get(filename));
+    pending.set(
+      filename,
+      setTimeout(() => {
+        pending.delete(filename);
+        regenerate(input, filename);
+      }, options.debounceMs)
+    );
+  };
+
+  const watch = (
+    input: string,
+    listener: (eventType: string, filename: string | null) => void
+  ) =>
+    options.poll
+      ? pollDirectory(input, options.pollIntervalMs, listener)
+      : fs.watch(input, { recursive: true }, listener);
+
+  const watchers = options.input.map((input: string) =>
+    watch(input, (eventType, filename) => {
+      if (!filename) return;
+      filename = path.resolve(input, filename);
+
+      if (shouldLog("debug")) {
+        process.stdout.write(chalk.gray("[DEBUG]\t"));
+        process.stdout.write(
+          chalk.gray(`Watcher event: ${eventType} ${path.relative(input, filename)}\n`)
+        );
+      }
+
+      // Included files can have any extension, so every change invalidates the cached parse
+      glslTypes.invalidate_import_cache(filename);
+
+      for (const [shader, imports] of dependencies) {
+        if (shader !== filename && imports.includes(filename)) {
+          forced.add(shader);
+          schedule(inputRoot(shader), shader);
+        }
+      }
+
+      if (!SHADER_EXTENSIONS.includes(path.extname(filename))) return;
+      if (!isSelected(path.relative(input, filename))) return;
+
+      schedule(input, filename);
+    })
+  );
+
+  // Release the watch handles and drop pending regenerations so process supervisors see a
+  // clean exit
+  const shutdown = () => {
+    if (shouldLog("info")) process.stdout.write(chalk.green("Shutting down\n"));
+    for (const watcher of watchers) {
+      watcher.close();
+    }
+
+    for (const timeout of pending.values()) {
+      clearTimeout(timeout);
+    }
+
+    process.exit(0);
+  };
+
+  process.on("SIGINT", shutdown);
+  process.on("SIGTERM", shutdown);
+} else if (options.once) {
+  const failed = generateAll();
+
+  // The exit code is the number of failures, capped below the codes shells reserve
+  if (failed > 0) process.exit(Math.min(failed, 125));
 } else {
   if (!options.file) {
     console.error("Please provide a file to process");
@@ -69,5 +1197,21 @@ if (options.watch) {
     process.exit(1);
   }
 
-  glslTypes.start_cli(options.file, options.input, options.output);
+  const input = inputRoot(path.resolve(options.file));
+  const route = routeFor(path.relative(input, path.resolve(options.file)));
+
+  glslTypes.start_cli(
+    options.file,
+    input,
+    route.output,
+    route.language,
+    generatorOptions
+  );
+
+  if (emitDependencyGraph) {
+    updateDependencies(options.file);
+    writeDependencyGraph();
+  }
+
+  if (options.emitIndex) writeIndex();
 }
@@ -1,14 +0,0 @@
-/* tslint:disable */
-/* eslint-disable */
-/**
-* @param {string} file_path
-* @param {string} input_folder
-* @param {string} output_folder
-*/
-export function start_cli(file_path: string, input_folder: string, output_folder: string): void;
-/**
-* @param {string} file
-* @param {string} input_folder
-* @returns {string}
-*/
-export function resolve_imports(file: string, input_folder: string): string;
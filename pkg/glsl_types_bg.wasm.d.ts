@@ -1,9 +0,0 @@
-/* tslint:disable */
-/* eslint-disable */
-export const memory: WebAssembly.Memory;
-export function start_cli(a: number, b: number, c: number, d: number, e: number, f: number): void;
-export function resolve_imports(a: number, b: number, c: number, d: number, e: number): void;
-export function __wbindgen_free(a: number, b: number, c: number): void;
-export function __wbindgen_malloc(a: number, b: number): number;
-export function __wbindgen_realloc(a: number, b: number, c: number, d: number): number;
-export function __wbindgen_add_to_stack_pointer(a: number): number;
@@ -0,0 +1,70 @@
+//! Rust API for embedding the generator, e.g. in a build script.
+//!
+//! These functions work on shader sources that are already in memory, so `#include` directives
+//! are not resolved here. The `build` module resolves them before handing the source over.
+//! `find_conflicts` is the exception, it compares whole projects and reads the files itself.
+//! Warnings about unsupported types are logged like in the CLI.
+
+use crate::generator::{common, conflicts, rust, type_script};
+use crate::utils::source;
+use std::path::Path;
+
+pub use crate::config::{parse_config, Config};
+pub use crate::generator::common::{
+  ArraySize, BufferBlock, ExtensionBehavior, ExtensionDirective, GeneratorError, GlslType,
+  ImageFormat, Interpolation, ShaderData, StructField, Subroutine, TypedVariable, UniformBlock,
+};
+pub use crate::generator::conflicts::Conflict;
+pub use crate::import_resolver::import_resolver::{DefinitionErrorType, ImportError};
+pub use crate::options::Options;
+pub use crate::utils::get_shader_type::ShaderType;
+
+#[derive(Clone, Debug)]
+pub struct Shader {
+  /// File name without the extension, used to name the generated types.
+  pub name: String,
+  pub source: String,
+  pub data: ShaderData,
+}
+
+/// Parses `source`, using the extension of `file_path` to decide the shader stage.
+pub fn extract_shader(
+  file_path: &Path,
+  source: &str,
+  options: &Options,
+) -> Result<Shader, GeneratorError> {
+  let file_path = file_path.to_path_buf();
+  let shader_type = common::shader_type(&file_path, options)?;
+
+  // Extracted before stripping, the doc comments of the uniforms are comments too
+  let data = common::extract_shader_data(source, shader_type)?;
+
+  let source = if options.strip_comments {
+    source::strip_comments(source)
+  } else {
+    source.to_string()
+  };
+
+  let name = match file_path.file_stem().and_then(|name| name.to_str()) {
+    Some(name) => name.to_string(),
+    None => return Err(GeneratorError::UnsupportedShaderType(file_path.clone())),
+  };
+
+  return Ok(Shader { name, source, data });
+}
+
+/// Same output as `--language ts`.
+pub fn render_typescript(shader: &Shader, options: &Options) -> String {
+  return type_script::render_types_file(&shader.source, &shader.name, &shader.data, options);
+}
+
+/// Same output as `--language rs`.
+pub fn render_rust_types(shader: &Shader) -> String {
+  return rust::render_types_file(&shader.source, &shader.name, &shader.data);
+}
+
+/// Same checks as the end of a `--once` run, between the declarations of `files` and between the
+/// stages of every `<stem>.vert` and `<stem>.frag` pair among them.
+pub fn find_conflicts(files: &[String], options: &Options) -> Vec<Conflict> {
+  return conflicts::find_conflicts(files, options);
+}
@@ -0,0 +1,228 @@
+//! Generates the Rust types of a folder of shaders from a Cargo build script.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!   glsl_types::build::BuildConfig::new().input("shaders/").run().unwrap();
+//! }
+//!
+//! // src/lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/wave.rs"));
+//! ```
+//!
+//! Each shader is written to `OUT_DIR` with the same subfolder it has in its input folder, e.g.
+//! `shaders/terrain/grass.vert` becomes `$OUT_DIR/terrain/grass.rs`. `#include` directives are
+//! resolved like in the CLI, and a change to an included file reruns the script too.
+
+use crate::api::{self, GeneratorError, ImportError, Options};
+use crate::canonicalize;
+use crate::generator::common;
+use crate::import_resolver::import_resolver;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Default)]
+pub struct BuildConfig {
+  inputs: Vec<PathBuf>,
+  output_folder: Option<PathBuf>,
+  options: Options,
+}
+
+impl BuildConfig {
+  pub fn new() -> Self {
+    return Self::default();
+  }
+
+  /// Adds a folder whose shaders are generated, searched recursively.
+  pub fn input(mut self, folder: impl Into<PathBuf>) -> Self {
+    self.inputs.push(folder.into());
+    return self;
+  }
+
+  /// Writes the generated files here instead of `OUT_DIR`.
+  pub fn output_folder(mut self, folder: impl Into<PathBuf>) -> Self {
+    self.output_folder = Some(folder.into());
+    return self;
+  }
+
+  pub fn options(mut self, options: Options) -> Self {
+    self.options = options;
+    return self;
+  }
+
+  pub fn run(self) -> Result<(), BuildError> {
+    return run(self);
+  }
+}
+
+#[derive(Debug)]
+pub enum BuildError {
+  /// `OUT_DIR` is not set, which means the helper is not running in a build script.
+  MissingOutDir,
+  Io {
+    path: PathBuf,
+    error: std::io::Error,
+  },
+  /// An `#include` of the shader could not be resolved.
+  Import { path: PathBuf, error: ImportError },
+  Generator {
+    path: PathBuf,
+    error: GeneratorError,
+  },
+  /// Two shaders that would be written to the same file, e.g. `wave.vert` and `wave.frag`.
+  OutputCollision { first: PathBuf, second: PathBuf },
+}
+
+impl Display for BuildError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      BuildError::MissingOutDir => write!(f, "OUT_DIR is not set"),
+      BuildError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+      BuildError::Import { path, error } => write!(f, "{}: {:?}", path.display(), error),
+      BuildError::Generator { path, error } => write!(f, "{}: {:?}", path.display(), error),
+      BuildError::OutputCollision { first, second } => write!(
+        f,
+        "{} and {} generate the same output file",
+        first.display(),
+        second.display()
+      ),
+    }
+  }
+}
+
+impl std::error::Error for BuildError {}
+
+pub fn run(config: BuildConfig) -> Result<(), BuildError> {
+  let output_folder = match config.output_folder {
+    Some(output_folder) => output_folder,
+    None => match std::env::var_os("OUT_DIR") {
+      Some(out_dir) => PathBuf::from(out_dir),
+      None => return Err(BuildError::MissingOutDir),
+    },
+  };
+
+  // Output file -> shader, to catch shaders that would overwrite each other
+  let mut outputs: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+  for input in &config.inputs {
+    // Watching the folder reruns the script when a shader is added or removed
+    println!("cargo:rerun-if-changed={}", input.display());
+
+    for file_path in find_files(input)? {
+      // Files with other extensions, e.g. included `.glsl` files, are not shaders
+      if common::shader_type(&file_path, &config.options).is_err() {
+        continue;
+      }
+
+      println!("cargo:rerun-if-changed={}", file_path.display());
+
+      let resolved =
+        import_resolver::resolve_imports(&file_path).map_err(|error| BuildError::Import {
+          path: file_path.clone(),
+          error,
+        })?;
+
+      // The resolved paths are canonical, the shader itself is already watched
+      let shader_path = PathBuf::from(canonicalize(file_path.to_str().unwrap()));
+      let mut included = Vec::new();
+
+      for origin in resolved.lines.iter().flatten() {
+        if origin.file != shader_path && !included.contains(&origin.file) {
+          println!("cargo:rerun-if-changed={}", origin.file.display());
+          included.push(origin.file.clone());
+        }
+      }
+
+      let shader =
+        api::extract_shader(&file_path, &resolved.source, &config.options).map_err(|error| {
+          BuildError::Generator {
+            path: file_path.clone(),
+            error,
+          }
+        })?;
+
+      let subfolder = file_path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(input).ok())
+        .unwrap_or(Path::new(""));
+      let output_path = output_folder
+        .join(subfolder)
+        .join(format!("{}.rs", shader.name));
+
+      if let Some((_, first)) = outputs.iter().find(|(output, _)| *output == output_path) {
+        return Err(BuildError::OutputCollision {
+          first: first.clone(),
+          second: file_path,
+        });
+      }
+
+      let io_error = |error| BuildError::Io {
+        path: output_path.clone(),
+        error,
+      };
+
+      std::fs::create_dir_all(output_path.parent().unwrap()).map_err(io_error)?;
+      std::fs::write(&output_path, api::render_rust_types(&shader)).map_err(io_error)?;
+      outputs.push((output_path, file_path));
+    }
+  }
+
+  return Ok(());
+}
+
+// Sorted so the output does not depend on the order the file system lists entries in
+fn find_files(folder: &Path) -> Result<Vec<PathBuf>, BuildError> {
+  let io_error = |error| BuildError::Io {
+    path: folder.to_path_buf(),
+    error,
+  };
+
+  let mut files = Vec::new();
+  let mut entries = std::fs::read_dir(folder)
+    .map_err(io_error)?
+    .collect::<Result<Vec<std::fs::DirEntry>, std::io::Error>>()
+    .map_err(io_error)?;
+  entries.sort_by_key(|entry| entry.path());
+
+  for entry in entries {
+    let path = entry.path();
+
+    if path.is_dir() {
+      files.extend(find_files(&path)?);
+    } else {
+      files.push(path);
+    }
+  }
+
+  return Ok(files);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::temp_dir::TempDir;
+
+  #[test]
+  fn resolves_the_includes_of_the_shaders() {
+    let dir = TempDir::new("build");
+    dir.write(
+      "shaders/lib/lights.glsl",
+      "uniform vec3 lightColor;\nvec3 light() { return lightColor; }\n",
+    );
+    dir.write(
+      "shaders/wave.frag",
+      "#version 300 es\nprecision highp float;\n#include \"lib/lights.glsl\"\nuniform float time;\nout vec4 color;\nvoid main() {}\n",
+    );
+    let output = dir.path().join("out");
+
+    let result = BuildConfig::new()
+      .input(dir.path().join("shaders"))
+      .output_folder(&output)
+      .run();
+
+    assert!(result.is_ok());
+    let generated = std::fs::read_to_string(output.join("wave.rs")).unwrap();
+    assert!(generated.contains("  pub lightColor: [f32; 3],\n"));
+    assert!(generated.contains("  pub time: f32,\n"));
+  }
+}
@@ -1,32 +1,433 @@
-use crate::generator::type_script;
-use crate::{canonicalize, create_dir_all, file_exists};
+use crate::generator::common::{self, GeneratorError, ShaderSource};
+use crate::generator::{
+  c, conflicts, docs, json, python, rust, template, ts_dynamodb, ts_edgedb, ts_fauna, ts_grpc,
+  ts_kafka, ts_mongo, ts_mqtt, ts_neon, ts_pixi, ts_redis_json, ts_shared_arraybuffer,
+  ts_socket_io, ts_wasm_memory, ts_webgpu_buffer, ts_webgpu_layout, ts_webrtc_datachannel,
+  ts_websocket, ts_xata, type_script, unused, wgsl,
+};
 use crate::import_resolver;
+use crate::options::Options;
+use crate::utils::json::Json;
+use crate::utils::log::{self, print_diagnostic, Level, Segment};
+use crate::utils::source;
+use crate::utils::source_map;
+use crate::{canonicalize, create_dir_all, file_exists, now, write_file};
 use std::path::PathBuf;
 
-pub fn generate(file_path: String, input_folder: String, output_folder: String) {
+pub fn generate(
+  file_path: String,
+  input_folder: String,
+  output_folder: String,
+  language: String,
+  options: &Options,
+) -> bool {
+  log::set_json_diagnostics(options.json_errors);
+  log::set_verbosity(options.verbosity);
+
   if !file_exists(&input_folder) {
     create_dir_all(&input_folder);
   }
 
-  if !file_exists(&output_folder) {
-    create_dir_all(&output_folder);
-  }
-
   let file_path = PathBuf::from(file_path);
   let input_folder = PathBuf::from(input_folder);
-  let output_folder = PathBuf::from(output_folder);
 
   // Update the file path to be relative to the input folder
-  let input_folder_canon = PathBuf::from(canonicalize(&input_folder.to_str().unwrap()));
+  let input_folder_canon = PathBuf::from(canonicalize(input_folder.to_str().unwrap()));
   let input_folder_parent = &input_folder_canon.parent().unwrap().to_path_buf();
 
-  let combined_vertex = if let Some(output) =
+  let start = log::enabled(Level::DEBUG).then(now);
+
+  let (combined_vertex, source_lines) = if let Some(resolved) =
     import_resolver::import_resolver::try_resolve_imports(&file_path, input_folder_parent)
   {
-    output
+    (resolved.source, resolved.lines)
+  } else {
+    return false;
+  };
+
+  if let Some(start) = start {
+    let file = file_path
+      .strip_prefix(input_folder_parent)
+      .unwrap_or(&file_path)
+      .to_str()
+      .unwrap();
+    let duration = now() - start;
+
+    print_diagnostic(
+      Level::DEBUG,
+      "IMPORTS_RESOLVED",
+      &[
+        Segment::Text("Resolved the imports of "),
+        Segment::Highlight(file),
+        Segment::Text(&format!(" in {:.2}ms", duration)),
+      ],
+      vec![
+        ("file", Json::string(file)),
+        ("durationMs", Json::Number(duration)),
+      ],
+    );
+  }
+
+  // The shader data is extracted from the source as written, the doc comments of the uniforms
+  // are lost once comments are stripped or the source is minified
+  let original_source = combined_vertex.clone();
+
+  // Stripping comments keeps every line where it was, so only minifying changes `source_lines`
+  let combined_vertex = if options.strip_comments {
+    source::strip_comments(&combined_vertex)
+  } else {
+    combined_vertex
+  };
+
+  let (combined_vertex, source_lines) = if options.minify_source {
+    let (minified, kept_lines) = source::minify(&combined_vertex);
+    let file = file_path
+      .strip_prefix(input_folder_parent)
+      .unwrap_or(&file_path)
+      .to_str()
+      .unwrap();
+
+    print_diagnostic(
+      Level::INFO,
+      "MINIFIED_SOURCE",
+      &[
+        Segment::Text("Minified "),
+        Segment::Highlight(file),
+        Segment::Text(&format!(
+          " from {} to {} bytes",
+          combined_vertex.len(),
+          minified.len()
+        )),
+      ],
+      vec![
+        ("file", Json::string(file)),
+        ("originalBytes", Json::Number(combined_vertex.len() as f64)),
+        ("minifiedBytes", Json::Number(minified.len() as f64)),
+      ],
+    );
+
+    let source_lines = kept_lines
+      .iter()
+      .map(|line| source_lines.get(*line).cloned().flatten())
+      .collect();
+
+    (minified, source_lines)
   } else {
-    return;
+    (combined_vertex, source_lines)
+  };
+
+  if options.warn_unused {
+    // An unsupported extension is reported by the backend
+    if let Ok(shader_type) = common::shader_type(&file_path, options) {
+      let file = file_path
+        .strip_prefix(input_folder_parent)
+        .unwrap_or(&file_path)
+        .to_str()
+        .unwrap();
+
+      for (kind, name) in unused::unused_variables(&combined_vertex, shader_type) {
+        print_diagnostic(
+          Level::WARN,
+          "UNUSED_VARIABLE",
+          &[
+            Segment::Text(&format!("The {} ", kind)),
+            Segment::Highlight(&name),
+            Segment::Text(" is declared but never used in "),
+            Segment::Highlight(file),
+          ],
+          vec![
+            ("file", Json::string(file)),
+            ("kind", Json::string(kind)),
+            ("name", Json::string(&name)),
+          ],
+        );
+      }
+    }
+  }
+
+  // A template replaces the backend of every language
+  let languages = match options.template {
+    Some(_) => vec!["template".to_string()],
+    None => language
+      .split(',')
+      .map(|language| language.trim().to_string())
+      .filter(|language| !language.is_empty())
+      .collect::<Vec<String>>(),
+  };
+
+  let shader_source = ShaderSource {
+    original: original_source,
+    embedded: combined_vertex,
+  };
+
+  let mut succeeded = true;
+
+  // e.g. `--language ts,rs`, every language is generated even when another one fails
+  for language in languages {
+    let output_folder = match options.language_output_folders.get(&language) {
+      Some(output_folder) => output_folder.clone(),
+      None => output_folder.clone(),
+    };
+    let output_folder =
+      shader_output_folder(&file_path, &input_folder_canon, output_folder, options);
+
+    let result = generate_language(
+      &language,
+      &shader_source,
+      &file_path,
+      &output_folder,
+      options,
+    );
+
+    if result.is_ok() && options.source_map && language == "ts" {
+      write_source_map(&file_path, &output_folder, &source_lines);
+    }
+
+    if result.is_ok() && options.emit_docs && language == "ts" {
+      write_docs(&shader_source.original, &file_path, &output_folder, options);
+    }
+
+    if let Err(err) = result {
+      print_error(&err, &file_path, input_folder_parent);
+      succeeded = false;
+    }
+  }
+
+  return succeeded;
+}
+
+// e.g. `shaders/terrain/grass.vert` is written to `output/terrain/grass.ts` when the structure
+// is mirrored
+fn shader_output_folder(
+  file_path: &PathBuf,
+  input_folder_canon: &PathBuf,
+  output_folder: String,
+  options: &Options,
+) -> PathBuf {
+  if !file_exists(&output_folder) {
+    create_dir_all(&output_folder);
+  }
+
+  let output_folder = PathBuf::from(output_folder);
+
+  if !options.mirror_structure {
+    return output_folder;
+  }
+
+  let file_folder = PathBuf::from(canonicalize(file_path.to_str().unwrap()))
+    .parent()
+    .unwrap()
+    .to_path_buf();
+
+  match file_folder.strip_prefix(input_folder_canon) {
+    Ok(subfolder) if !subfolder.as_os_str().is_empty() => {
+      let output_folder = output_folder.join(subfolder);
+
+      if !file_exists(output_folder.to_str().unwrap()) {
+        create_dir_all(output_folder.to_str().unwrap());
+      }
+
+      return output_folder;
+    }
+    _ => return output_folder,
+  }
+}
+
+fn generate_language(
+  language: &str,
+  source: &ShaderSource,
+  file_path: &PathBuf,
+  output_folder: &PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  return match language {
+    "ts" => type_script::generate_types_file(source, file_path, output_folder, options),
+    "ts-neon" => ts_neon::generate_types_file(source, file_path, output_folder, options),
+    "ts-edgedb" => ts_edgedb::generate_types_file(source, file_path, output_folder, options),
+    "ts-xata" => ts_xata::generate_types_file(source, file_path, output_folder, options),
+    "ts-fauna" => ts_fauna::generate_types_file(source, file_path, output_folder, options),
+    "ts-mongo" => ts_mongo::generate_types_file(source, file_path, output_folder, options),
+    "ts-dynamodb" => ts_dynamodb::generate_types_file(source, file_path, output_folder, options),
+    "ts-redis-json" => {
+      ts_redis_json::generate_types_file(source, file_path, output_folder, options)
+    }
+    "ts-kafka" => ts_kafka::generate_types_file(source, file_path, output_folder, options),
+    "ts-mqtt" => ts_mqtt::generate_types_file(source, file_path, output_folder, options),
+    "ts-websocket" => ts_websocket::generate_types_file(source, file_path, output_folder, options),
+    "ts-grpc" => ts_grpc::generate_types_file(source, file_path, output_folder, options),
+    "ts-socket-io" => ts_socket_io::generate_types_file(source, file_path, output_folder, options),
+    "ts-webrtc-datachannel" => {
+      ts_webrtc_datachannel::generate_types_file(source, file_path, output_folder, options)
+    }
+    "ts-shared-arraybuffer" => {
+      ts_shared_arraybuffer::generate_types_file(source, file_path, output_folder, options)
+    }
+    "ts-wasm-memory" => {
+      ts_wasm_memory::generate_types_file(source, file_path, output_folder, options)
+    }
+    "ts-webgpu-buffer" => {
+      ts_webgpu_buffer::generate_types_file(source, file_path, output_folder, options)
+    }
+    "ts-pixi" => ts_pixi::generate_types_file(source, file_path, output_folder, options),
+    "rs" => rust::generate_types_file(source, file_path, output_folder, options),
+    "json" => json::generate_types_file(source, file_path, output_folder, options),
+    "ts-webgpu-layout" => {
+      ts_webgpu_layout::generate_types_file(source, file_path, output_folder, options)
+    }
+    "py" => python::generate_types_file(source, file_path, output_folder, options),
+    "c" => c::generate_types_file(source, file_path, output_folder, options),
+    "template" => template::generate_types_file(source, file_path, output_folder, options),
+    "wgsl" => wgsl::generate_types_file(source, file_path, output_folder, options),
+    _ => Err(GeneratorError::UnknownLanguage(language.to_string())),
   };
+}
+
+/// Warns about every vertex attribute that `files` declare with different types, which breaks
+/// sharing a vertex array object between their programs, and about every extension they give
+/// different `#extension` behaviors. Shaders that fail to resolve or parse are skipped, their
+/// errors are reported when they are generated.
+pub fn report_conflicts(files: Vec<String>, options: &Options) {
+  log::set_json_diagnostics(options.json_errors);
+  log::set_verbosity(options.verbosity);
+
+  conflicts::report_conflicts(&files, options);
+}
+
+// Written as `<name>.glsl.map` next to `<name>.ts`, with the sources relative to the map
+fn write_source_map(
+  file_path: &PathBuf,
+  output_folder: &PathBuf,
+  source_lines: &[Option<import_resolver::import_resolver::SourceLine>],
+) {
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_folder_canon = PathBuf::from(canonicalize(output_folder.to_str().unwrap()));
+
+  let lines = source_lines
+    .iter()
+    .map(|origin| {
+      origin.as_ref().map(|origin| {
+        (
+          source_map::relative_path(&output_folder_canon, &origin.file),
+          origin.line,
+        )
+      })
+    })
+    .collect::<Vec<Option<(String, usize)>>>();
+
+  let map = source_map::line_source_map(&format!("{}.glsl", output_file_name), &lines);
+  let map_path = output_folder.join(format!("{}.glsl.map", output_file_name));
+  write_file(map_path.to_str().unwrap(), &map.to_pretty_string());
+}
 
-  type_script::generate_types_file(combined_vertex, &file_path, &output_folder);
+// Written as `<name>.md` next to `<name>.ts`
+fn write_docs(source: &String, file_path: &PathBuf, output_folder: &PathBuf, options: &Options) {
+  // The backend already parsed the same shader, so these do not fail
+  let shader_data = match common::shader_type(file_path, options)
+    .and_then(|shader_type| common::extract_shader_data(source, shader_type))
+  {
+    Ok(shader_data) => shader_data,
+    Err(_) => return,
+  };
+
+  let file_name = file_path.file_name().unwrap().to_str().unwrap();
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let docs = docs::render_docs_file(file_name, &shader_data);
+  let docs_path = output_folder.join(format!("{}.md", output_file_name));
+  write_file(docs_path.to_str().unwrap(), &docs);
+}
+
+fn print_error(err: &GeneratorError, file_path: &PathBuf, input_folder_parent: &PathBuf) {
+  let file_path = file_path
+    .strip_prefix(input_folder_parent)
+    .unwrap_or(file_path);
+  let file = file_path.to_str().unwrap();
+
+  match err {
+    GeneratorError::UnsupportedShaderType(shader_path) => {
+      let shader_path = shader_path
+        .strip_prefix(input_folder_parent)
+        .unwrap_or(shader_path);
+
+      print_diagnostic(
+        Level::ERROR,
+        "UNSUPPORTED_SHADER_TYPE",
+        &[
+          Segment::Text("Unsupported shader extension: "),
+          Segment::Highlight(shader_path.to_str().unwrap()),
+        ],
+        vec![("file", Json::string(shader_path.to_str().unwrap()))],
+      );
+    }
+    GeneratorError::CouldNotParseShader(message) => print_diagnostic(
+      Level::ERROR,
+      "PARSE_ERROR",
+      &[
+        Segment::Text("Could not parse shader "),
+        Segment::Highlight(file),
+        Segment::Text(&format!(": {}", message)),
+      ],
+      vec![("file", Json::string(file))],
+    ),
+    GeneratorError::UnsupportedShaderStage(shader_type) => print_diagnostic(
+      Level::ERROR,
+      "UNSUPPORTED_SHADER_STAGE",
+      &[
+        Segment::Text(&format!(
+          "{:?} shaders are not supported by this language: ",
+          shader_type
+        )),
+        Segment::Highlight(file),
+      ],
+      vec![("file", Json::string(file))],
+    ),
+    GeneratorError::UniformArraySizeMismatch {
+      name,
+      first,
+      second,
+    } => print_diagnostic(
+      Level::ERROR,
+      "UNIFORM_ARRAY_SIZE_MISMATCH",
+      &[
+        Segment::Text("The uniform "),
+        Segment::Highlight(name),
+        Segment::Text(&format!(
+          " is declared with different array sizes ({} and {}) in ",
+          common::array_size_label(first),
+          common::array_size_label(second)
+        )),
+        Segment::Highlight(file),
+      ],
+      vec![
+        ("file", Json::string(file)),
+        ("name", Json::string(name)),
+        ("first", Json::string(&common::array_size_label(first))),
+        ("second", Json::string(&common::array_size_label(second))),
+      ],
+    ),
+    GeneratorError::UnknownLanguage(language) => print_diagnostic(
+      Level::ERROR,
+      "UNKNOWN_LANGUAGE",
+      &[
+        Segment::Text("Unknown language: "),
+        Segment::Highlight(language),
+      ],
+      vec![
+        ("file", Json::string(file)),
+        ("language", Json::string(language)),
+      ],
+    ),
+    GeneratorError::InvalidTemplate(message) => print_diagnostic(
+      Level::ERROR,
+      "INVALID_TEMPLATE",
+      &[
+        Segment::Text("Invalid template for "),
+        Segment::Highlight(file),
+        Segment::Text(&format!(": {}", message)),
+      ],
+      vec![
+        ("file", Json::string(file)),
+        ("message", Json::string(message)),
+      ],
+    ),
+  }
 }
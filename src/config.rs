@@ -0,0 +1,444 @@
+use crate::options::Options;
+use wasm_bindgen::prelude::*;
+
+/// Contents of `.glsltypes.toml`. The CLI flags take precedence over these values.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+  input_folder: Option<String>,
+  output_folder: Option<String>,
+  language: Option<String>,
+  debounce_ms: Option<u32>,
+  routes: Vec<Route>,
+  options: Options,
+}
+
+/// A `[[routes]]` entry: shaders matching `pattern` are generated with this language and
+/// output folder instead of the global ones.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct Route {
+  pattern: String,
+  language: Option<String>,
+  output_folder: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Route {
+  /// Glob matched against the shader path relative to its input folder.
+  #[wasm_bindgen(getter)]
+  pub fn pattern(&self) -> String {
+    return self.pattern.clone();
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn language(&self) -> Option<String> {
+    return self.language.clone();
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn output_folder(&self) -> Option<String> {
+    return self.output_folder.clone();
+  }
+}
+
+#[wasm_bindgen]
+impl Config {
+  #[wasm_bindgen(getter)]
+  pub fn input_folder(&self) -> Option<String> {
+    return self.input_folder.clone();
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn output_folder(&self) -> Option<String> {
+    return self.output_folder.clone();
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn language(&self) -> Option<String> {
+    return self.language.clone();
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn debounce_ms(&self) -> Option<u32> {
+    return self.debounce_ms;
+  }
+
+  /// Routes in the order they are declared, the first matching one applies.
+  #[wasm_bindgen(getter)]
+  pub fn routes(&self) -> Vec<Route> {
+    return self.routes.clone();
+  }
+
+  pub fn options(&self) -> Options {
+    return self.options.clone();
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+  String(String),
+  Bool(bool),
+  Integer(u32),
+}
+
+/// Parses the subset of TOML used by the config file: `key = value` pairs with string,
+/// boolean and non-negative integer values, `[table]` and `[[routes]]` headers and `#`
+/// comments.
+#[wasm_bindgen]
+pub fn parse_config(content: &str) -> Result<Config, String> {
+  let mut config = Config::default();
+  let mut table = String::new();
+  // Line of the last `[[routes]]` header, to report a route without a pattern
+  let mut route_line = 0;
+
+  for (index, line) in content.lines().enumerate() {
+    let line_number = index + 1;
+    let line = strip_comment(line).trim();
+
+    if line.is_empty() {
+      continue;
+    }
+
+    if let Some(name) = line
+      .strip_prefix("[[")
+      .and_then(|line| line.strip_suffix("]]"))
+    {
+      table = name.trim().to_string();
+
+      if table != "routes" {
+        return Err(format!(
+          "line {}: unknown array of tables [[{}]]",
+          line_number, table
+        ));
+      }
+
+      check_route(&config).map_err(|err| format!("line {}: {}", route_line, err))?;
+      config.routes.push(Route::default());
+      route_line = line_number;
+      continue;
+    }
+
+    if let Some(name) = line
+      .strip_prefix('[')
+      .and_then(|line| line.strip_suffix(']'))
+    {
+      table = name.trim().to_string();
+
+      if table != "type_overrides" && table != "extensions" && table != "outputs" {
+        return Err(format!("line {}: unknown table [{}]", line_number, table));
+      }
+
+      continue;
+    }
+
+    let (key, value) = match line.split_once('=') {
+      Some((key, value)) => (unquote_key(key.trim()), value.trim()),
+      None => return Err(format!("line {}: expected `key = value`", line_number)),
+    };
+
+    let value = parse_value(value).map_err(|err| format!("line {}: {}", line_number, err))?;
+
+    let result = match table.as_str() {
+      "type_overrides" => match value {
+        Value::String(label) => {
+          config.options.set_type_override(key.clone(), label);
+          Ok(())
+        }
+        _ => Err("type overrides must be strings".to_string()),
+      },
+      "outputs" => match value {
+        Value::String(output_folder) => {
+          config
+            .options
+            .set_language_output_folder(key.clone(), output_folder);
+          Ok(())
+        }
+        _ => Err("outputs must map a language to a folder".to_string()),
+      },
+      "extensions" => match value {
+        Value::String(stage) => {
+          if config.options.set_extension(key.clone(), stage.clone()) {
+            Ok(())
+          } else {
+            Err(format!("unknown shader stage \"{}\"", stage))
+          }
+        }
+        _ => Err("extensions must map to a shader stage".to_string()),
+      },
+      "routes" => set_route_key(config.routes.last_mut().unwrap(), &key, value),
+      _ => set_top_level_key(&mut config, &key, value),
+    };
+
+    result.map_err(|err| format!("line {}: {}", line_number, err))?;
+  }
+
+  check_route(&config).map_err(|err| format!("line {}: {}", route_line, err))?;
+
+  return Ok(config);
+}
+
+fn set_route_key(route: &mut Route, key: &str, value: Value) -> Result<(), String> {
+  match (key, value) {
+    ("pattern", Value::String(value)) => route.pattern = value,
+    ("language", Value::String(value)) => route.language = Some(value),
+    ("output_folder", Value::String(value)) => route.output_folder = Some(value),
+    ("pattern" | "language" | "output_folder", _) => {
+      return Err(format!("invalid value for `{}`", key))
+    }
+    _ => return Err(format!("unknown route key `{}`", key)),
+  }
+
+  return Ok(());
+}
+
+// The last route has to be complete before the next one starts
+fn check_route(config: &Config) -> Result<(), String> {
+  match config.routes.last() {
+    Some(route) if route.pattern.is_empty() => return Err("route without a `pattern`".to_string()),
+    _ => return Ok(()),
+  }
+}
+
+fn set_top_level_key(config: &mut Config, key: &str, value: Value) -> Result<(), String> {
+  match (key, value) {
+    ("input_folder", Value::String(value)) => config.input_folder = Some(value),
+    ("output_folder", Value::String(value)) => config.output_folder = Some(value),
+    ("language", Value::String(value)) => config.language = Some(value),
+    ("strip_comments_from_source", Value::Bool(value)) => config.options.set_strip_comments(value),
+    ("debounce_ms", Value::Integer(value)) => config.debounce_ms = Some(value),
+    ("mirror_structure", Value::Bool(value)) => config.options.set_mirror_structure(value),
+    ("naming_convention", Value::String(value)) => {
+      if !config.options.set_naming_convention(value.clone()) {
+        return Err(format!("unknown naming convention \"{}\"", value));
+      }
+    }
+    ("output_extension", Value::String(value)) => {
+      if !config.options.set_output_extension(value.clone()) {
+        return Err(format!("unsupported output extension \"{}\"", value));
+      }
+    }
+    ("export_prefix", Value::String(value)) => {
+      if !config.options.set_export_prefix(value.clone()) {
+        return Err(format!("invalid export prefix \"{}\"", value));
+      }
+    }
+    (
+      "input_folder"
+      | "output_folder"
+      | "language"
+      | "strip_comments_from_source"
+      | "debounce_ms"
+      | "mirror_structure"
+      | "naming_convention"
+      | "output_extension"
+      | "export_prefix",
+      _,
+    ) => return Err(format!("invalid value for `{}`", key)),
+    _ => return Err(format!("unknown key `{}`", key)),
+  }
+
+  return Ok(());
+}
+
+fn parse_value(value: &str) -> Result<Value, String> {
+  if value == "true" {
+    return Ok(Value::Bool(true));
+  }
+
+  if value == "false" {
+    return Ok(Value::Bool(false));
+  }
+
+  if value.starts_with('"') {
+    return parse_string(value);
+  }
+
+  if let Ok(value) = value.parse::<u32>() {
+    return Ok(Value::Integer(value));
+  }
+
+  return Err(format!("unsupported value `{}`", value));
+}
+
+fn parse_string(value: &str) -> Result<Value, String> {
+  let mut output = String::new();
+  let mut chars = value.chars().skip(1);
+
+  while let Some(c) = chars.next() {
+    match c {
+      '"' => {
+        if chars.next().is_some() {
+          return Err(format!("unexpected characters after string {}", value));
+        }
+
+        return Ok(Value::String(output));
+      }
+      '\\' => match chars.next() {
+        Some('n') => output.push('\n'),
+        Some('t') => output.push('\t'),
+        Some('"') => output.push('"'),
+        Some('\\') => output.push('\\'),
+        _ => return Err(format!("invalid escape in {}", value)),
+      },
+      c => output.push(c),
+    }
+  }
+
+  return Err(format!("unterminated string {}", value));
+}
+
+fn strip_comment(line: &str) -> &str {
+  let mut in_string = false;
+  let mut escaped = false;
+
+  for (i, c) in line.char_indices() {
+    match c {
+      '\\' if in_string && !escaped => {
+        escaped = true;
+        continue;
+      }
+      '"' if !escaped => in_string = !in_string,
+      '#' if !in_string => return &line[..i],
+      _ => {}
+    }
+
+    escaped = false;
+  }
+
+  return line;
+}
+
+// Keys can be quoted, e.g. `"my-type" = "MyType"`
+fn unquote_key(key: &str) -> String {
+  match key.strip_prefix('"').and_then(|key| key.strip_suffix('"')) {
+    Some(key) => key.to_string(),
+    None => key.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::get_shader_type::ShaderType;
+
+  #[test]
+  fn parses_the_top_level_keys() {
+    let config = parse_config(
+      "input_folder = \"shaders\"\noutput_folder = \"src/generated\"\nlanguage = \"ts\"\ndebounce_ms = 100\nstrip_comments_from_source = true\n",
+    )
+    .unwrap();
+
+    assert_eq!(config.input_folder().as_deref(), Some("shaders"));
+    assert_eq!(config.output_folder().as_deref(), Some("src/generated"));
+    assert_eq!(config.language().as_deref(), Some("ts"));
+    assert_eq!(config.debounce_ms(), Some(100));
+    assert!(config.options().strip_comments);
+  }
+
+  #[test]
+  fn parses_every_table() {
+    let config = parse_config(
+      "[type_overrides]\nvec3 = \"Vector3\"\n\n[extensions]\nvs = \"vertex\"\n\n[outputs]\nrs = \"src/shaders\"\n\n[[routes]]\npattern = \"post/**\"\nlanguage = \"wgsl\"\n\n[[routes]]\npattern = \"ui/*.frag\"\noutput_folder = \"ui\"\n",
+    )
+    .unwrap();
+    let options = config.options();
+
+    assert_eq!(
+      options.type_overrides.get("vec3").map(String::as_str),
+      Some("Vector3")
+    );
+    assert_eq!(options.extensions.get("vs"), Some(&ShaderType::Vertex));
+    assert_eq!(
+      options
+        .language_output_folders
+        .get("rs")
+        .map(String::as_str),
+      Some("src/shaders")
+    );
+
+    let routes = config.routes();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].pattern(), "post/**");
+    assert_eq!(routes[0].language().as_deref(), Some("wgsl"));
+    assert_eq!(routes[0].output_folder(), None);
+    assert_eq!(routes[1].pattern(), "ui/*.frag");
+    assert_eq!(routes[1].output_folder().as_deref(), Some("ui"));
+  }
+
+  #[test]
+  fn parses_quoted_keys() {
+    let config = parse_config("[type_overrides]\n\"my-struct\" = \"MyStruct\"\n").unwrap();
+
+    assert_eq!(
+      config
+        .options()
+        .type_overrides
+        .get("my-struct")
+        .map(String::as_str),
+      Some("MyStruct")
+    );
+  }
+
+  #[test]
+  fn keeps_hashes_inside_strings() {
+    let config = parse_config(
+      "# Shared settings\noutput_folder = \"out#1\" # not part of the value\n[type_overrides]\nvec4 = \"Color \\\"#rgba\\\"\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(config.output_folder().as_deref(), Some("out#1"));
+    assert_eq!(
+      config
+        .options()
+        .type_overrides
+        .get("vec4")
+        .map(String::as_str),
+      Some("Color \"#rgba\"")
+    );
+  }
+
+  #[test]
+  fn errors_point_at_the_line() {
+    let error = |content: &str| parse_config(content).unwrap_err();
+
+    assert_eq!(
+      error("language = \"ts\"\n\n[colors]\n"),
+      "line 3: unknown table [colors]"
+    );
+    assert_eq!(
+      error("[[shaders]]\n"),
+      "line 1: unknown array of tables [[shaders]]"
+    );
+    assert_eq!(
+      error("language = \"ts\"\noutput_folder\n"),
+      "line 2: expected `key = value`"
+    );
+    assert_eq!(error("colour = true\n"), "line 1: unknown key `colour`");
+    assert_eq!(
+      error("debounce_ms = \"50\"\n"),
+      "line 1: invalid value for `debounce_ms`"
+    );
+    assert_eq!(
+      error("language = \"ts\n"),
+      "line 1: unterminated string \"ts"
+    );
+    assert_eq!(
+      error("[extensions]\nvs = \"vertices\"\n"),
+      "line 2: unknown shader stage \"vertices\""
+    );
+    assert_eq!(
+      error("[type_overrides]\nvec3 = 3\n"),
+      "line 2: type overrides must be strings"
+    );
+    // A route is checked when the next one starts, and reported at its header
+    assert_eq!(
+      error("[[routes]]\nlanguage = \"rs\"\n\n[[routes]]\npattern = \"*\"\n"),
+      "line 1: route without a `pattern`"
+    );
+    assert_eq!(
+      error("language = \"ts\"\n[[routes]]\nlanguage = \"rs\"\n"),
+      "line 2: route without a `pattern`"
+    );
+  }
+}
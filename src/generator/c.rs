@@ -0,0 +1,222 @@
+use super::common::{self, ArraySize, GeneratorError, GlslType, ScalarKind, ShaderData};
+use crate::options::Options;
+use crate::utils::get_shader_type::ShaderType;
+use crate::write_file;
+use glsl::syntax::TypeSpecifierNonArray;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_file = render_header_file(&source.embedded, output_file_name, &shader_data);
+
+  let output_file_path = output_folder.join(format!("{}.h", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+/// Contents of the generated `.h` file for a shader named `output_file_name`.
+pub fn render_header_file(
+  content: &str,
+  output_file_name: &str,
+  shader_data: &ShaderData,
+) -> String {
+  let type_name = common::to_pascal_case(output_file_name);
+  let snake_case_name = common::to_snake_case(output_file_name)
+    .trim_matches('_')
+    .to_string();
+  let macro_name = snake_case_name.to_uppercase();
+
+  let stage = match shader_data.shader_type {
+    ShaderType::Vertex => "VERTEX",
+    ShaderType::Fragment => "FRAGMENT",
+    ShaderType::Geometry => "GEOMETRY",
+    ShaderType::Compute => "COMPUTE",
+    ShaderType::TessControl => "TESS_CONTROL",
+    ShaderType::TessEvaluation => "TESS_EVALUATION",
+  };
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str(&format!("#ifndef {0}_H\n#define {0}_H\n\n", macro_name));
+  output_file.push_str(
+    "// GLint comes from the OpenGL loader (glad, GLEW...) included before this header\n\n",
+  );
+
+  output_file.push_str(&format!(
+    "#define {}_{}_SOURCE \\\n{}\n",
+    macro_name,
+    stage,
+    string_literal(content)
+  ));
+
+  let mut structs = Vec::new();
+  let mut fields = String::new();
+  let mut uniform_names = Vec::new();
+
+  for uniform in &shader_data.uniforms {
+    let ty = shader_data.resolve_variable_type(uniform);
+
+    if let Some(field) = c_field(&type_name, &uniform.identifier, &ty, &mut structs) {
+      if let Some(doc_comment) = &uniform.doc_comment {
+        fields.push_str(&format!("  // {}\n", doc_comment));
+      }
+
+      fields.push_str(&format!("  {};\n", field));
+      uniform_names.extend(location_names(&uniform.identifier, &ty));
+    }
+  }
+
+  for definition in structs {
+    output_file.push('\n');
+    output_file.push_str(&definition);
+  }
+
+  output_file.push_str(&format!(
+    "\nstruct {}Uniforms {{\n{}}};\n",
+    type_name, fields
+  ));
+
+  // A zero sized array is not valid C
+  if !uniform_names.is_empty() {
+    let names = uniform_names
+      .iter()
+      .map(|name| format!("  \"{}\",\n", name))
+      .collect::<String>();
+
+    output_file.push_str(&format!(
+      r#"
+// Names to pass to glGetUniformLocation, in the order of the locations array
+#define {0}_UNIFORM_COUNT {2}
+static const char *const {1}_uniform_names[{0}_UNIFORM_COUNT] = {{
+{3}}};
+extern GLint {1}_uniform_locations[{0}_UNIFORM_COUNT];
+"#,
+      macro_name,
+      snake_case_name,
+      uniform_names.len(),
+      names
+    ));
+  }
+
+  output_file.push_str(&format!("\n#endif // {}_H\n", macro_name));
+
+  return output_file;
+}
+
+/// The source as one string literal per line, joined with line continuations.
+fn string_literal(content: &str) -> String {
+  return content
+    .lines()
+    .map(|line| {
+      format!(
+        "  \"{}\\n\"",
+        line.replace('\\', "\\\\").replace('"', "\\\"")
+      )
+    })
+    .collect::<Vec<String>>()
+    .join(" \\\n");
+}
+
+/// C declaration of a field, e.g. `float position[3]`, `None` when the type has no C equivalent.
+/// Struct types are rendered into `structs` first so they are declared before being used.
+fn c_field(
+  owner: &str,
+  identifier: &str,
+  ty: &GlslType,
+  structs: &mut Vec<String>,
+) -> Option<String> {
+  let (base, dimensions) = c_type(owner, identifier, ty, structs)?;
+  return Some(format!("{} {}{}", base, identifier, dimensions));
+}
+
+// Element type and array dimensions, which C puts after the name
+fn c_type(
+  owner: &str,
+  identifier: &str,
+  ty: &GlslType,
+  structs: &mut Vec<String>,
+) -> Option<(String, String)> {
+  let declaration = match ty {
+    GlslType::Struct {
+      name: struct_name,
+      fields: struct_fields,
+    } => {
+      // C has a single struct namespace, so the names carry the shader name
+      let struct_name = match struct_name {
+        Some(struct_name) => format!("{}{}", owner, common::capitalize_first_letter(struct_name)),
+        None => format!("{}{}", owner, common::capitalize_first_letter(identifier)),
+      };
+
+      let fields = struct_fields
+        .iter()
+        .filter_map(|field| c_field(owner, &field.identifier, &field.ty, structs))
+        .map(|field| format!("  {};\n", field))
+        .collect::<String>();
+
+      let definition = format!("struct {} {{\n{}}};\n", struct_name, fields);
+
+      if !structs.contains(&definition) {
+        structs.push(definition);
+      }
+
+      (format!("struct {}", struct_name), String::new())
+    }
+    GlslType::Array(element, ArraySize::Sized(size)) => {
+      let (base, dimensions) = c_type(owner, identifier, element, structs)?;
+      (base, format!("[{}]{}", size, dimensions))
+    }
+    // Runtime-sized arrays only exist in buffer blocks
+    GlslType::Array(_, ArraySize::Unsized) => return None,
+    GlslType::Opaque(ty) if common::double_component_count(ty).is_some() => value_type(ty)?,
+    // Samplers are set to the index of a texture unit
+    GlslType::Opaque(_) => ("int".to_string(), String::new()),
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) => value_type(ty)?,
+    GlslType::Unknown(_) => return None,
+  };
+
+  return Some(declaration);
+}
+
+// Matrices are flattened in column-major order, e.g. `mat4` -> `float[16]`
+fn value_type(ty: &TypeSpecifierNonArray) -> Option<(String, String)> {
+  let (element, count) = match common::scalar_kind(ty) {
+    Some(ScalarKind::Float) => ("float", common::component_count(ty)?),
+    // glUniform*i is used for booleans, so they are ints here too
+    Some(ScalarKind::Int) | Some(ScalarKind::Bool) => ("int", common::component_count(ty)?),
+    Some(ScalarKind::UInt) => ("unsigned int", common::component_count(ty)?),
+    None => ("double", common::double_component_count(ty)?),
+  };
+
+  if count == 1 {
+    return Some((element.to_string(), String::new()));
+  }
+
+  return Some((element.to_string(), format!("[{}]", count)));
+}
+
+/// Names of the locations a uniform needs. Arrays of values are set at once through the
+/// location of their first element, struct members each have their own location.
+fn location_names(name: &str, ty: &GlslType) -> Vec<String> {
+  match ty {
+    GlslType::Struct { fields, .. } => fields
+      .iter()
+      .flat_map(|field| location_names(&format!("{}.{}", name, field.identifier), &field.ty))
+      .collect(),
+    GlslType::Array(element, ArraySize::Sized(size)) => match element.as_ref() {
+      GlslType::Struct { .. } | GlslType::Array(..) => (0..*size)
+        .flat_map(|i| location_names(&format!("{}[{}]", name, i), element))
+        .collect(),
+      _ => vec![name.to_string()],
+    },
+    GlslType::Array(_, ArraySize::Unsized) | GlslType::Unknown(_) => Vec::new(),
+    _ => vec![name.to_string()],
+  }
+}
@@ -0,0 +1,203 @@
+//! Compares the uniforms and attributes of a regenerated file with its previous generation, so
+//! watch mode can show how a shader change affects the generated API.
+
+use super::common::ShaderData;
+use super::type_script;
+use crate::canonicalize;
+use crate::utils::get_shader_type::ShaderType;
+use crate::utils::json::Json;
+use crate::utils::log::{print_diagnostic, Level, Segment};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf};
+
+thread_local! {
+  // Shader data of every shader generated by this process, keyed by its canonical path. Only
+  // watch mode and the Vite plugin generate a shader more than once. The shaders of a stage pair
+  // write the same output file, so it cannot be the key.
+  static PREVIOUS_GENERATIONS: RefCell<HashMap<PathBuf, ShaderData>> =
+    RefCell::new(HashMap::new());
+}
+
+/// A uniform or attribute whose type differs between two generations, `previous_type_label` is
+/// `None` when it was added and `type_label` is `None` when it was removed.
+#[derive(Debug, PartialEq)]
+pub struct ApiChange {
+  pub kind: &'static str,
+  pub name: String,
+  pub previous_type_label: Option<String>,
+  pub type_label: Option<String>,
+}
+
+/// Uniforms and attributes added, removed or redeclared with another type since `previous`,
+/// the additions and changes in the order of `current` followed by the removals.
+pub fn api_changes(previous: &ShaderData, current: &ShaderData) -> Vec<ApiChange> {
+  let mut changes = Vec::new();
+
+  for kind in ["uniform", "attribute"] {
+    let previous_variables = variable_types(previous, kind);
+    let current_variables = variable_types(current, kind);
+
+    for (name, type_label) in &current_variables {
+      let previous_type_label = previous_variables
+        .iter()
+        .find(|(previous, _)| previous == name)
+        .map(|(_, previous_type_label)| previous_type_label.clone());
+
+      if previous_type_label.as_ref() != Some(type_label) {
+        changes.push(ApiChange {
+          kind,
+          name: name.clone(),
+          previous_type_label,
+          type_label: Some(type_label.clone()),
+        });
+      }
+    }
+
+    for (name, type_label) in previous_variables {
+      if !current_variables
+        .iter()
+        .any(|(current, _)| *current == name)
+      {
+        changes.push(ApiChange {
+          kind,
+          name,
+          previous_type_label: Some(type_label),
+          type_label: None,
+        });
+      }
+    }
+  }
+
+  return changes;
+}
+
+/// Prints the changes since the last time the shader at `file_path` was generated, e.g.
+/// `[~] uniform vec2 resolution changed vec2 → vec3`. Nothing is printed the first time.
+pub fn report_changes(file_path: &PathBuf, shader_data: &ShaderData) {
+  let key = PathBuf::from(canonicalize(file_path.to_str().unwrap()));
+  let previous = PREVIOUS_GENERATIONS
+    .with(|generations| generations.borrow_mut().insert(key, shader_data.clone()));
+
+  let previous = match previous {
+    Some(previous) => previous,
+    None => return,
+  };
+
+  let file = file_path.file_name().unwrap().to_str().unwrap();
+
+  for change in api_changes(&previous, shader_data) {
+    let (marker, declared_type, description) =
+      match (&change.previous_type_label, &change.type_label) {
+        (Some(previous), Some(current)) => (
+          "~",
+          previous,
+          format!(" changed {} → {}", previous, current),
+        ),
+        (Some(previous), None) => ("-", previous, " removed".to_string()),
+        (None, Some(current)) => ("+", current, " added".to_string()),
+        (None, None) => continue,
+      };
+
+    let optional_string = |label: &Option<String>| match label {
+      Some(label) => Json::string(label),
+      None => Json::Null,
+    };
+
+    print_diagnostic(
+      Level::INFO,
+      "GENERATED_API_CHANGED",
+      &[
+        Segment::Text(&format!("[{}] {} {} ", marker, change.kind, declared_type)),
+        Segment::Highlight(&change.name),
+        Segment::Text(&description),
+      ],
+      vec![
+        ("file", Json::string(file)),
+        ("kind", Json::string(change.kind)),
+        ("name", Json::string(&change.name)),
+        ("previousType", optional_string(&change.previous_type_label)),
+        ("type", optional_string(&change.type_label)),
+      ],
+    );
+  }
+}
+
+// Name and type of every variable of `kind`, a uniform declared again by an imported file is
+// only listed once
+fn variable_types(shader_data: &ShaderData, kind: &str) -> Vec<(String, String)> {
+  let variables = match (kind, &shader_data.shader_type) {
+    ("uniform", _) => shader_data.uniforms.as_slice(),
+    // Only the inputs of a vertex shader are attributes, the others are varyings
+    ("attribute", ShaderType::Vertex) => shader_data.ins.as_slice(),
+    _ => &[],
+  };
+
+  let mut types: Vec<(String, String)> = Vec::new();
+
+  for variable in variables {
+    if !types.iter().any(|(name, _)| *name == variable.identifier) {
+      types.push((
+        variable.identifier.clone(),
+        type_script::variable_type_label(variable),
+      ));
+    }
+  }
+
+  return types;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::generator::common;
+
+  fn shader_data(source: &str) -> ShaderData {
+    return common::extract_shader_data(source, ShaderType::Vertex).unwrap();
+  }
+
+  #[test]
+  fn lists_added_changed_and_removed_variables() {
+    let previous = shader_data("#version 300 es\nuniform vec2 resolution;\nuniform float time;\nin vec3 position;\nin vec4 tangent;\nvoid main() {}\n");
+    let current = shader_data("#version 300 es\nuniform vec3 resolution;\nuniform float time;\nuniform vec3 newLight;\nin vec3 position;\nvoid main() {}\n");
+
+    assert_eq!(
+      api_changes(&previous, &current),
+      vec![
+        ApiChange {
+          kind: "uniform",
+          name: "resolution".to_string(),
+          previous_type_label: Some("vec2".to_string()),
+          type_label: Some("vec3".to_string()),
+        },
+        ApiChange {
+          kind: "uniform",
+          name: "newLight".to_string(),
+          previous_type_label: None,
+          type_label: Some("vec3".to_string()),
+        },
+        ApiChange {
+          kind: "attribute",
+          name: "tangent".to_string(),
+          previous_type_label: Some("vec4".to_string()),
+          type_label: None,
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn array_sizes_are_part_of_the_type() {
+    let previous = shader_data("#version 300 es\nuniform vec3 lights[4];\nvoid main() {}\n");
+    let current = shader_data("#version 300 es\nuniform vec3 lights[8];\nvoid main() {}\n");
+
+    assert_eq!(
+      api_changes(&previous, &current),
+      vec![ApiChange {
+        kind: "uniform",
+        name: "lights".to_string(),
+        previous_type_label: Some("vec3[4]".to_string()),
+        type_label: Some("vec3[8]".to_string()),
+      }]
+    );
+    assert!(api_changes(&current, &current).is_empty());
+  }
+}
@@ -1,19 +1,832 @@
-use crate::logln;
-use crate::utils::get_shader_type::ShaderType;
+use crate::now;
+use crate::options::{NamingConvention, Options};
+use crate::utils::get_shader_type::{get_shader_type, ShaderType};
+use crate::utils::json::Json;
+use crate::utils::log::{self, print_diagnostic, Level, Segment};
 use glsl::parser::Parse as _;
 use glsl::syntax::{
-  ShaderStage, SingleDeclaration, StorageQualifier, TypeQualifierSpec, TypeSpecifierNonArray,
+  ArraySpecifier, ArraySpecifierDimension, Block, Declaration, Expr, ExternalDeclaration,
+  InterpolationQualifier, LayoutQualifierSpec, PrecisionQualifier, PreprocessorExtension,
+  PreprocessorExtensionBehavior, PreprocessorExtensionName, PreprocessorVersion,
+  PreprocessorVersionProfile, ShaderStage, SingleDeclaration, StorageQualifier,
+  StructFieldSpecifier, StructSpecifier, TypeQualifier, TypeQualifierSpec, TypeSpecifierNonArray,
 };
 use glsl::visitor::{Host, Visit, Visitor};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const GENERATED_FILE_HEADER: &str =
+  "// DO NOT EDIT THIS FILE\n// This file is generated by glsl-types\n\n";
+
+#[derive(Debug, Clone)]
+pub enum GeneratorError {
+  UnsupportedShaderType(PathBuf),
+  CouldNotParseShader(String),
+  UnknownLanguage(String),
+  UnsupportedShaderStage(ShaderType),
+  /// A uniform declared more than once, e.g. in the shader and in a file it imports, with
+  /// different array sizes. `None` means it is not an array.
+  UniformArraySizeMismatch {
+    name: String,
+    first: Option<ArraySize>,
+    second: Option<ArraySize>,
+  },
+  /// A `--template` that could not be parsed, with the line of the problem.
+  InvalidTemplate(String),
+}
+
+/// Stage of the shader, taken from the extensions in the config file first.
+pub fn shader_type(file_path: &PathBuf, options: &Options) -> Result<ShaderType, GeneratorError> {
+  let configured = file_path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .and_then(|extension| options.extensions.get(extension));
+
+  if let Some(shader_type) = configured {
+    return Ok(shader_type.clone());
+  }
+
+  return get_shader_type(file_path)
+    .ok_or_else(|| GeneratorError::UnsupportedShaderType(file_path.clone()));
+}
 
 pub fn capitalize_first_letter(s: &str) -> String {
-  s.chars().next().unwrap().to_uppercase().collect::<String>() + &s[1..]
+  let mut chars = s.chars();
+
+  return match chars.next() {
+    Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    None => String::new(),
+  };
+}
+
+/// e.g. `my-special_shader` -> `MySpecialShader`. Letters inside a segment keep their case, so
+/// `myShader` -> `MyShader`.
+pub fn to_pascal_case(s: &str) -> String {
+  let output = s
+    .split(|c: char| !c.is_alphanumeric())
+    .map(capitalize_first_letter)
+    .collect::<String>();
+
+  // Identifiers cannot start with a digit
+  if output.starts_with(|c: char| c.is_ascii_digit()) {
+    return format!("_{}", output);
+  }
+
+  return output;
+}
+
+/// `file_stem` with the export prefix in front when it starts with a digit, e.g. `3d_effect`,
+/// since the names generated from it could not start an identifier. A stem without letters or
+/// digits, e.g. `+`, would give empty names and is replaced by `shader`.
+pub fn prefixed_file_stem(file_stem: &str, options: &Options) -> String {
+  if !file_stem.chars().any(char::is_alphanumeric) {
+    print_diagnostic(
+      Level::WARN,
+      "EXPORT_NAME_EMPTY",
+      &[
+        Segment::Text("The file name "),
+        Segment::Highlight(file_stem),
+        Segment::Text(" has no letters or digits, its exports are named after shader"),
+      ],
+      vec![("file", Json::string(file_stem))],
+    );
+
+    return "shader".to_string();
+  }
+
+  if !file_stem.starts_with(|c: char| c.is_ascii_digit()) {
+    return file_stem.to_string();
+  }
+
+  let prefix = options.export_prefix.as_deref().unwrap_or("_");
+
+  print_diagnostic(
+    Level::WARN,
+    "EXPORT_NAME_PREFIXED",
+    &[
+      Segment::Text("The file name "),
+      Segment::Highlight(file_stem),
+      Segment::Text(&format!(
+        " starts with a digit, its exports are prefixed with {}",
+        prefix
+      )),
+    ],
+    vec![
+      ("file", Json::string(file_stem)),
+      ("prefix", Json::string(prefix)),
+    ],
+  );
+
+  return format!("{}{}", prefix, file_stem);
+}
+
+/// Name of the constant exported for a shader file named `file_stem`.
+pub fn export_name(file_stem: &str, naming_convention: NamingConvention) -> String {
+  let pascal_case = to_pascal_case(file_stem);
+
+  return match naming_convention {
+    NamingConvention::Pascal => pascal_case,
+    NamingConvention::Camel => {
+      let mut chars = pascal_case.chars();
+
+      match chars.next() {
+        Some(first) => first.to_lowercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+      }
+    }
+    NamingConvention::ScreamingSnake => {
+      let output = to_snake_case(file_stem).trim_matches('_').to_uppercase();
+
+      if output.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", output)
+      } else {
+        output
+      }
+    }
+  };
+}
+
+pub fn to_snake_case(s: &str) -> String {
+  let mut output = String::new();
+  let mut previous_was_lowercase = false;
+
+  for c in s.chars() {
+    if c.is_alphanumeric() {
+      if c.is_uppercase() && previous_was_lowercase {
+        output.push('_');
+      }
+
+      previous_was_lowercase = c.is_lowercase() || c.is_numeric();
+      output.extend(c.to_lowercase());
+    } else {
+      if !output.is_empty() && !output.ends_with('_') {
+        output.push('_');
+      }
+
+      previous_was_lowercase = false;
+    }
+  }
+
+  return output;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScalarKind {
+  Float,
+  Int,
+  UInt,
+  Bool,
+}
+
+pub fn scalar_kind(ty: &TypeSpecifierNonArray) -> Option<ScalarKind> {
+  let kind = match ty {
+    TypeSpecifierNonArray::Float
+    | TypeSpecifierNonArray::Vec2
+    | TypeSpecifierNonArray::Vec3
+    | TypeSpecifierNonArray::Vec4
+    | TypeSpecifierNonArray::Mat2
+    | TypeSpecifierNonArray::Mat3
+    | TypeSpecifierNonArray::Mat4 => ScalarKind::Float,
+
+    TypeSpecifierNonArray::Int
+    | TypeSpecifierNonArray::IVec2
+    | TypeSpecifierNonArray::IVec3
+    | TypeSpecifierNonArray::IVec4 => ScalarKind::Int,
+
+    TypeSpecifierNonArray::UInt
+    | TypeSpecifierNonArray::UVec2
+    | TypeSpecifierNonArray::UVec3
+    | TypeSpecifierNonArray::UVec4 => ScalarKind::UInt,
+
+    TypeSpecifierNonArray::Bool
+    | TypeSpecifierNonArray::BVec2
+    | TypeSpecifierNonArray::BVec3
+    | TypeSpecifierNonArray::BVec4 => ScalarKind::Bool,
+
+    _ => return None,
+  };
+
+  return Some(kind);
+}
+
+/// Number of scalar components of a value type, e.g. 3 for `vec3` and 16 for `mat4`.
+/// Opaque types such as samplers do not have components and return `None`.
+pub fn component_count(ty: &TypeSpecifierNonArray) -> Option<usize> {
+  let count = match ty {
+    TypeSpecifierNonArray::Float
+    | TypeSpecifierNonArray::Int
+    | TypeSpecifierNonArray::UInt
+    | TypeSpecifierNonArray::Bool => 1,
+
+    TypeSpecifierNonArray::Vec2
+    | TypeSpecifierNonArray::IVec2
+    | TypeSpecifierNonArray::UVec2
+    | TypeSpecifierNonArray::BVec2 => 2,
+
+    TypeSpecifierNonArray::Vec3
+    | TypeSpecifierNonArray::IVec3
+    | TypeSpecifierNonArray::UVec3
+    | TypeSpecifierNonArray::BVec3 => 3,
+
+    TypeSpecifierNonArray::Vec4
+    | TypeSpecifierNonArray::IVec4
+    | TypeSpecifierNonArray::UVec4
+    | TypeSpecifierNonArray::BVec4
+    | TypeSpecifierNonArray::Mat2 => 4,
+
+    TypeSpecifierNonArray::Mat3 => 9,
+    TypeSpecifierNonArray::Mat4 => 16,
+    _ => return None,
+  };
+
+  return Some(count);
+}
+
+/// Rust type with the same components as the GLSL value type, e.g. `[f32; 3]` for `vec3`.
+pub fn convert_glsl_to_rust_type(ty: &TypeSpecifierNonArray) -> Option<String> {
+  let element = match scalar_kind(ty)? {
+    ScalarKind::Float => "f32",
+    ScalarKind::Int => "i32",
+    ScalarKind::UInt => "u32",
+    ScalarKind::Bool => "bool",
+  };
+
+  let count = component_count(ty)?;
+
+  if let Some((columns, rows)) = matrix_dimensions(ty) {
+    return Some(format!("[[{}; {}]; {}]", element, rows, columns));
+  }
+
+  if count == 1 {
+    return Some(element.to_string());
+  }
+
+  return Some(format!("[{}; {}]", element, count));
+}
+
+/// Python annotation with the same components as the GLSL value type, e.g.
+/// `tuple[float, float, float]` for `vec3`. Matrices are tuples of columns.
+pub fn convert_glsl_to_python_type(ty: &TypeSpecifierNonArray) -> Option<String> {
+  let element = match scalar_kind(ty)? {
+    ScalarKind::Float => "float",
+    ScalarKind::Int | ScalarKind::UInt => "int",
+    ScalarKind::Bool => "bool",
+  };
+
+  let tuple = |element: &str, count: usize| format!("tuple[{}]", vec![element; count].join(", "));
+
+  if let Some((columns, rows)) = matrix_dimensions(ty) {
+    return Some(tuple(&tuple(element, rows), columns));
+  }
+
+  let count = component_count(ty)?;
+
+  if count == 1 {
+    return Some(element.to_string());
+  }
+
+  return Some(tuple(element, count));
+}
+
+/// WGSL type with the same components as the GLSL value type, e.g. `vec3f` for `vec3` and
+/// `mat4x4f` for `mat4`. Booleans become `u32`, since WGSL buffers cannot hold `bool` and
+/// GLSL stores them in 4 bytes.
+pub fn convert_glsl_to_wgsl_type(ty: &TypeSpecifierNonArray) -> Option<String> {
+  let (scalar, suffix) = match scalar_kind(ty)? {
+    ScalarKind::Float => ("f32", "f"),
+    ScalarKind::Int => ("i32", "i"),
+    ScalarKind::UInt | ScalarKind::Bool => ("u32", "u"),
+  };
+
+  if let Some((columns, rows)) = matrix_dimensions(ty) {
+    return Some(format!("mat{}x{}{}", columns, rows, suffix));
+  }
+
+  let count = component_count(ty)?;
+
+  if count == 1 {
+    return Some(scalar.to_string());
+  }
+
+  return Some(format!("vec{}{}", count, suffix));
+}
+
+/// Columns and rows of a matrix type, `None` for every other type.
+pub fn matrix_dimensions(ty: &TypeSpecifierNonArray) -> Option<(usize, usize)> {
+  match ty {
+    TypeSpecifierNonArray::Mat2 => Some((2, 2)),
+    TypeSpecifierNonArray::Mat3 => Some((3, 3)),
+    TypeSpecifierNonArray::Mat4 => Some((4, 4)),
+    _ => None,
+  }
+}
+
+pub fn is_matrix(ty: &TypeSpecifierNonArray) -> bool {
+  matrix_dimensions(ty).is_some()
+}
+
+/// Number of components of a double precision type such as `dvec3`. Doubles are kept apart
+/// from the other value types since WebGL and the memory layouts here do not support them.
+pub fn double_component_count(ty: &TypeSpecifierNonArray) -> Option<usize> {
+  let count = match ty {
+    TypeSpecifierNonArray::Double => 1,
+    TypeSpecifierNonArray::DVec2 => 2,
+    TypeSpecifierNonArray::DVec3 => 3,
+    TypeSpecifierNonArray::DVec4 => 4,
+    TypeSpecifierNonArray::DMat2 => 4,
+    TypeSpecifierNonArray::DMat3 => 9,
+    TypeSpecifierNonArray::DMat4 => 16,
+    _ => return None,
+  };
+
+  return Some(count);
+}
+
+/// A single scalar of a uniform, used by the backends that store every
+/// component in its own column (e.g. `resolution` -> `resolution_x`, `resolution_y`).
+#[derive(Clone, Debug)]
+pub struct Component {
+  pub identifier: String,
+  pub suffix: Option<String>,
+  pub index: Option<usize>,
+  pub kind: ScalarKind,
+}
+
+impl Component {
+  pub fn snake_case_name(&self) -> String {
+    match &self.suffix {
+      Some(suffix) => format!("{}_{}", self.identifier, suffix),
+      None => self.identifier.clone(),
+    }
+  }
+
+  pub fn camel_case_name(&self) -> String {
+    match &self.suffix {
+      Some(suffix) => format!("{}{}", self.identifier, capitalize_first_letter(suffix)),
+      None => self.identifier.clone(),
+    }
+  }
+
+  /// Accessor relative to the uniforms object, e.g. `resolution[0]` or `time`.
+  pub fn accessor(&self) -> String {
+    match self.index {
+      Some(index) => format!("{}[{}]", self.identifier, index),
+      None => self.identifier.clone(),
+    }
+  }
+}
+
+pub fn flatten_components(variable: &TypedVariable) -> Vec<Component> {
+  let (count, kind) = match (
+    component_count(&variable.type_label),
+    scalar_kind(&variable.type_label),
+  ) {
+    (Some(count), Some(kind)) => (count, kind),
+    _ => return Vec::new(),
+  };
+
+  if count == 1 {
+    return vec![Component {
+      identifier: variable.identifier.clone(),
+      suffix: None,
+      index: None,
+      kind,
+    }];
+  }
+
+  return (0..count)
+    .map(|index| {
+      let suffix = if is_matrix(&variable.type_label) {
+        index.to_string()
+      } else {
+        ["x", "y", "z", "w"][index].to_string()
+      };
+
+      Component {
+        identifier: variable.identifier.clone(),
+        suffix: Some(suffix),
+        index: Some(index),
+        kind,
+      }
+    })
+    .collect();
+}
+
+/// Uniforms that hold plain values and can be stored or transmitted (samplers and arrays are skipped).
+pub fn value_uniforms(shader_data: &ShaderData) -> Vec<&TypedVariable> {
+  shader_data
+    .uniforms
+    .iter()
+    .filter(|uniform| {
+      uniform.array_size.is_none() && component_count(&uniform.type_label).is_some()
+    })
+    .collect()
+}
+
+/// Length of an array declaration. `Unsized` covers `float values[]` and dimensions that are
+/// not integer literals (e.g. `float values[COUNT]`), which cannot be evaluated here.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArraySize {
+  Sized(usize),
+  Unsized,
+}
+
+/// Size of the outermost dimension of an array specifier.
+pub fn array_size(array_specifier: &Option<ArraySpecifier>) -> Option<ArraySize> {
+  let dimension = array_specifier.as_ref()?.dimensions.0.first()?;
+
+  let size = match dimension {
+    ArraySpecifierDimension::ExplicitlySized(expr) => match expr.as_ref() {
+      Expr::IntConst(size) if *size >= 0 => ArraySize::Sized(*size as usize),
+      Expr::UIntConst(size) => ArraySize::Sized(*size as usize),
+      _ => ArraySize::Unsized,
+    },
+    ArraySpecifierDimension::Unsized => ArraySize::Unsized,
+  };
+
+  return Some(size);
+}
+
+/// Array size as shown in diagnostics, e.g. `[8]`.
+pub fn array_size_label(array_size: &Option<ArraySize>) -> String {
+  return match array_size {
+    Some(ArraySize::Sized(size)) => format!("[{}]", size),
+    Some(ArraySize::Unsized) => "[]".to_string(),
+    None => "not an array".to_string(),
+  };
 }
 
 #[derive(Clone, Debug)]
 pub struct TypedVariable {
   pub identifier: String,
   pub type_label: TypeSpecifierNonArray,
+  pub array_size: Option<ArraySize>,
+  pub binding: Option<usize>,
+  /// Descriptor set from `layout(set = N)`, as used by GLSL written for Vulkan.
+  pub set: Option<usize>,
+  pub location: Option<usize>,
+  /// `lowp`, `mediump` or `highp` on the declaration itself, e.g. `uniform lowp vec4 color`.
+  pub precision: Option<PrecisionQualifier>,
+  pub interpolation: Option<Interpolation>,
+  /// Format qualifier of an image uniform, e.g. `layout(binding = 0, rgba32f)`.
+  pub format: Option<ImageFormat>,
+  /// Text of the `//` comment lines right above the declaration, only filled in for uniforms.
+  pub doc_comment: Option<String>,
+}
+
+/// Interpolation of an `in` or `out` variable. `centroid` is a storage qualifier in GLSL but
+/// is reported here when no interpolation qualifier is given alongside it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Interpolation {
+  Flat,
+  Smooth,
+  Centroid,
+  NoPerspective,
+}
+
+impl Interpolation {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Interpolation::Flat => "flat",
+      Interpolation::Smooth => "smooth",
+      Interpolation::Centroid => "centroid",
+      Interpolation::NoPerspective => "noperspective",
+    }
+  }
+}
+
+/// Format qualifier of an image, in the order of the GLSL specification.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFormat {
+  Rgba32f,
+  Rgba16f,
+  Rg32f,
+  Rg16f,
+  R11fG11fB10f,
+  R32f,
+  R16f,
+  Rgba16,
+  Rgb10A2,
+  Rgba8,
+  Rg16,
+  Rg8,
+  R16,
+  R8,
+  Rgba16Snorm,
+  Rgba8Snorm,
+  Rg16Snorm,
+  Rg8Snorm,
+  R16Snorm,
+  R8Snorm,
+  Rgba32i,
+  Rgba16i,
+  Rgba8i,
+  Rg32i,
+  Rg16i,
+  Rg8i,
+  R32i,
+  R16i,
+  R8i,
+  Rgba32ui,
+  Rgba16ui,
+  Rgb10A2ui,
+  Rgba8ui,
+  Rg32ui,
+  Rg16ui,
+  Rg8ui,
+  R32ui,
+  R16ui,
+  R8ui,
+}
+
+const IMAGE_FORMATS: [(ImageFormat, &str); 39] = [
+  (ImageFormat::Rgba32f, "rgba32f"),
+  (ImageFormat::Rgba16f, "rgba16f"),
+  (ImageFormat::Rg32f, "rg32f"),
+  (ImageFormat::Rg16f, "rg16f"),
+  (ImageFormat::R11fG11fB10f, "r11f_g11f_b10f"),
+  (ImageFormat::R32f, "r32f"),
+  (ImageFormat::R16f, "r16f"),
+  (ImageFormat::Rgba16, "rgba16"),
+  (ImageFormat::Rgb10A2, "rgb10_a2"),
+  (ImageFormat::Rgba8, "rgba8"),
+  (ImageFormat::Rg16, "rg16"),
+  (ImageFormat::Rg8, "rg8"),
+  (ImageFormat::R16, "r16"),
+  (ImageFormat::R8, "r8"),
+  (ImageFormat::Rgba16Snorm, "rgba16_snorm"),
+  (ImageFormat::Rgba8Snorm, "rgba8_snorm"),
+  (ImageFormat::Rg16Snorm, "rg16_snorm"),
+  (ImageFormat::Rg8Snorm, "rg8_snorm"),
+  (ImageFormat::R16Snorm, "r16_snorm"),
+  (ImageFormat::R8Snorm, "r8_snorm"),
+  (ImageFormat::Rgba32i, "rgba32i"),
+  (ImageFormat::Rgba16i, "rgba16i"),
+  (ImageFormat::Rgba8i, "rgba8i"),
+  (ImageFormat::Rg32i, "rg32i"),
+  (ImageFormat::Rg16i, "rg16i"),
+  (ImageFormat::Rg8i, "rg8i"),
+  (ImageFormat::R32i, "r32i"),
+  (ImageFormat::R16i, "r16i"),
+  (ImageFormat::R8i, "r8i"),
+  (ImageFormat::Rgba32ui, "rgba32ui"),
+  (ImageFormat::Rgba16ui, "rgba16ui"),
+  (ImageFormat::Rgb10A2ui, "rgb10_a2ui"),
+  (ImageFormat::Rgba8ui, "rgba8ui"),
+  (ImageFormat::Rg32ui, "rg32ui"),
+  (ImageFormat::Rg16ui, "rg16ui"),
+  (ImageFormat::Rg8ui, "rg8ui"),
+  (ImageFormat::R32ui, "r32ui"),
+  (ImageFormat::R16ui, "r16ui"),
+  (ImageFormat::R8ui, "r8ui"),
+];
+
+impl ImageFormat {
+  pub fn from_name(name: &str) -> Option<ImageFormat> {
+    return IMAGE_FORMATS
+      .iter()
+      .find(|(_, format_name)| *format_name == name)
+      .map(|(format, _)| *format);
+  }
+
+  /// GLSL spelling of the format, e.g. `rgba32f`.
+  pub fn as_str(&self) -> &'static str {
+    return IMAGE_FORMATS
+      .iter()
+      .find(|(format, _)| format == self)
+      .map(|(_, name)| *name)
+      .unwrap();
+  }
+}
+
+/// A resolved shader as written, which the shader data is extracted from, and as embedded in the
+/// generated files after `--strip-comments` and `--minify-source`.
+pub struct ShaderSource {
+  pub original: String,
+  pub embedded: String,
+}
+
+/// Integer value of a layout qualifier such as `binding = 2` or `local_size_x = 8`.
+pub fn layout_value(qualifier: &Option<TypeQualifier>, name: &str) -> Option<usize> {
+  for qualifier in &qualifier.as_ref()?.qualifiers {
+    if let TypeQualifierSpec::Layout(layout) = qualifier {
+      for id in &layout.ids {
+        if let LayoutQualifierSpec::Identifier(identifier, Some(value)) = id {
+          if identifier.as_str() == name {
+            return match value.as_ref() {
+              Expr::IntConst(value) if *value >= 0 => Some(*value as usize),
+              Expr::UIntConst(value) => Some(*value as usize),
+              _ => None,
+            };
+          }
+        }
+      }
+    }
+  }
+
+  return None;
+}
+
+/// First layout qualifier without a value that is an image format.
+pub fn image_format(qualifier: &Option<TypeQualifier>) -> Option<ImageFormat> {
+  for qualifier in &qualifier.as_ref()?.qualifiers {
+    if let TypeQualifierSpec::Layout(layout) = qualifier {
+      for id in &layout.ids {
+        if let LayoutQualifierSpec::Identifier(identifier, None) = id {
+          if let Some(format) = ImageFormat::from_name(identifier.as_str()) {
+            return Some(format);
+          }
+        }
+      }
+    }
+  }
+
+  return None;
+}
+
+pub fn precision(qualifier: &Option<TypeQualifier>) -> Option<PrecisionQualifier> {
+  return qualifier
+    .as_ref()?
+    .qualifiers
+    .0
+    .iter()
+    .find_map(|qualifier| match qualifier {
+      TypeQualifierSpec::Precision(precision) => Some(precision.clone()),
+      _ => None,
+    });
+}
+
+pub fn interpolation(qualifier: &Option<TypeQualifier>) -> Option<Interpolation> {
+  let qualifier = qualifier.as_ref()?;
+
+  let interpolation = qualifier
+    .qualifiers
+    .0
+    .iter()
+    .find_map(|qualifier| match qualifier {
+      TypeQualifierSpec::Interpolation(InterpolationQualifier::Flat) => Some(Interpolation::Flat),
+      TypeQualifierSpec::Interpolation(InterpolationQualifier::Smooth) => {
+        Some(Interpolation::Smooth)
+      }
+      TypeQualifierSpec::Interpolation(InterpolationQualifier::NoPerspective) => {
+        Some(Interpolation::NoPerspective)
+      }
+      _ => None,
+    });
+
+  if interpolation.is_none() && has_storage(qualifier, StorageQualifier::Centroid) {
+    return Some(Interpolation::Centroid);
+  }
+
+  return interpolation;
+}
+
+const INPUT_PRIMITIVES: [&str; 5] = [
+  "points",
+  "lines",
+  "lines_adjacency",
+  "triangles",
+  "triangles_adjacency",
+];
+const OUTPUT_PRIMITIVES: [&str; 3] = ["points", "line_strip", "triangle_strip"];
+const TESSELLATION_DOMAINS: [&str; 3] = ["triangles", "quads", "isolines"];
+const TESSELLATION_SPACINGS: [&str; 3] = [
+  "equal_spacing",
+  "fractional_even_spacing",
+  "fractional_odd_spacing",
+];
+const TESSELLATION_WINDINGS: [&str; 2] = ["cw", "ccw"];
+
+/// Variables GLSL declares for every shader, including the ones added by GL_KHR_vulkan_glsl and
+/// multiview. Shaders can redeclare some of them, e.g. `layout(depth_greater) out float
+/// gl_FragDepth;`, which does not make them inputs or outputs of the shader.
+pub const BUILTIN_VARIABLES: [&str; 51] = [
+  // Vertex
+  "gl_VertexID",
+  "gl_InstanceID",
+  "gl_VertexIndex",
+  "gl_InstanceIndex",
+  "gl_DrawID",
+  "gl_BaseVertex",
+  "gl_BaseInstance",
+  "gl_Position",
+  "gl_PointSize",
+  "gl_ClipDistance",
+  "gl_CullDistance",
+  "gl_in",
+  "gl_out",
+  // Tessellation
+  "gl_PatchVerticesIn",
+  "gl_PrimitiveID",
+  "gl_InvocationID",
+  "gl_TessLevelOuter",
+  "gl_TessLevelInner",
+  "gl_TessCoord",
+  // Geometry
+  "gl_PrimitiveIDIn",
+  "gl_Layer",
+  "gl_ViewportIndex",
+  // Fragment
+  "gl_FragCoord",
+  "gl_FrontFacing",
+  "gl_PointCoord",
+  "gl_SampleID",
+  "gl_SamplePosition",
+  "gl_SampleMaskIn",
+  "gl_SampleMask",
+  "gl_HelperInvocation",
+  "gl_FragDepth",
+  "gl_FragColor",
+  "gl_FragData",
+  "gl_LastFragData",
+  // Compute
+  "gl_NumWorkGroups",
+  "gl_WorkGroupSize",
+  "gl_WorkGroupID",
+  "gl_LocalInvocationID",
+  "gl_GlobalInvocationID",
+  "gl_LocalInvocationIndex",
+  // Multiview
+  "gl_ViewID_OVR",
+  "gl_ViewIndex",
+  // Uniforms
+  "gl_DepthRange",
+  "gl_NumSamples",
+  // Compatibility profile and GLSL ES 1.00 attributes
+  "gl_Vertex",
+  "gl_Normal",
+  "gl_Color",
+  "gl_SecondaryColor",
+  "gl_FogCoord",
+  "gl_MultiTexCoord0",
+  "gl_TexCoord",
+];
+
+pub fn is_builtin_variable(name: &str) -> bool {
+  return BUILTIN_VARIABLES.contains(&name);
+}
+
+/// First layout qualifier without a value that is one of `names`, e.g. `triangles`.
+fn layout_flag(qualifier: &TypeQualifier, names: &[&str]) -> Option<String> {
+  for qualifier in &qualifier.qualifiers {
+    if let TypeQualifierSpec::Layout(layout) = qualifier {
+      for id in &layout.ids {
+        if let LayoutQualifierSpec::Identifier(identifier, None) = id {
+          if names.contains(&identifier.as_str()) {
+            return Some(identifier.as_str().to_string());
+          }
+        }
+      }
+    }
+  }
+
+  return None;
+}
+
+// Types listed by a `subroutine(A, B)` qualifier, empty for the `subroutine` of a type or uniform
+fn subroutine_types(qualifier: &TypeQualifier) -> Option<Vec<String>> {
+  return qualifier
+    .qualifiers
+    .0
+    .iter()
+    .find_map(|qualifier| match qualifier {
+      TypeQualifierSpec::Storage(StorageQualifier::Subroutine(types)) => Some(
+        types
+          .iter()
+          .map(|type_name| type_name.as_str().to_string())
+          .collect(),
+      ),
+      _ => None,
+    });
+}
+
+fn has_storage(qualifier: &TypeQualifier, storage: StorageQualifier) -> bool {
+  qualifier
+    .qualifiers
+    .0
+    .iter()
+    .any(|qualifier| *qualifier == TypeQualifierSpec::Storage(storage.clone()))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructField {
+  pub identifier: String,
+  pub ty: GlslType,
+}
+
+/// A GLSL type resolved against the structs declared in the shader.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GlslType {
+  Scalar(TypeSpecifierNonArray),
+  Vector(TypeSpecifierNonArray),
+  Matrix(TypeSpecifierNonArray),
+  Opaque(TypeSpecifierNonArray),
+  Struct {
+    name: Option<String>,
+    fields: Vec<StructField>,
+  },
+  Array(Box<GlslType>, ArraySize),
+  Unknown(String),
 }
 
 #[derive(Clone, Debug)]
@@ -21,12 +834,376 @@ pub struct ShaderData {
   pub uniforms: Vec<TypedVariable>,
   pub ins: Vec<TypedVariable>,
   pub outs: Vec<TypedVariable>,
+  pub structs: HashMap<String, StructSpecifier>,
+  pub buffers: Vec<BufferBlock>,
+  pub uniform_blocks: Vec<UniformBlock>,
+  /// `local_size_x`, `local_size_y` and `local_size_z` of a compute shader.
+  pub workgroup_size: Option<[usize; 3]>,
+  /// Input and output primitives and `max_vertices` of a geometry shader.
+  pub primitive_in: Option<String>,
+  pub primitive_out: Option<String>,
+  pub max_vertices: Option<usize>,
+  /// `layout(vertices = N) out` of a tessellation control shader.
+  pub patch_vertices: Option<usize>,
+  /// `triangles`, `quads` or `isolines`, the spacing, the winding and `point_mode` from the
+  /// `layout(...) in` of a tessellation evaluation shader.
+  pub tessellation_domain: Option<String>,
+  pub tessellation_spacing: Option<String>,
+  pub tessellation_winding: Option<String>,
+  pub point_mode: bool,
+  /// Number and profile (`core`, `compatibility` or `es`) of the `#version` directive.
+  pub glsl_version: Option<u32>,
+  pub glsl_profile: Option<String>,
+  /// `#extension` directives, in declaration order.
+  pub extensions: Vec<ExtensionDirective>,
+  /// `subroutine uniform` declarations, which are not part of `uniforms`.
+  pub subroutines: Vec<Subroutine>,
+  /// `atomic_uint` uniforms, which are backed by an atomic counter buffer instead of holding a
+  /// value and are not part of `uniforms`.
+  pub atomic_counters: Vec<AtomicCounter>,
   pub shader_type: ShaderType,
 }
 
+/// A `subroutine uniform Shade shading;` declaration, `functions` are the ones declared with
+/// `subroutine(Shade)` that can be selected for it.
+#[derive(Clone, Debug)]
+pub struct Subroutine {
+  pub uniform: String,
+  pub type_name: String,
+  pub functions: Vec<String>,
+}
+
+/// A `layout(binding = 0) uniform atomic_uint name;` declaration. `binding` is the index of the
+/// atomic counter buffer binding point, 0 when it is not given.
+#[derive(Clone, Debug)]
+pub struct AtomicCounter {
+  pub name: String,
+  pub binding: u32,
+}
+
+/// An `#extension name : behavior` directive, `name` is `all` for `#extension all`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionDirective {
+  pub name: String,
+  pub behavior: ExtensionBehavior,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExtensionBehavior {
+  Enable,
+  Require,
+  Warn,
+  Disable,
+}
+
+impl ExtensionBehavior {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ExtensionBehavior::Enable => "enable",
+      ExtensionBehavior::Require => "require",
+      ExtensionBehavior::Warn => "warn",
+      ExtensionBehavior::Disable => "disable",
+    }
+  }
+
+  /// Whether the shader uses the extension, `warn` enables it too.
+  pub fn is_required(&self) -> bool {
+    return *self != ExtensionBehavior::Disable;
+  }
+}
+
+/// A `buffer` (shader storage) block.
+#[derive(Clone, Debug)]
+pub struct BufferBlock {
+  pub name: String,
+  pub binding: Option<usize>,
+  pub set: Option<usize>,
+  pub fields: Vec<StructField>,
+}
+
+/// A `uniform` block, e.g. `layout(std140) uniform Transform { mat4 model; } transform;`.
+#[derive(Clone, Debug)]
+pub struct UniformBlock {
+  pub name: String,
+  pub instance_name: Option<String>,
+  pub binding: Option<usize>,
+  pub set: Option<usize>,
+  pub fields: Vec<StructField>,
+}
+
+impl ShaderData {
+  pub fn resolve_type(&self, ty: &TypeSpecifierNonArray) -> GlslType {
+    self.resolve_type_with_stack(ty, &mut Vec::new())
+  }
+
+  /// Type of a uniform, in or out, including its array dimension.
+  pub fn resolve_variable_type(&self, variable: &TypedVariable) -> GlslType {
+    let ty = self.resolve_type(&variable.type_label);
+
+    match variable.array_size {
+      Some(size) => GlslType::Array(Box::new(ty), size),
+      None => ty,
+    }
+  }
+
+  // `stack` holds the structs being resolved so self-referencing structs do not recurse forever
+  fn resolve_type_with_stack(
+    &self,
+    ty: &TypeSpecifierNonArray,
+    stack: &mut Vec<String>,
+  ) -> GlslType {
+    match ty {
+      TypeSpecifierNonArray::Struct(struct_specifier) => {
+        self.resolve_struct(struct_specifier, stack)
+      }
+      TypeSpecifierNonArray::TypeName(name) => {
+        let name = name.as_str().to_string();
+
+        match self.structs.get(&name) {
+          Some(struct_specifier) if !stack.contains(&name) => {
+            self.resolve_struct(struct_specifier, stack)
+          }
+          _ => GlslType::Unknown(name),
+        }
+      }
+      _ if is_matrix(ty) => GlslType::Matrix(ty.clone()),
+      _ => match component_count(ty) {
+        Some(1) => GlslType::Scalar(ty.clone()),
+        Some(_) => GlslType::Vector(ty.clone()),
+        None => GlslType::Opaque(ty.clone()),
+      },
+    }
+  }
+
+  fn resolve_struct(
+    &self,
+    struct_specifier: &StructSpecifier,
+    stack: &mut Vec<String>,
+  ) -> GlslType {
+    let name = struct_specifier
+      .name
+      .as_ref()
+      .map(|name| name.as_str().to_string());
+
+    if let Some(name) = &name {
+      stack.push(name.clone());
+    }
+
+    let fields = self.resolve_fields(&struct_specifier.fields.0, stack);
+
+    if name.is_some() {
+      stack.pop();
+    }
+
+    return GlslType::Struct { name, fields };
+  }
+
+  fn resolve_fields(
+    &self,
+    field_specifiers: &[StructFieldSpecifier],
+    stack: &mut Vec<String>,
+  ) -> Vec<StructField> {
+    let mut fields = Vec::new();
+
+    for field in field_specifiers {
+      let ty = self.resolve_type_with_stack(&field.ty.ty, stack);
+
+      for identifier in &field.identifiers {
+        // The dimension can be written on the field name (`vec3 points[4]`) or on the type (`vec3[4] points`)
+        let size = array_size(&identifier.array_spec).or(array_size(&field.ty.array_specifier));
+
+        let ty = match size {
+          Some(size) => GlslType::Array(Box::new(ty.clone()), size),
+          None => ty.clone(),
+        };
+
+        fields.push(StructField {
+          identifier: identifier.ident.as_str().to_string(),
+          ty,
+        });
+      }
+    }
+
+    return fields;
+  }
+}
+
 impl Visitor for ShaderData {
+  fn visit_declaration(&mut self, declaration: &Declaration) -> Visit {
+    if let Declaration::Global(type_qualifier, _) = declaration {
+      let qualifier = Some(type_qualifier.clone());
+
+      if let Some(x) = layout_value(&qualifier, "local_size_x") {
+        self.workgroup_size = Some([
+          x,
+          layout_value(&qualifier, "local_size_y").unwrap_or(1),
+          layout_value(&qualifier, "local_size_z").unwrap_or(1),
+        ]);
+      }
+
+      let is_in = has_storage(type_qualifier, StorageQualifier::In);
+      let is_out = has_storage(type_qualifier, StorageQualifier::Out);
+
+      // `triangles` is both a geometry input primitive and a tessellation domain
+      match self.shader_type {
+        ShaderType::TessControl if is_out => {
+          if let Some(patch_vertices) = layout_value(&qualifier, "vertices") {
+            self.patch_vertices = Some(patch_vertices);
+          }
+        }
+        ShaderType::TessEvaluation if is_in => {
+          if let Some(domain) = layout_flag(type_qualifier, &TESSELLATION_DOMAINS) {
+            self.tessellation_domain = Some(domain);
+          }
+
+          if let Some(spacing) = layout_flag(type_qualifier, &TESSELLATION_SPACINGS) {
+            self.tessellation_spacing = Some(spacing);
+          }
+
+          if let Some(winding) = layout_flag(type_qualifier, &TESSELLATION_WINDINGS) {
+            self.tessellation_winding = Some(winding);
+          }
+
+          if layout_flag(type_qualifier, &["point_mode"]).is_some() {
+            self.point_mode = true;
+          }
+        }
+        ShaderType::TessControl | ShaderType::TessEvaluation => {}
+        _ => {
+          if is_in {
+            if let Some(primitive) = layout_flag(type_qualifier, &INPUT_PRIMITIVES) {
+              self.primitive_in = Some(primitive);
+            }
+          }
+
+          if is_out {
+            if let Some(primitive) = layout_flag(type_qualifier, &OUTPUT_PRIMITIVES) {
+              self.primitive_out = Some(primitive);
+            }
+
+            if let Some(max_vertices) = layout_value(&qualifier, "max_vertices") {
+              self.max_vertices = Some(max_vertices);
+            }
+          }
+        }
+      }
+    }
+
+    Visit::Children
+  }
+
+  fn visit_preprocessor_version(&mut self, version: &PreprocessorVersion) -> Visit {
+    self.glsl_version = Some(version.version as u32);
+    self.glsl_profile = version.profile.as_ref().map(|profile| {
+      match profile {
+        PreprocessorVersionProfile::Core => "core",
+        PreprocessorVersionProfile::Compatibility => "compatibility",
+        PreprocessorVersionProfile::ES => "es",
+      }
+      .to_string()
+    });
+
+    Visit::Parent
+  }
+
+  fn visit_preprocessor_extension(&mut self, extension: &PreprocessorExtension) -> Visit {
+    let name = match &extension.name {
+      PreprocessorExtensionName::All => "all".to_string(),
+      PreprocessorExtensionName::Specific(name) => name.clone(),
+    };
+
+    // The behavior is mandatory in GLSL, a directive without one has no effect
+    let behavior = match extension.behavior {
+      Some(PreprocessorExtensionBehavior::Enable) => ExtensionBehavior::Enable,
+      Some(PreprocessorExtensionBehavior::Require) => ExtensionBehavior::Require,
+      Some(PreprocessorExtensionBehavior::Warn) => ExtensionBehavior::Warn,
+      Some(PreprocessorExtensionBehavior::Disable) => ExtensionBehavior::Disable,
+      None => return Visit::Parent,
+    };
+
+    self.extensions.push(ExtensionDirective { name, behavior });
+
+    Visit::Parent
+  }
+
+  fn visit_block(&mut self, block: &Block) -> Visit {
+    if has_storage(&block.qualifier, StorageQualifier::Buffer) {
+      self.buffers.push(BufferBlock {
+        name: block.name.as_str().to_string(),
+        binding: layout_value(&Some(block.qualifier.clone()), "binding"),
+        set: layout_value(&Some(block.qualifier.clone()), "set"),
+        fields: self.resolve_fields(&block.fields, &mut Vec::new()),
+      });
+    }
+
+    if has_storage(&block.qualifier, StorageQualifier::Uniform) {
+      self.uniform_blocks.push(UniformBlock {
+        name: block.name.as_str().to_string(),
+        instance_name: block
+          .identifier
+          .as_ref()
+          .map(|identifier| identifier.ident.as_str().to_string()),
+        binding: layout_value(&Some(block.qualifier.clone()), "binding"),
+        set: layout_value(&Some(block.qualifier.clone()), "set"),
+        fields: self.resolve_fields(&block.fields, &mut Vec::new()),
+      });
+    }
+
+    Visit::Parent
+  }
+
   fn visit_single_declaration(&mut self, declaration: &SingleDeclaration) -> Visit {
-    if let Some(name) = &declaration.name {
+    if let TypeSpecifierNonArray::Struct(struct_specifier) = &declaration.ty.ty.ty {
+      if let Some(struct_name) = &struct_specifier.name {
+        self
+          .structs
+          .insert(struct_name.as_str().to_string(), struct_specifier.clone());
+      }
+    }
+
+    if let Some(name) = declaration
+      .name
+      .as_ref()
+      .filter(|name| !is_builtin_variable(name.as_str()))
+    {
+      // They are set with `glUniformSubroutinesuiv` instead of holding a value
+      if let Some(qualifier) = &declaration.ty.qualifier {
+        if subroutine_types(qualifier).is_some() {
+          if let TypeSpecifierNonArray::TypeName(type_name) = &declaration.ty.ty.ty {
+            self.subroutines.push(Subroutine {
+              uniform: name.as_str().to_string(),
+              type_name: type_name.as_str().to_string(),
+              functions: Vec::new(),
+            });
+          }
+
+          return Visit::Parent;
+        }
+      }
+
+      if declaration.ty.ty.ty == TypeSpecifierNonArray::AtomicUInt {
+        self.atomic_counters.push(AtomicCounter {
+          name: name.as_str().to_string(),
+          binding: layout_value(&declaration.ty.qualifier, "binding").unwrap_or(0) as u32,
+        });
+
+        return Visit::Parent;
+      }
+
+      let variable = TypedVariable {
+        identifier: name.as_str().to_string(),
+        type_label: declaration.ty.ty.ty.clone(),
+        array_size: array_size(&declaration.array_specifier)
+          .or(array_size(&declaration.ty.ty.array_specifier)),
+        binding: layout_value(&declaration.ty.qualifier, "binding"),
+        set: layout_value(&declaration.ty.qualifier, "set"),
+        location: layout_value(&declaration.ty.qualifier, "location"),
+        precision: precision(&declaration.ty.qualifier),
+        interpolation: interpolation(&declaration.ty.qualifier),
+        format: image_format(&declaration.ty.qualifier),
+        doc_comment: None,
+      };
+
       if let Some(type_qualifier) = &declaration.ty.qualifier {
         type_qualifier
           .qualifiers
@@ -35,27 +1212,15 @@ impl Visitor for ShaderData {
           .for_each(|qualifier| {
             if let TypeQualifierSpec::Storage(storage_qualifier) = qualifier {
               if storage_qualifier == StorageQualifier::Uniform {
-                self.uniforms.push(TypedVariable {
-                  identifier: name.as_str().to_string(),
-                  type_label: declaration.ty.ty.ty.clone(),
-                });
+                self.uniforms.push(variable.clone());
               }
 
-              match self.shader_type {
-                _ => {
-                  if storage_qualifier == StorageQualifier::Out {
-                    self.outs.push(TypedVariable {
-                      identifier: name.as_str().to_string(),
-                      type_label: declaration.ty.ty.ty.clone(),
-                    });
-                  }
-                  if storage_qualifier == StorageQualifier::In {
-                    self.ins.push(TypedVariable {
-                      identifier: name.as_str().to_string(),
-                      type_label: declaration.ty.ty.ty.clone(),
-                    });
-                  }
-                }
+              if storage_qualifier == StorageQualifier::Out {
+                self.outs.push(variable.clone());
+              }
+
+              if storage_qualifier == StorageQualifier::In {
+                self.ins.push(variable.clone());
               }
             }
           });
@@ -66,23 +1231,248 @@ impl Visitor for ShaderData {
   }
 }
 
-pub fn extract_shader_data(file: &String, shader_type: ShaderType) -> ShaderData {
-  let stage: Result<glsl::syntax::TranslationUnit, glsl::parser::ParseError> =
-    ShaderStage::parse(file);
+pub fn extract_shader_data(
+  file: &str,
+  shader_type: ShaderType,
+) -> Result<ShaderData, GeneratorError> {
+  // The clock comes from the host, so it is only read when the timing is printed
+  let start = log::enabled(Level::DEBUG).then(now);
+
+  let stage = ShaderStage::parse(file)
+    .map_err(|error| GeneratorError::CouldNotParseShader(error.to_string()))?;
+
+  if let Some(start) = start {
+    let stage_name = format!("{:?}", shader_type).to_lowercase();
+    let duration = now() - start;
+
+    print_diagnostic(
+      Level::DEBUG,
+      "SHADER_PARSED",
+      &[Segment::Text(&format!(
+        "Parsed the {} shader AST in {:.2}ms",
+        stage_name, duration
+      ))],
+      vec![
+        ("stage", Json::String(stage_name)),
+        ("durationMs", Json::Number(duration)),
+      ],
+    );
+  }
+
+  let mut shader_data = shader_data_from_stage(&stage, shader_type)?;
+  let doc_comments = uniform_doc_comments(file);
+
+  for uniform in &mut shader_data.uniforms {
+    uniform.doc_comment = doc_comments.get(&uniform.identifier).cloned();
+  }
+
+  return Ok(shader_data);
+}
+
+/// Uniform name -> text of the `//` comment lines right above its declaration, joined with
+/// spaces. A blank line or any other code in between detaches a comment.
+fn uniform_doc_comments(source: &str) -> HashMap<String, String> {
+  let mut doc_comments = HashMap::new();
+  let mut comments: Vec<&str> = Vec::new();
+
+  for line in source.lines() {
+    let line = line.trim();
+
+    if let Some(comment) = line.strip_prefix("//") {
+      let comment = comment.trim();
+
+      // Lines of the import resolver, the `// File:` headers and the directives it comments
+      // out such as `// #include "noise.glsl"`
+      if comment.starts_with("File: ") || comment.starts_with('#') {
+        comments.clear();
+      } else {
+        comments.push(comment);
+      }
+
+      continue;
+    }
+
+    if !comments.is_empty() {
+      for name in uniform_names(line) {
+        doc_comments.insert(name, comments.join(" "));
+      }
+    }
+
+    comments.clear();
+  }
+
+  return doc_comments;
+}
+
+// Names declared by a line such as `layout(location = 0) uniform vec3 a, b[2];`
+fn uniform_names(line: &str) -> Vec<String> {
+  let declaration = match line.split_once(';') {
+    Some((declaration, _)) => declaration,
+    None => return Vec::new(),
+  };
+
+  // The layout qualifier can contain commas
+  let declaration = match declaration.rfind(')') {
+    Some(end) => &declaration[end + 1..],
+    None => declaration,
+  };
 
+  if !declaration.split_whitespace().any(|word| word == "uniform") {
+    return Vec::new();
+  }
+
+  return declaration
+    .split(',')
+    .filter_map(|declarator| {
+      let declarator = declarator.split('[').next()?.trim();
+      declarator.split_whitespace().last().map(str::to_string)
+    })
+    .collect();
+}
+
+/// Same as `extract_shader_data`, for a shader that is already parsed.
+pub fn shader_data_from_stage(
+  stage: &ShaderStage,
+  shader_type: ShaderType,
+) -> Result<ShaderData, GeneratorError> {
   let mut shader_data = ShaderData {
     uniforms: Vec::new(),
     ins: Vec::new(),
     outs: Vec::new(),
+    structs: HashMap::new(),
+    buffers: Vec::new(),
+    uniform_blocks: Vec::new(),
+    workgroup_size: None,
+    primitive_in: None,
+    primitive_out: None,
+    max_vertices: None,
+    patch_vertices: None,
+    tessellation_domain: None,
+    tessellation_spacing: None,
+    tessellation_winding: None,
+    point_mode: false,
+    glsl_version: None,
+    glsl_profile: None,
+    extensions: Vec::new(),
+    subroutines: Vec::new(),
+    atomic_counters: Vec::new(),
     shader_type,
   };
 
-  match stage {
-    Ok(stage) => stage.visit(&mut shader_data),
-    Err(e) => {
-      logln(&format!("Error parsing the shader: {}", e));
+  stage.visit(&mut shader_data);
+
+  // The functions can be defined before the uniforms that select them are declared
+  for external_declaration in &stage.0 {
+    if let ExternalDeclaration::FunctionDefinition(definition) = external_declaration {
+      let types = match &definition.prototype.ty.qualifier {
+        Some(qualifier) => subroutine_types(qualifier).unwrap_or_default(),
+        None => continue,
+      };
+
+      for subroutine in &mut shader_data.subroutines {
+        if types.contains(&subroutine.type_name) {
+          subroutine
+            .functions
+            .push(definition.prototype.name.as_str().to_string());
+        }
+      }
     }
   }
 
-  return shader_data;
+  for (i, uniform) in shader_data.uniforms.iter().enumerate() {
+    let mismatch = shader_data.uniforms[..i].iter().find(|previous| {
+      previous.identifier == uniform.identifier && previous.array_size != uniform.array_size
+    });
+
+    if let Some(previous) = mismatch {
+      return Err(GeneratorError::UniformArraySizeMismatch {
+        name: uniform.identifier.clone(),
+        first: previous.array_size,
+        second: uniform.array_size,
+      });
+    }
+  }
+
+  return Ok(shader_data);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn uniforms_declared_twice_with_different_array_sizes_are_reported() {
+    let source =
+      "#version 300 es\nuniform vec3 positions[8];\nuniform vec3 positions[16];\nvoid main() {}\n";
+
+    match extract_shader_data(source, ShaderType::Vertex) {
+      Err(GeneratorError::UniformArraySizeMismatch {
+        name,
+        first,
+        second,
+      }) => {
+        assert_eq!(name, "positions");
+        assert_eq!(first, Some(ArraySize::Sized(8)));
+        assert_eq!(second, Some(ArraySize::Sized(16)));
+      }
+      other => panic!("expected an array size mismatch, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn non_ascii_file_names_keep_their_characters() {
+    assert_eq!(capitalize_first_letter(""), "");
+    assert_eq!(capitalize_first_letter("éclair"), "Éclair");
+    assert_eq!(to_pascal_case("über_glow"), "ÜberGlow");
+  }
+
+  #[test]
+  fn non_ascii_file_names_keep_their_characters_in_snake_case() {
+    assert_eq!(to_snake_case("ÜberGlow"), "über_glow");
+    assert_eq!(
+      export_name("über_glow", NamingConvention::ScreamingSnake),
+      "ÜBER_GLOW"
+    );
+    assert_eq!(
+      export_name("日本", NamingConvention::ScreamingSnake),
+      "日本"
+    );
+
+    let options = Options::default();
+    assert_eq!(prefixed_file_stem("+", &options), "shader");
+    assert_eq!(
+      export_name(
+        &prefixed_file_stem("+", &options),
+        NamingConvention::ScreamingSnake
+      ),
+      "SHADER"
+    );
+  }
+
+  #[test]
+  fn file_names_starting_with_a_digit_are_prefixed() {
+    let mut options = Options::default();
+    assert_eq!(prefixed_file_stem("3d_effect", &options), "_3d_effect");
+    assert_eq!(prefixed_file_stem("wave", &options), "wave");
+
+    assert!(options.set_export_prefix("Fx".to_string()));
+    assert!(!options.set_export_prefix("3x".to_string()));
+    assert_eq!(
+      export_name(
+        &prefixed_file_stem("3d_effect", &options),
+        NamingConvention::Pascal
+      ),
+      "Fx3dEffect"
+    );
+  }
+
+  #[test]
+  fn redeclared_builtins_are_not_outputs() {
+    let source = "#version 460\nlayout(depth_greater) out float gl_FragDepth;\nlayout(origin_upper_left) in vec4 gl_FragCoord;\nout vec4 color;\nvoid main() {}\n";
+    let shader_data = extract_shader_data(source, ShaderType::Fragment).unwrap();
+
+    assert!(shader_data.ins.is_empty());
+    assert_eq!(shader_data.outs.len(), 1);
+    assert_eq!(shader_data.outs[0].identifier, "color");
+  }
 }
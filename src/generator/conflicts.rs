@@ -0,0 +1,725 @@
+//! Checks the shaders of a project against each other. Every shader is generated on its own, so
+//! declarations that only conflict across files are found here, after all of them are generated.
+
+use super::common::{
+  self, ArraySize, ExtensionBehavior, GlslType, Interpolation, ShaderData, TypedVariable,
+  UniformBlock,
+};
+use super::type_script;
+use crate::import_resolver::import_resolver::resolve_imports;
+use crate::options::Options;
+use crate::utils::get_shader_type::ShaderType;
+use crate::utils::json::Json;
+use crate::utils::log::{print_diagnostic, Level, Segment};
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub enum Conflict {
+  /// Two shaders declare the same vertex attribute with different types.
+  AttributeType {
+    name: String,
+    first_file: String,
+    first_type: String,
+    second_file: String,
+    second_type: String,
+  },
+  /// Two shaders give the same extension different behaviors.
+  ExtensionBehavior {
+    name: String,
+    first_file: String,
+    first_behavior: ExtensionBehavior,
+    second_file: String,
+    second_behavior: ExtensionBehavior,
+  },
+  /// `<stem>.vert` and `<stem>.frag` declare different versions, e.g. `300 es` and `310 es`.
+  /// `None` when a shader has no `#version` directive.
+  Version {
+    vertex_file: String,
+    vertex_version: Option<String>,
+    fragment_file: String,
+    fragment_version: Option<String>,
+  },
+  /// `<stem>.vert` and `<stem>.frag` declare the same uniform with different types.
+  UniformType {
+    name: String,
+    vertex_file: String,
+    vertex_type: String,
+    fragment_file: String,
+    fragment_type: String,
+  },
+  /// `<stem>.vert` and `<stem>.frag` declare a uniform block of the same name with other
+  /// members, e.g. `Transform { mat4 model; }` and `Transform { vec4 model; }`.
+  UniformBlockLayout {
+    name: String,
+    vertex_file: String,
+    vertex_layout: String,
+    fragment_file: String,
+    fragment_layout: String,
+  },
+  /// `<stem>.vert` and `<stem>.frag` declare the same uniform with different array sizes.
+  UniformArraySize {
+    name: String,
+    vertex_file: String,
+    vertex_size: Option<ArraySize>,
+    fragment_file: String,
+    fragment_size: Option<ArraySize>,
+  },
+  /// An `out` of `<stem>.vert` is an `in` of `<stem>.frag` with another type.
+  VaryingType {
+    name: String,
+    vertex_file: String,
+    vertex_type: String,
+    fragment_file: String,
+    fragment_type: String,
+  },
+  /// An `out` of `<stem>.vert` is an `in` of `<stem>.frag` with another interpolation
+  /// qualifier, `smooth` when none is given.
+  VaryingInterpolation {
+    name: String,
+    vertex_file: String,
+    vertex_interpolation: Interpolation,
+    fragment_file: String,
+    fragment_interpolation: Interpolation,
+  },
+  /// An `out` of `<stem>.vert` that `<stem>.frag` does not declare.
+  UnreadVarying {
+    name: String,
+    vertex_file: String,
+    vertex_type: String,
+    fragment_file: String,
+  },
+  /// An `in` of `<stem>.frag` that `<stem>.vert` does not declare.
+  UnwrittenVarying {
+    name: String,
+    vertex_file: String,
+    fragment_file: String,
+    fragment_type: String,
+  },
+  /// A variable that `<stem>.vert` and `<stem>.frag` both declare `in`, or both `out`.
+  VaryingDirection {
+    name: String,
+    qualifier: &'static str,
+    vertex_file: String,
+    fragment_file: String,
+  },
+}
+
+/// Conflicts between the declarations of `files`, in the order of `files`. Files that cannot be
+/// read or parsed are skipped, generating them already reported why.
+pub fn find_conflicts(files: &[String], options: &Options) -> Vec<Conflict> {
+  let mut conflicts = Vec::new();
+  let mut shaders: Vec<(String, ShaderData)> = Vec::new();
+
+  // Attribute -> type and file of its first declaration
+  let mut declarations: Vec<(String, String, String)> = Vec::new();
+  // Extension -> behavior and file of its first directive
+  let mut directives: Vec<(String, ExtensionBehavior, String)> = Vec::new();
+
+  for file in files {
+    let file_path = PathBuf::from(file);
+
+    let shader_type = match common::shader_type(&file_path, options) {
+      Ok(shader_type) => shader_type,
+      Err(_) => continue,
+    };
+
+    let shader_data = match resolve_imports(&file_path) {
+      Ok(resolved) => match common::extract_shader_data(&resolved.source, shader_type) {
+        Ok(shader_data) => shader_data,
+        Err(_) => continue,
+      },
+      Err(_) => continue,
+    };
+
+    // Only the inputs of a vertex shader are attributes
+    let attributes = match shader_data.shader_type {
+      ShaderType::Vertex => shader_data.ins.as_slice(),
+      _ => &[],
+    };
+
+    for attribute in attributes {
+      let label = type_script::variable_type_label(attribute);
+      let previous = declarations
+        .iter()
+        .find(|(name, _, _)| *name == attribute.identifier);
+
+      match previous {
+        Some((name, first_type, first_file)) if *first_type != label => {
+          conflicts.push(Conflict::AttributeType {
+            name: name.clone(),
+            first_file: first_file.clone(),
+            first_type: first_type.clone(),
+            second_file: file.clone(),
+            second_type: label,
+          })
+        }
+        Some(_) => {}
+        None => declarations.push((attribute.identifier.clone(), label, file.clone())),
+      }
+    }
+
+    for extension in &shader_data.extensions {
+      let previous = directives
+        .iter()
+        .find(|(name, _, _)| *name == extension.name);
+
+      match previous {
+        // A file can change the behavior of an extension itself, only other files conflict
+        Some((name, first_behavior, first_file))
+          if *first_behavior != extension.behavior && first_file != file =>
+        {
+          conflicts.push(Conflict::ExtensionBehavior {
+            name: name.clone(),
+            first_file: first_file.clone(),
+            first_behavior: *first_behavior,
+            second_file: file.clone(),
+            second_behavior: extension.behavior,
+          })
+        }
+        Some(_) => {}
+        None => directives.push((extension.name.clone(), extension.behavior, file.clone())),
+      }
+    }
+
+    shaders.push((file.clone(), shader_data));
+  }
+
+  for (vertex_file, vertex) in &shaders {
+    if vertex.shader_type != ShaderType::Vertex {
+      continue;
+    }
+
+    // `<stem>.frag` in the same folder, the program `<stem>.vert` is linked into
+    let stem = PathBuf::from(vertex_file).with_extension("");
+    let fragment = shaders.iter().find(|(file, shader_data)| {
+      shader_data.shader_type == ShaderType::Fragment
+        && PathBuf::from(file).with_extension("") == stem
+    });
+
+    if let Some((fragment_file, fragment)) = fragment {
+      conflicts.extend(pair_conflicts(vertex_file, vertex, fragment_file, fragment));
+    }
+  }
+
+  return conflicts;
+}
+
+/// Warns about every conflict between the declarations of `files`.
+pub fn report_conflicts(files: &[String], options: &Options) {
+  for conflict in find_conflicts(files, options) {
+    match conflict {
+      Conflict::AttributeType {
+        name,
+        first_file,
+        first_type,
+        second_file,
+        second_type,
+      } => print_diagnostic(
+        Level::WARN,
+        "ATTRIBUTE_TYPE_CONFLICT",
+        &[
+          Segment::Text("The attribute "),
+          Segment::Highlight(&name),
+          Segment::Text(&format!(" is a {} in ", first_type)),
+          Segment::Highlight(&first_file),
+          Segment::Text(&format!(" but a {} in ", second_type)),
+          Segment::Highlight(&second_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("firstFile", Json::string(&first_file)),
+          ("firstType", Json::string(&first_type)),
+          ("secondFile", Json::string(&second_file)),
+          ("secondType", Json::string(&second_type)),
+        ],
+      ),
+      Conflict::ExtensionBehavior {
+        name,
+        first_file,
+        first_behavior,
+        second_file,
+        second_behavior,
+      } => print_diagnostic(
+        Level::WARN,
+        "EXTENSION_BEHAVIOR_CONFLICT",
+        &[
+          Segment::Text("The extension "),
+          Segment::Highlight(&name),
+          Segment::Text(&format!(" is set to {} in ", first_behavior.as_str())),
+          Segment::Highlight(&first_file),
+          Segment::Text(&format!(" but to {} in ", second_behavior.as_str())),
+          Segment::Highlight(&second_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("firstFile", Json::string(&first_file)),
+          ("firstBehavior", Json::string(first_behavior.as_str())),
+          ("secondFile", Json::string(&second_file)),
+          ("secondBehavior", Json::string(second_behavior.as_str())),
+        ],
+      ),
+      Conflict::Version {
+        vertex_file,
+        vertex_version,
+        fragment_file,
+        fragment_version,
+      } => {
+        let directive = |version: &Option<String>| match version {
+          Some(version) => format!("#version {}", version),
+          None => "no #version".to_string(),
+        };
+        let optional_string = |version: &Option<String>| match version {
+          Some(version) => Json::string(version),
+          None => Json::Null,
+        };
+
+        print_diagnostic(
+          Level::WARN,
+          "VERSION_CONFLICT",
+          &[
+            Segment::Highlight(&vertex_file),
+            Segment::Text(&format!(" has {} but ", directive(&vertex_version))),
+            Segment::Highlight(&fragment_file),
+            Segment::Text(&format!(" has {}", directive(&fragment_version))),
+          ],
+          vec![
+            ("vertexFile", Json::string(&vertex_file)),
+            ("vertexVersion", optional_string(&vertex_version)),
+            ("fragmentFile", Json::string(&fragment_file)),
+            ("fragmentVersion", optional_string(&fragment_version)),
+          ],
+        )
+      }
+      Conflict::UniformType {
+        name,
+        vertex_file,
+        vertex_type,
+        fragment_file,
+        fragment_type,
+      } => print_diagnostic(
+        Level::WARN,
+        "UNIFORM_TYPE_CONFLICT",
+        &[
+          Segment::Text("The uniform "),
+          Segment::Highlight(&name),
+          Segment::Text(&format!(" is a {} in ", vertex_type)),
+          Segment::Highlight(&vertex_file),
+          Segment::Text(&format!(" but a {} in ", fragment_type)),
+          Segment::Highlight(&fragment_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("vertexFile", Json::string(&vertex_file)),
+          ("vertexType", Json::string(&vertex_type)),
+          ("fragmentFile", Json::string(&fragment_file)),
+          ("fragmentType", Json::string(&fragment_type)),
+        ],
+      ),
+      Conflict::UniformBlockLayout {
+        name,
+        vertex_file,
+        vertex_layout,
+        fragment_file,
+        fragment_layout,
+      } => print_diagnostic(
+        Level::WARN,
+        "UNIFORM_BLOCK_LAYOUT_CONFLICT",
+        &[
+          Segment::Text("The uniform block "),
+          Segment::Highlight(&name),
+          Segment::Text(&format!(" is {} in ", vertex_layout)),
+          Segment::Highlight(&vertex_file),
+          Segment::Text(&format!(" but {} in ", fragment_layout)),
+          Segment::Highlight(&fragment_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("vertexFile", Json::string(&vertex_file)),
+          ("vertexLayout", Json::string(&vertex_layout)),
+          ("fragmentFile", Json::string(&fragment_file)),
+          ("fragmentLayout", Json::string(&fragment_layout)),
+        ],
+      ),
+      Conflict::UniformArraySize {
+        name,
+        vertex_file,
+        vertex_size,
+        fragment_file,
+        fragment_size,
+      } => print_diagnostic(
+        Level::WARN,
+        "UNIFORM_ARRAY_SIZE_CONFLICT",
+        &[
+          Segment::Text("The uniform "),
+          Segment::Highlight(&name),
+          Segment::Text(&format!(
+            " is declared {} in ",
+            common::array_size_label(&vertex_size)
+          )),
+          Segment::Highlight(&vertex_file),
+          Segment::Text(&format!(
+            " but {} in ",
+            common::array_size_label(&fragment_size)
+          )),
+          Segment::Highlight(&fragment_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("vertexFile", Json::string(&vertex_file)),
+          (
+            "vertexSize",
+            Json::string(&common::array_size_label(&vertex_size)),
+          ),
+          ("fragmentFile", Json::string(&fragment_file)),
+          (
+            "fragmentSize",
+            Json::string(&common::array_size_label(&fragment_size)),
+          ),
+        ],
+      ),
+      Conflict::VaryingType {
+        name,
+        vertex_file,
+        vertex_type,
+        fragment_file,
+        fragment_type,
+      } => print_diagnostic(
+        Level::WARN,
+        "VARYING_TYPE_CONFLICT",
+        &[
+          Segment::Text("The varying "),
+          Segment::Highlight(&name),
+          Segment::Text(&format!(" is a {} in ", vertex_type)),
+          Segment::Highlight(&vertex_file),
+          Segment::Text(&format!(" but a {} in ", fragment_type)),
+          Segment::Highlight(&fragment_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("vertexFile", Json::string(&vertex_file)),
+          ("vertexType", Json::string(&vertex_type)),
+          ("fragmentFile", Json::string(&fragment_file)),
+          ("fragmentType", Json::string(&fragment_type)),
+        ],
+      ),
+      Conflict::VaryingInterpolation {
+        name,
+        vertex_file,
+        vertex_interpolation,
+        fragment_file,
+        fragment_interpolation,
+      } => print_diagnostic(
+        Level::WARN,
+        "VARYING_INTERPOLATION_CONFLICT",
+        &[
+          Segment::Text("The varying "),
+          Segment::Highlight(&name),
+          Segment::Text(&format!(" is {} in ", vertex_interpolation.as_str())),
+          Segment::Highlight(&vertex_file),
+          Segment::Text(&format!(" but {} in ", fragment_interpolation.as_str())),
+          Segment::Highlight(&fragment_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("vertexFile", Json::string(&vertex_file)),
+          (
+            "vertexInterpolation",
+            Json::string(vertex_interpolation.as_str()),
+          ),
+          ("fragmentFile", Json::string(&fragment_file)),
+          (
+            "fragmentInterpolation",
+            Json::string(fragment_interpolation.as_str()),
+          ),
+        ],
+      ),
+      Conflict::UnreadVarying {
+        name,
+        vertex_file,
+        vertex_type,
+        fragment_file,
+      } => print_diagnostic(
+        Level::WARN,
+        "UNREAD_VARYING",
+        &[
+          Segment::Text(&format!("The {} out ", vertex_type)),
+          Segment::Highlight(&name),
+          Segment::Text(" of "),
+          Segment::Highlight(&vertex_file),
+          Segment::Text(" is not an in of "),
+          Segment::Highlight(&fragment_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("vertexFile", Json::string(&vertex_file)),
+          ("vertexType", Json::string(&vertex_type)),
+          ("fragmentFile", Json::string(&fragment_file)),
+        ],
+      ),
+      Conflict::UnwrittenVarying {
+        name,
+        vertex_file,
+        fragment_file,
+        fragment_type,
+      } => print_diagnostic(
+        Level::WARN,
+        "UNWRITTEN_VARYING",
+        &[
+          Segment::Text(&format!("The {} in ", fragment_type)),
+          Segment::Highlight(&name),
+          Segment::Text(" of "),
+          Segment::Highlight(&fragment_file),
+          Segment::Text(" is not an out of "),
+          Segment::Highlight(&vertex_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("vertexFile", Json::string(&vertex_file)),
+          ("fragmentFile", Json::string(&fragment_file)),
+          ("fragmentType", Json::string(&fragment_type)),
+        ],
+      ),
+      Conflict::VaryingDirection {
+        name,
+        qualifier,
+        vertex_file,
+        fragment_file,
+      } => print_diagnostic(
+        Level::WARN,
+        "VARYING_DIRECTION_CONFLICT",
+        &[
+          Segment::Text("The varying "),
+          Segment::Highlight(&name),
+          Segment::Text(&format!(" is declared {} by both ", qualifier)),
+          Segment::Highlight(&vertex_file),
+          Segment::Text(" and "),
+          Segment::Highlight(&fragment_file),
+        ],
+        vec![
+          ("name", Json::string(&name)),
+          ("qualifier", Json::string(qualifier)),
+          ("vertexFile", Json::string(&vertex_file)),
+          ("fragmentFile", Json::string(&fragment_file)),
+        ],
+      ),
+    }
+  }
+}
+
+// Conflicts between the two stages of one program
+fn pair_conflicts(
+  vertex_file: &str,
+  vertex: &ShaderData,
+  fragment_file: &str,
+  fragment: &ShaderData,
+) -> Vec<Conflict> {
+  let mut conflicts = Vec::new();
+
+  let vertex_version = version(vertex);
+  let fragment_version = version(fragment);
+
+  if vertex_version != fragment_version {
+    conflicts.push(Conflict::Version {
+      vertex_file: vertex_file.to_string(),
+      vertex_version,
+      fragment_file: fragment_file.to_string(),
+      fragment_version,
+    });
+  }
+
+  for (i, uniform) in vertex.uniforms.iter().enumerate() {
+    // Declarations repeated by the same file were already checked when it was extracted
+    if vertex.uniforms[..i]
+      .iter()
+      .any(|previous| previous.identifier == uniform.identifier)
+    {
+      continue;
+    }
+
+    let other = fragment
+      .uniforms
+      .iter()
+      .find(|other| other.identifier == uniform.identifier);
+
+    if let Some(other) = other {
+      // Without the array sizes, which are compared on their own. Structs are resolved, so a
+      // struct declared with other fields in each stage conflicts too
+      let vertex_type = type_signature(&vertex.resolve_type(&uniform.type_label));
+      let fragment_type = type_signature(&fragment.resolve_type(&other.type_label));
+
+      if vertex_type != fragment_type {
+        conflicts.push(Conflict::UniformType {
+          name: uniform.identifier.clone(),
+          vertex_file: vertex_file.to_string(),
+          vertex_type,
+          fragment_file: fragment_file.to_string(),
+          fragment_type,
+        });
+      }
+
+      if other.array_size != uniform.array_size {
+        conflicts.push(Conflict::UniformArraySize {
+          name: uniform.identifier.clone(),
+          vertex_file: vertex_file.to_string(),
+          vertex_size: uniform.array_size,
+          fragment_file: fragment_file.to_string(),
+          fragment_size: other.array_size,
+        });
+      }
+    }
+  }
+
+  for block in &vertex.uniform_blocks {
+    let other = fragment
+      .uniform_blocks
+      .iter()
+      .find(|other| other.name == block.name);
+
+    if let Some(other) = other {
+      let vertex_layout = block_layout(block);
+      let fragment_layout = block_layout(other);
+
+      if vertex_layout != fragment_layout {
+        conflicts.push(Conflict::UniformBlockLayout {
+          name: block.name.clone(),
+          vertex_file: vertex_file.to_string(),
+          vertex_layout,
+          fragment_file: fragment_file.to_string(),
+          fragment_layout,
+        });
+      }
+    }
+  }
+
+  for varying in &vertex.outs {
+    let vertex_type = type_script::variable_type_label(varying);
+    let input = fragment
+      .ins
+      .iter()
+      .find(|input| input.identifier == varying.identifier);
+
+    match input {
+      Some(input) => {
+        let fragment_type = type_script::variable_type_label(input);
+
+        if fragment_type != vertex_type {
+          conflicts.push(Conflict::VaryingType {
+            name: varying.identifier.clone(),
+            vertex_file: vertex_file.to_string(),
+            vertex_type,
+            fragment_file: fragment_file.to_string(),
+            fragment_type,
+          });
+        }
+
+        // Both stages have to interpolate a varying the same way, e.g. `flat` for integers
+        let vertex_interpolation = interpolation(varying);
+        let fragment_interpolation = interpolation(input);
+
+        if vertex_interpolation != fragment_interpolation {
+          conflicts.push(Conflict::VaryingInterpolation {
+            name: varying.identifier.clone(),
+            vertex_file: vertex_file.to_string(),
+            vertex_interpolation,
+            fragment_file: fragment_file.to_string(),
+            fragment_interpolation,
+          });
+        }
+      }
+      None => {
+        if declares(&fragment.outs, &varying.identifier) {
+          conflicts.push(Conflict::VaryingDirection {
+            name: varying.identifier.clone(),
+            qualifier: "out",
+            vertex_file: vertex_file.to_string(),
+            fragment_file: fragment_file.to_string(),
+          });
+        } else {
+          conflicts.push(Conflict::UnreadVarying {
+            name: varying.identifier.clone(),
+            vertex_file: vertex_file.to_string(),
+            vertex_type,
+            fragment_file: fragment_file.to_string(),
+          });
+        }
+      }
+    }
+  }
+
+  for input in &fragment.ins {
+    // The types of the inputs the vertex shader writes were compared above
+    if declares(&vertex.outs, &input.identifier) {
+      continue;
+    }
+
+    if declares(&vertex.ins, &input.identifier) {
+      conflicts.push(Conflict::VaryingDirection {
+        name: input.identifier.clone(),
+        qualifier: "in",
+        vertex_file: vertex_file.to_string(),
+        fragment_file: fragment_file.to_string(),
+      });
+    } else {
+      conflicts.push(Conflict::UnwrittenVarying {
+        name: input.identifier.clone(),
+        vertex_file: vertex_file.to_string(),
+        fragment_file: fragment_file.to_string(),
+        fragment_type: type_script::variable_type_label(input),
+      });
+    }
+  }
+
+  return conflicts;
+}
+
+// GLSL spelling of a type, with the fields of structs, e.g. `Light { vec3 position; }`
+fn type_signature(ty: &GlslType) -> String {
+  return match ty {
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) | GlslType::Opaque(ty) => {
+      type_script::glsl_type_name(ty)
+    }
+    GlslType::Struct { name, fields } => {
+      let fields = fields
+        .iter()
+        .map(|field| format!(" {} {};", type_signature(&field.ty), field.identifier))
+        .collect::<String>();
+
+      format!("{} {{{} }}", name.as_deref().unwrap_or("struct"), fields)
+    }
+    GlslType::Array(element, size) => format!(
+      "{}{}",
+      type_signature(element),
+      common::array_size_label(&Some(*size))
+    ),
+    GlslType::Unknown(name) => name.clone(),
+  };
+}
+
+// Name and members of a block, written like a struct
+fn block_layout(block: &UniformBlock) -> String {
+  return type_signature(&GlslType::Struct {
+    name: Some(block.name.clone()),
+    fields: block.fields.clone(),
+  });
+}
+
+// Number and profile of the `#version` directive, e.g. `300 es`
+fn version(shader_data: &ShaderData) -> Option<String> {
+  let version = shader_data.glsl_version?;
+
+  return match &shader_data.glsl_profile {
+    Some(profile) => Some(format!("{} {}", version, profile)),
+    None => Some(version.to_string()),
+  };
+}
+
+fn interpolation(variable: &TypedVariable) -> Interpolation {
+  return variable
+    .interpolation
+    .clone()
+    .unwrap_or(Interpolation::Smooth);
+}
+
+fn declares(variables: &[TypedVariable], name: &str) -> bool {
+  return variables.iter().any(|variable| variable.identifier == name);
+}
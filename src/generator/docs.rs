@@ -0,0 +1,80 @@
+//! Markdown table of the uniforms of a shader, written next to the TypeScript output by
+//! `--emit-docs`.
+
+use super::common::{ArraySize, ShaderData};
+use super::type_script;
+
+/// Contents of the generated `.md` file. `shader_data` has to be extracted from the source
+/// before its comments are stripped, the descriptions are their doc comments.
+pub fn render_docs_file(file_name: &str, shader_data: &ShaderData) -> String {
+  let stage = format!("{:?}", shader_data.shader_type).to_lowercase();
+
+  let mut output_file = format!(
+    "<!-- This file is generated by glsl-types -->\n\n# {}\n\n",
+    file_name
+  );
+  output_file.push_str("| Uniform Name | GLSL Type | Stage | Array Size | Description |\n");
+  output_file.push_str("| --- | --- | --- | --- | --- |\n");
+
+  let mut documented = Vec::new();
+
+  for uniform in &shader_data.uniforms {
+    // A uniform can be declared again by an imported file
+    if documented.contains(&&uniform.identifier) {
+      continue;
+    }
+
+    let array_size = match uniform.array_size {
+      Some(ArraySize::Sized(size)) => size.to_string(),
+      Some(ArraySize::Unsized) => "runtime".to_string(),
+      None => String::new(),
+    };
+
+    let description = uniform
+      .doc_comment
+      .as_ref()
+      .map(|description| description.replace('|', "\\|"))
+      .unwrap_or_default();
+
+    output_file.push_str(&format!(
+      "| `{}` | `{}` | {} | {} | {} |\n",
+      uniform.identifier,
+      type_script::glsl_type_name(&uniform.type_label),
+      stage,
+      array_size,
+      description
+    ));
+    documented.push(&uniform.identifier);
+  }
+
+  return output_file;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::generator::common;
+  use crate::utils::get_shader_type::ShaderType;
+
+  fn render(source: &str) -> String {
+    let shader_data = common::extract_shader_data(source, ShaderType::Fragment);
+
+    return render_docs_file("wave.frag", &shader_data.unwrap());
+  }
+
+  #[test]
+  fn describes_uniforms_with_the_comments_above_them() {
+    let output = render("#version 300 es\nprecision highp float;\n// Seconds since the start\n// of the animation\nuniform float time;\n// Colors | tints\nlayout(location = 2) uniform vec3 palette[4];\nvoid main() {}\n");
+
+    assert!(output
+      .contains("| `time` | `float` | fragment |  | Seconds since the start of the animation |\n"));
+    assert!(output.contains("| `palette` | `vec3` | fragment | 4 | Colors \\| tints |\n"));
+  }
+
+  #[test]
+  fn comments_separated_by_a_blank_line_are_not_descriptions() {
+    let output = render("#version 300 es\nprecision highp float;\n// Not attached to anything\n\nuniform mat4 model;\nvoid main() {}\n");
+
+    assert!(output.contains("| `model` | `mat4` | fragment |  |  |\n"));
+  }
+}
@@ -0,0 +1,291 @@
+use super::common::{
+  self, ArraySize, GeneratorError, GlslType, ShaderData, StructField, TypedVariable, UniformBlock,
+};
+use super::type_script;
+use crate::options::Options;
+use crate::utils::get_shader_type::ShaderType;
+use crate::utils::json::Json;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+
+  let output_file_path = output_folder.join(format!("{}.json", output_file_name));
+  write_file(
+    output_file_path.to_str().unwrap(),
+    &(shader_json(&source.embedded, &shader_data).to_pretty_string() + "\n"),
+  );
+
+  return Ok(());
+}
+
+/// Description of the shader written by the `json` backend, also the context of `--template`.
+pub fn shader_json(content: &str, shader_data: &ShaderData) -> Json {
+  let stage = match shader_data.shader_type {
+    ShaderType::Vertex => "vertex",
+    ShaderType::Fragment => "fragment",
+    ShaderType::Geometry => "geometry",
+    ShaderType::Compute => "compute",
+    ShaderType::TessControl => "tess-control",
+    ShaderType::TessEvaluation => "tess-evaluation",
+  };
+
+  let glsl_version = match (shader_data.glsl_version, &shader_data.glsl_profile) {
+    (Some(version), Some(profile)) => Json::String(format!("{} {}", version, profile)),
+    (Some(version), None) => Json::String(version.to_string()),
+    (None, _) => Json::Null,
+  };
+
+  let mut fields = vec![
+    ("stage", Json::string(stage)),
+    ("glslVersion", glsl_version),
+    (
+      "uniforms",
+      variables_json(&shader_data.uniforms, shader_data),
+    ),
+    (
+      "uniformBlocks",
+      uniform_blocks_json(&shader_data.uniform_blocks),
+    ),
+    ("ins", variables_json(&shader_data.ins, shader_data)),
+    ("outs", variables_json(&shader_data.outs, shader_data)),
+    (
+      "extensions",
+      Json::Array(
+        shader_data
+          .extensions
+          .iter()
+          .map(|extension| {
+            Json::object(vec![
+              ("name", Json::string(&extension.name)),
+              ("behavior", Json::string(extension.behavior.as_str())),
+            ])
+          })
+          .collect(),
+      ),
+    ),
+    (
+      "subroutines",
+      Json::Array(
+        shader_data
+          .subroutines
+          .iter()
+          .map(|subroutine| {
+            Json::object(vec![
+              ("uniform", Json::string(&subroutine.uniform)),
+              ("type", Json::string(&subroutine.type_name)),
+              (
+                "functions",
+                Json::Array(
+                  subroutine
+                    .functions
+                    .iter()
+                    .map(|function| Json::string(function))
+                    .collect(),
+                ),
+              ),
+            ])
+          })
+          .collect(),
+      ),
+    ),
+    (
+      "atomicCounters",
+      Json::Array(
+        shader_data
+          .atomic_counters
+          .iter()
+          .map(|counter| {
+            Json::object(vec![
+              ("name", Json::string(&counter.name)),
+              ("binding", Json::Number(counter.binding as f64)),
+            ])
+          })
+          .collect(),
+      ),
+    ),
+  ];
+
+  if shader_data.shader_type == ShaderType::Geometry {
+    fields.push(("primitiveIn", optional_string(&shader_data.primitive_in)));
+    fields.push(("primitiveOut", optional_string(&shader_data.primitive_out)));
+    fields.push(("maxVertices", optional_number(shader_data.max_vertices)));
+  }
+
+  if shader_data.shader_type == ShaderType::TessControl {
+    fields.push(("patchVertices", optional_number(shader_data.patch_vertices)));
+  }
+
+  if shader_data.shader_type == ShaderType::TessEvaluation {
+    fields.push(("domain", optional_string(&shader_data.tessellation_domain)));
+    fields.push((
+      "spacing",
+      Json::string(
+        shader_data
+          .tessellation_spacing
+          .as_deref()
+          .unwrap_or("equal_spacing"),
+      ),
+    ));
+    fields.push((
+      "winding",
+      Json::string(shader_data.tessellation_winding.as_deref().unwrap_or("ccw")),
+    ));
+    fields.push(("pointMode", Json::Bool(shader_data.point_mode)));
+  }
+
+  if shader_data.shader_type == ShaderType::Compute {
+    let buffers = shader_data
+      .buffers
+      .iter()
+      .map(|buffer| {
+        Json::object(vec![
+          ("name", Json::string(&buffer.name)),
+          ("binding", optional_number(buffer.binding)),
+          ("fields", fields_json(&buffer.fields)),
+        ])
+      })
+      .collect();
+
+    // GLSL defaults every dimension of the workgroup to 1
+    let workgroup_size = shader_data
+      .workgroup_size
+      .unwrap_or([1, 1, 1])
+      .iter()
+      .map(|size| Json::Number(*size as f64))
+      .collect();
+
+    fields.push(("buffers", Json::Array(buffers)));
+    fields.push(("workgroupSize", Json::Array(workgroup_size)));
+  }
+
+  fields.push(("source", Json::string(content)));
+
+  return Json::object(fields);
+}
+
+fn uniform_blocks_json(blocks: &[UniformBlock]) -> Json {
+  return Json::Array(
+    blocks
+      .iter()
+      .map(|block| {
+        Json::object(vec![
+          ("name", Json::string(&block.name)),
+          ("instanceName", optional_string(&block.instance_name)),
+          ("binding", optional_number(block.binding)),
+          ("fields", fields_json(&block.fields)),
+        ])
+      })
+      .collect(),
+  );
+}
+
+fn fields_json(fields: &[StructField]) -> Json {
+  return Json::Array(
+    fields
+      .iter()
+      .map(|field| type_json(&field.identifier, &field.ty, None, &None))
+      .collect(),
+  );
+}
+
+fn variables_json(variables: &[TypedVariable], shader_data: &ShaderData) -> Json {
+  return Json::Array(
+    variables
+      .iter()
+      .map(|variable| {
+        type_json(
+          &variable.identifier,
+          &shader_data.resolve_variable_type(variable),
+          variable.binding,
+          &variable.doc_comment,
+        )
+      })
+      .collect(),
+  );
+}
+
+/// `{ name, type, arraySize }`, plus `fields` for structs and `docComment` for documented
+/// uniforms. `arraySize` is `null` for values that are not arrays and `0` for runtime-sized
+/// arrays.
+fn type_json(
+  name: &str,
+  ty: &GlslType,
+  binding: Option<usize>,
+  doc_comment: &Option<String>,
+) -> Json {
+  let (ty, array_size) = match ty {
+    GlslType::Array(element, ArraySize::Sized(size)) => {
+      (element.as_ref(), Json::Number(*size as f64))
+    }
+    GlslType::Array(element, ArraySize::Unsized) => (element.as_ref(), Json::Number(0.0)),
+    _ => (ty, Json::Null),
+  };
+
+  let mut fields = vec![("name", Json::string(name))];
+
+  match ty {
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) | GlslType::Opaque(ty) => {
+      fields.push((
+        "type",
+        Json::String(type_script::convert_glsl_to_ts_label(ty)),
+      ));
+      fields.push(("arraySize", array_size));
+    }
+    GlslType::Struct {
+      name: struct_name,
+      fields: struct_fields,
+    } => {
+      let struct_name = struct_name.clone().unwrap_or("struct".to_string());
+      let struct_fields = struct_fields
+        .iter()
+        .map(|field| type_json(&field.identifier, &field.ty, None, &None))
+        .collect();
+
+      fields.push(("type", Json::String(struct_name)));
+      fields.push(("arraySize", array_size));
+      fields.push(("fields", Json::Array(struct_fields)));
+    }
+    // Arrays of arrays only keep their outermost dimension
+    GlslType::Array(..) => {
+      fields.push(("type", Json::string("UNKNOWN")));
+      fields.push(("arraySize", array_size));
+    }
+    GlslType::Unknown(type_name) => {
+      fields.push(("type", Json::string(type_name)));
+      fields.push(("arraySize", array_size));
+    }
+  }
+
+  if let Some(binding) = binding {
+    fields.push(("binding", Json::Number(binding as f64)));
+  }
+
+  if let Some(doc_comment) = doc_comment {
+    fields.push(("docComment", Json::string(doc_comment)));
+  }
+
+  return Json::object(fields);
+}
+
+fn optional_string(value: &Option<String>) -> Json {
+  match value {
+    Some(value) => Json::string(value),
+    None => Json::Null,
+  }
+}
+
+fn optional_number(value: Option<usize>) -> Json {
+  match value {
+    Some(value) => Json::Number(value as f64),
+    None => Json::Null,
+  }
+}
@@ -0,0 +1,207 @@
+//! JSON Schema (draft-07) of the shader object in the TypeScript output, written next to it by
+//! `--emit-json-schema`.
+//!
+//! The schema is read back from the object literal the TypeScript backend emits, so the two
+//! cannot describe different shapes.
+
+use crate::utils::json::Json;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Contents of the generated `.schema.json` file, `None` if `object` is not a literal this
+/// module understands.
+pub fn render_schema_file(export_name: &str, file_name: &str, object: &str) -> Option<String> {
+  let mut parser = Parser {
+    chars: object.chars().peekable(),
+  };
+
+  let schema = match parser.value()? {
+    Json::Object(mut fields) => {
+      let mut header = vec![
+        (
+          "$schema".to_string(),
+          Json::string("http://json-schema.org/draft-07/schema#"),
+        ),
+        ("title".to_string(), Json::string(export_name)),
+        (
+          "description".to_string(),
+          Json::String(format!("Shader object generated from {}", file_name)),
+        ),
+      ];
+      header.append(&mut fields);
+      Json::Object(header)
+    }
+    _ => return None,
+  };
+
+  return Some(schema.to_pretty_string() + "\n");
+}
+
+/// Reads the subset of JavaScript the shader object is written in: objects with identifier
+/// keys, arrays, strings, numbers, `null`, booleans, doc comments and the `SOURCE` constant.
+/// Every value is returned as the schema that only accepts it.
+struct Parser<'a> {
+  chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+  fn value(&mut self) -> Option<Json> {
+    self.skip_whitespace();
+
+    return match self.chars.peek()? {
+      '{' => self.object(),
+      '[' => self.array(),
+      '"' => {
+        self.chars.next();
+        let value = self.take_while(|c| c != '"');
+        self.chars.next();
+        Some(Json::object(vec![("const", Json::String(value))]))
+      }
+      c if c.is_ascii_digit() || *c == '-' => {
+        let number = self.take_while(|c| c.is_ascii_digit() || c == '-' || c == '.');
+        Some(Json::object(vec![(
+          "const",
+          Json::Number(number.parse().ok()?),
+        )]))
+      }
+      _ => match self.identifier().as_str() {
+        "null" => Some(Json::object(vec![("type", Json::string("null"))])),
+        "true" => Some(Json::object(vec![("const", Json::Bool(true))])),
+        "false" => Some(Json::object(vec![("const", Json::Bool(false))])),
+        // `source: SOURCE`, the embedded shader
+        "SOURCE" => Some(Json::object(vec![("type", Json::string("string"))])),
+        _ => None,
+      },
+    };
+  }
+
+  fn object(&mut self) -> Option<Json> {
+    self.chars.next();
+    let mut properties = Vec::new();
+
+    loop {
+      self.skip_whitespace();
+
+      if self.chars.peek()? == &'}' {
+        self.chars.next();
+        break;
+      }
+
+      let key = self.identifier();
+      self.skip_whitespace();
+
+      if key.is_empty() || self.chars.next()? != ':' {
+        return None;
+      }
+
+      properties.push((key, self.value()?));
+      self.skip_separator();
+    }
+
+    let required = properties
+      .iter()
+      .map(|(key, _)| Json::string(key))
+      .collect();
+
+    return Some(Json::object(vec![
+      ("type", Json::string("object")),
+      ("properties", Json::Object(properties)),
+      ("required", Json::Array(required)),
+      ("additionalProperties", Json::Bool(false)),
+    ]));
+  }
+
+  fn array(&mut self) -> Option<Json> {
+    self.chars.next();
+    let mut items = Vec::new();
+
+    loop {
+      self.skip_whitespace();
+
+      if self.chars.peek()? == &']' {
+        self.chars.next();
+        break;
+      }
+
+      items.push(self.value()?);
+      self.skip_separator();
+    }
+
+    let length = Json::Number(items.len() as f64);
+
+    return Some(Json::object(vec![
+      ("type", Json::string("array")),
+      ("items", Json::Array(items)),
+      ("minItems", length.clone()),
+      ("maxItems", length),
+    ]));
+  }
+
+  fn identifier(&mut self) -> String {
+    return self.take_while(|c| c.is_alphanumeric() || c == '_' || c == '$');
+  }
+
+  fn skip_separator(&mut self) {
+    self.skip_whitespace();
+
+    if self.chars.peek() == Some(&',') {
+      self.chars.next();
+    }
+  }
+
+  // Whitespace and `/** ... */` doc comments
+  fn skip_whitespace(&mut self) {
+    loop {
+      self.take_while(char::is_whitespace);
+
+      if self.chars.peek() != Some(&'/') {
+        return;
+      }
+
+      let mut previous = ' ';
+      for c in self.chars.by_ref() {
+        if previous == '*' && c == '/' {
+          break;
+        }
+        previous = c;
+      }
+    }
+  }
+
+  fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+    let mut output = String::new();
+
+    while let Some(c) = self.chars.next_if(|c| predicate(*c)) {
+      output.push(c);
+    }
+
+    return output;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn every_value_is_a_constant_of_the_schema() {
+    let object = "{\n  uniforms: {\n    /** Seconds */\n    time: \"float\",\n  },\n  workgroupSize: [8, 1, 1],\n  glslProfile: null,\n  source: SOURCE\n}";
+    let schema = render_schema_file("Wave", "wave.frag", object).unwrap();
+
+    assert!(schema.starts_with(
+      "{\n  \"$schema\": \"http://json-schema.org/draft-07/schema#\",\n  \"title\": \"Wave\",\n"
+    ));
+    assert!(schema.contains("\"time\": {\n          \"const\": \"float\"\n        }"));
+    assert!(schema.contains("\"minItems\": 3,"));
+    assert!(schema.contains("\"glslProfile\": {\n      \"type\": \"null\"\n    }"));
+    assert!(schema.contains("\"source\": {\n      \"type\": \"string\"\n    }"));
+    assert!(schema.contains(
+      "\"required\": [\n    \"uniforms\",\n    \"workgroupSize\",\n    \"glslProfile\",\n    \"source\"\n  ],"
+    ));
+  }
+
+  #[test]
+  fn other_expressions_are_not_understood() {
+    assert!(render_schema_file("Wave", "wave.frag", "{ uniforms: makeUniforms() }").is_none());
+  }
+}
@@ -0,0 +1,342 @@
+use super::common::{self, ArraySize, GlslType, ShaderData, TypedVariable, UniformBlock};
+use crate::utils::json::Json;
+use crate::utils::log::{print_diagnostic, Level, Segment};
+use glsl::syntax::TypeSpecifierNonArray;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayoutRules {
+  Std140,
+  Std430,
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldLayout {
+  pub offset: usize,
+  pub size: usize,
+  /// Byte offset of every scalar component relative to `offset`, in column-major order.
+  pub component_offsets: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Layout {
+  pub fields: Vec<FieldLayout>,
+  pub size: usize,
+}
+
+fn round_up(value: usize, alignment: usize) -> usize {
+  value.div_ceil(alignment) * alignment
+}
+
+fn vector_alignment(components: usize) -> usize {
+  match components {
+    1 => 4,
+    2 => 8,
+    _ => 16,
+  }
+}
+
+/// Distance in bytes between two consecutive columns of a matrix.
+pub fn matrix_column_stride(rows: usize, rules: LayoutRules) -> usize {
+  match rules {
+    LayoutRules::Std140 => round_up(vector_alignment(rows), 16),
+    LayoutRules::Std430 => vector_alignment(rows),
+  }
+}
+
+pub fn base_alignment(ty: &TypeSpecifierNonArray, rules: LayoutRules) -> Option<usize> {
+  if let Some((_, rows)) = common::matrix_dimensions(ty) {
+    return Some(matrix_column_stride(rows, rules));
+  }
+
+  return common::component_count(ty).map(vector_alignment);
+}
+
+pub fn size_of(ty: &TypeSpecifierNonArray, rules: LayoutRules) -> Option<usize> {
+  if let Some((columns, rows)) = common::matrix_dimensions(ty) {
+    return Some(matrix_column_stride(rows, rules) * columns);
+  }
+
+  return common::component_count(ty).map(|count| count * 4);
+}
+
+pub fn component_offsets(ty: &TypeSpecifierNonArray, rules: LayoutRules) -> Vec<usize> {
+  if let Some((columns, rows)) = common::matrix_dimensions(ty) {
+    let stride = matrix_column_stride(rows, rules);
+
+    return (0..columns)
+      .flat_map(|column| (0..rows).map(move |row| column * stride + row * 4))
+      .collect();
+  }
+
+  return match common::component_count(ty) {
+    Some(count) => (0..count).map(|index| index * 4).collect(),
+    None => Vec::new(),
+  };
+}
+
+/// Uniforms written to the buffer of the `language` backend, see `common::value_uniforms`.
+/// Array and struct uniforms are not laid out, so each one is warned about instead of silently
+/// missing from the buffer. Samplers are never part of a buffer.
+pub fn buffer_uniforms<'a>(
+  shader_data: &'a ShaderData,
+  file_name: &str,
+  language: &str,
+) -> Vec<&'a TypedVariable> {
+  let uniforms = common::value_uniforms(shader_data);
+
+  for uniform in &shader_data.uniforms {
+    let skipped = match shader_data.resolve_variable_type(uniform) {
+      GlslType::Opaque(_) => false,
+      GlslType::Array(element, _) => !matches!(*element, GlslType::Opaque(_)),
+      _ => !uniforms
+        .iter()
+        .any(|value| value.identifier == uniform.identifier),
+    };
+
+    if skipped {
+      print_diagnostic(
+        Level::WARN,
+        "UNIFORM_NOT_IN_BUFFER",
+        &[
+          Segment::Text("The uniform "),
+          Segment::Highlight(&uniform.identifier),
+          Segment::Text(" of "),
+          Segment::Highlight(file_name),
+          Segment::Text(&format!(
+            " is an array or a struct, --language {} leaves it out of the buffer",
+            language
+          )),
+        ],
+        vec![
+          ("file", Json::string(file_name)),
+          ("name", Json::string(&uniform.identifier)),
+          ("language", Json::string(language)),
+        ],
+      );
+    }
+  }
+
+  return uniforms;
+}
+
+/// Lays out the variables one after another, as if they were the members of a uniform block.
+/// Variables without a memory representation (e.g. samplers) are skipped.
+pub fn compute_layout(variables: &[&TypedVariable], rules: LayoutRules) -> Layout {
+  let mut fields = Vec::new();
+  let mut offset = 0;
+  let mut max_alignment = 4;
+
+  for variable in variables {
+    let (alignment, size) = match (
+      base_alignment(&variable.type_label, rules),
+      size_of(&variable.type_label, rules),
+    ) {
+      (Some(alignment), Some(size)) => (alignment, size),
+      _ => continue,
+    };
+
+    offset = round_up(offset, alignment);
+    max_alignment = max_alignment.max(alignment);
+
+    fields.push(FieldLayout {
+      offset,
+      size,
+      component_offsets: component_offsets(&variable.type_label, rules),
+    });
+
+    offset += size;
+  }
+
+  let block_alignment = match rules {
+    LayoutRules::Std140 => round_up(max_alignment, 16),
+    LayoutRules::Std430 => max_alignment,
+  };
+
+  return Layout {
+    fields,
+    size: round_up(offset, block_alignment),
+  };
+}
+
+/// Base alignment and size of a resolved type, following the array and struct rules of the
+/// layout. `None` for types without a memory representation and runtime-sized arrays.
+pub fn type_alignment_and_size(ty: &GlslType, rules: LayoutRules) -> Option<(usize, usize)> {
+  match ty {
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) => {
+      Some((base_alignment(ty, rules)?, size_of(ty, rules)?))
+    }
+    GlslType::Array(element, ArraySize::Sized(length)) => {
+      let (alignment, size) = type_alignment_and_size(element, rules)?;
+
+      // std140 rounds the alignment and stride of array elements up to a vec4
+      let alignment = match rules {
+        LayoutRules::Std140 => round_up(alignment, 16),
+        LayoutRules::Std430 => alignment,
+      };
+
+      Some((alignment, round_up(size, alignment) * length))
+    }
+    GlslType::Struct { fields, .. } => {
+      let mut offset = 0;
+      let mut max_alignment = 4;
+
+      for field in fields {
+        let (alignment, size) = type_alignment_and_size(&field.ty, rules)?;
+
+        offset = round_up(offset, alignment) + size;
+        max_alignment = max_alignment.max(alignment);
+      }
+
+      let alignment = match rules {
+        LayoutRules::Std140 => round_up(max_alignment, 16),
+        LayoutRules::Std430 => max_alignment,
+      };
+
+      Some((alignment, round_up(offset, alignment)))
+    }
+    GlslType::Array(_, ArraySize::Unsized) | GlslType::Opaque(_) | GlslType::Unknown(_) => None,
+  }
+}
+
+/// Byte offset of every member of a uniform block under std140. Offsets stop at the first
+/// member whose size cannot be known, since every member after it depends on it.
+pub fn compute_std140_offsets(block: &UniformBlock) -> Vec<(String, usize)> {
+  let mut offsets = Vec::new();
+  let mut offset = 0;
+
+  for field in &block.fields {
+    let (alignment, size) = match type_alignment_and_size(&field.ty, LayoutRules::Std140) {
+      Some(layout) => layout,
+      None => break,
+    };
+
+    offset = round_up(offset, alignment);
+    offsets.push((field.identifier.clone(), offset));
+    offset += size;
+  }
+
+  return offsets;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::get_shader_type::ShaderType;
+
+  fn offsets(block: &str) -> Vec<(String, usize)> {
+    let source = format!("#version 300 es\n{}\nvoid main() {{}}\n", block);
+    let shader_data = common::extract_shader_data(&source, ShaderType::Vertex).unwrap();
+
+    return compute_std140_offsets(&shader_data.uniform_blocks[0]);
+  }
+
+  fn offset_values(block: &str) -> Vec<usize> {
+    return offsets(block)
+      .into_iter()
+      .map(|(_, offset)| offset)
+      .collect();
+  }
+
+  #[test]
+  fn scalars_are_packed() {
+    assert_eq!(
+      offsets("uniform Block { float a; int b; uint c; bool d; };"),
+      vec![
+        ("a".to_string(), 0),
+        ("b".to_string(), 4),
+        ("c".to_string(), 8),
+        ("d".to_string(), 12)
+      ]
+    );
+  }
+
+  #[test]
+  fn vectors_are_aligned_to_their_size() {
+    assert_eq!(
+      offset_values("uniform Block { float a; vec2 b; float c; vec4 d; };"),
+      vec![0, 8, 16, 32]
+    );
+  }
+
+  #[test]
+  fn vec3_is_aligned_like_vec4() {
+    assert_eq!(
+      offset_values("uniform Block { float a; vec3 b; vec3 c; };"),
+      vec![0, 16, 32]
+    );
+  }
+
+  #[test]
+  fn scalar_after_vec3_fills_the_padding() {
+    assert_eq!(
+      offset_values("uniform Block { vec3 a; float b; vec2 c; };"),
+      vec![0, 12, 16]
+    );
+  }
+
+  #[test]
+  fn matrix_columns_have_a_vec4_stride() {
+    assert_eq!(
+      offset_values("uniform Block { mat4 a; mat3 b; mat2 c; float d; };"),
+      vec![0, 64, 112, 144]
+    );
+  }
+
+  #[test]
+  fn array_elements_have_a_vec4_stride() {
+    assert_eq!(
+      offset_values("uniform Block { float a[3]; float b; vec2 c[2]; float d; };"),
+      vec![0, 48, 64, 96]
+    );
+  }
+
+  #[test]
+  fn matrix_arrays() {
+    assert_eq!(
+      offset_values("uniform Block { mat4 a[2]; float b; };"),
+      vec![0, 128]
+    );
+  }
+
+  #[test]
+  fn structs_are_aligned_and_padded_to_vec4() {
+    assert_eq!(
+      offset_values(
+        "struct Light { vec3 position; float intensity; float range; };\n\
+         uniform Block { float a; Light light; float b; };"
+      ),
+      vec![0, 16, 48]
+    );
+  }
+
+  #[test]
+  fn struct_arrays() {
+    assert_eq!(
+      offset_values(
+        "struct Item { float value; };\n\
+         uniform Block { Item items[3]; float b; };"
+      ),
+      vec![0, 48]
+    );
+  }
+
+  #[test]
+  fn nested_structs() {
+    assert_eq!(
+      offset_values(
+        "struct Inner { vec2 a; };\n\
+         struct Outer { float b; Inner inner; };\n\
+         uniform Block { Outer outer; vec3 c; };"
+      ),
+      vec![0, 32]
+    );
+  }
+
+  #[test]
+  fn offsets_stop_at_unknown_types() {
+    assert_eq!(
+      offset_values("uniform Block { float a; Missing b; float c; };"),
+      vec![0]
+    );
+  }
+}
@@ -1,2 +1,34 @@
-pub mod type_script;
+pub mod c;
+pub mod changes;
 pub mod common;
+pub mod conflicts;
+pub mod docs;
+pub mod json;
+pub mod json_schema;
+pub mod layout;
+pub mod python;
+pub mod rust;
+pub mod template;
+pub mod threejs;
+pub mod ts_dynamodb;
+pub mod ts_edgedb;
+pub mod ts_fauna;
+pub mod ts_grpc;
+pub mod ts_kafka;
+pub mod ts_mongo;
+pub mod ts_mqtt;
+pub mod ts_neon;
+pub mod ts_pixi;
+pub mod ts_redis_json;
+pub mod ts_shared_arraybuffer;
+pub mod ts_socket_io;
+pub mod ts_wasm_memory;
+pub mod ts_webgpu_buffer;
+pub mod ts_webgpu_layout;
+pub mod ts_webrtc_datachannel;
+pub mod ts_websocket;
+pub mod ts_xata;
+pub mod type_script;
+pub mod unused;
+pub mod webgl_setters;
+pub mod wgsl;
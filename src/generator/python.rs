@@ -0,0 +1,176 @@
+use super::common::{self, ArraySize, GeneratorError, GlslType, ShaderData};
+use crate::options::Options;
+use crate::utils::get_shader_type::ShaderType;
+use crate::write_file;
+
+const PYTHON_KEYWORDS: [&str; 35] = [
+  "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+  "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in",
+  "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with",
+  "yield",
+];
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_file = render_types_file(&source.embedded, output_file_name, &shader_data);
+
+  let output_file_path = output_folder.join(format!("{}.py", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+/// Contents of the generated `.py` module for a shader named `output_file_name`.
+pub fn render_types_file(
+  content: &str,
+  output_file_name: &str,
+  shader_data: &ShaderData,
+) -> String {
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let mut classes = Vec::new();
+
+  let mut output_file = String::new();
+  output_file.push_str("# DO NOT EDIT THIS FILE\n# This file is generated by glsl-types\n\n");
+  output_file.push_str("import dataclasses\n\n");
+
+  let source_name = match shader_data.shader_type {
+    ShaderType::Vertex => "VERTEX_SOURCE",
+    ShaderType::Fragment => "FRAGMENT_SOURCE",
+    ShaderType::Geometry => "GEOMETRY_SOURCE",
+    ShaderType::Compute => "COMPUTE_SOURCE",
+    ShaderType::TessControl => "TESS_CONTROL_SOURCE",
+    ShaderType::TessEvaluation => "TESS_EVALUATION_SOURCE",
+  };
+
+  // Escaped so backslashes and quotes in the shader cannot end the string early
+  let source = content.replace('\\', "\\\\").replace('"', "\\\"");
+  output_file.push_str(&format!("{}: str = \"\"\"{}\"\"\"\n", source_name, source));
+
+  let fields = shader_data
+    .uniforms
+    .iter()
+    .map(|variable| {
+      (
+        variable.identifier.clone(),
+        shader_data.resolve_variable_type(variable),
+        variable.doc_comment.clone(),
+      )
+    })
+    .collect::<Vec<(String, GlslType, Option<String>)>>();
+
+  let uniforms = render_class(
+    &format!("{}Uniforms", output_type_name),
+    &fields,
+    &mut classes,
+  );
+
+  for definition in classes {
+    output_file.push_str("\n\n");
+    output_file.push_str(&definition);
+  }
+
+  output_file.push_str("\n\n");
+  output_file.push_str(&uniforms);
+
+  return output_file;
+}
+
+/// Renders `class <name>` as a dataclass from the name, type and doc comment of each field.
+/// Classes of the struct fields are rendered into `classes` first so they are declared before
+/// being used.
+fn render_class(
+  name: &str,
+  fields: &[(String, GlslType, Option<String>)],
+  classes: &mut Vec<String>,
+) -> String {
+  let mut definition = String::new();
+
+  for (identifier, ty, doc_comment) in fields {
+    let python_type = match python_field_type(name, identifier, ty, classes) {
+      Some(python_type) => python_type,
+      None => continue,
+    };
+
+    if let Some(doc_comment) = doc_comment {
+      definition.push_str(&format!("    # {}\n", doc_comment));
+    }
+
+    definition.push_str(&format!(
+      "    {}: {}\n",
+      python_identifier(identifier),
+      python_type
+    ));
+  }
+
+  if definition.is_empty() {
+    definition.push_str("    pass\n");
+  }
+
+  return format!("@dataclasses.dataclass\nclass {}:\n{}", name, definition);
+}
+
+/// Python annotation of a field, `None` when the type has no Python equivalent.
+fn python_field_type(
+  owner: &str,
+  identifier: &str,
+  ty: &GlslType,
+  classes: &mut Vec<String>,
+) -> Option<String> {
+  let field_type = match ty {
+    GlslType::Struct {
+      name: struct_name,
+      fields: struct_fields,
+    } => {
+      let struct_name = match struct_name {
+        Some(struct_name) => common::capitalize_first_letter(struct_name),
+        None => format!("{}{}", owner, common::capitalize_first_letter(identifier)),
+      };
+
+      let struct_fields = struct_fields
+        .iter()
+        .map(|field| (field.identifier.clone(), field.ty.clone(), None))
+        .collect::<Vec<(String, GlslType, Option<String>)>>();
+
+      let class_definition = render_class(&struct_name, &struct_fields, classes);
+
+      if !classes.contains(&class_definition) {
+        classes.push(class_definition);
+      }
+
+      struct_name
+    }
+    GlslType::Array(element, ArraySize::Sized(_)) => {
+      format!(
+        "list[{}]",
+        python_field_type(owner, identifier, element, classes)?
+      )
+    }
+    // Runtime-sized arrays only exist in buffer blocks
+    GlslType::Array(_, ArraySize::Unsized) => return None,
+    // Samplers are bound to texture units
+    GlslType::Opaque(_) => "int".to_string(),
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) => {
+      common::convert_glsl_to_python_type(ty)?
+    }
+    GlslType::Unknown(_) => return None,
+  };
+
+  return Some(field_type);
+}
+
+fn python_identifier(identifier: &str) -> String {
+  if PYTHON_KEYWORDS.contains(&identifier) {
+    return format!("{}_", identifier);
+  }
+
+  return identifier.to_string();
+}
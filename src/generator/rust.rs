@@ -0,0 +1,253 @@
+use super::common::{self, ArraySize, GeneratorError, GlslType, ShaderData, TypedVariable};
+use crate::options::Options;
+use crate::utils::get_shader_type::ShaderType;
+use crate::write_file;
+use glsl::syntax::TypeSpecifierNonArray;
+
+const RUST_KEYWORDS: [&str; 38] = [
+  "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+  "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+  "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+  "use", "where", "while",
+];
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_file = render_types_file(&source.embedded, output_file_name, &shader_data);
+
+  let output_file_path = output_folder.join(format!("{}.rs", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+/// Contents of the generated `.rs` file for a shader named `output_file_name`.
+pub fn render_types_file(
+  content: &str,
+  output_file_name: &str,
+  shader_data: &ShaderData,
+) -> String {
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let mut structs = Vec::new();
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+
+  let source_name = match shader_data.shader_type {
+    ShaderType::Vertex => "VERTEX_SOURCE",
+    ShaderType::Fragment => "FRAGMENT_SOURCE",
+    ShaderType::Geometry => "GEOMETRY_SOURCE",
+    ShaderType::Compute => "COMPUTE_SOURCE",
+    ShaderType::TessControl => "TESS_CONTROL_SOURCE",
+    ShaderType::TessEvaluation => "TESS_EVALUATION_SOURCE",
+  };
+
+  let hashes = "#".repeat(raw_string_hashes(content));
+  output_file.push_str(&format!(
+    "pub const {}: &str = r{}\"{}\"{};\n",
+    source_name, hashes, content, hashes
+  ));
+
+  let uniforms = rust_struct(
+    &format!("{}Uniforms", output_type_name),
+    &shader_data.uniforms,
+    shader_data,
+    &mut structs,
+  );
+
+  let attributes = match shader_data.shader_type {
+    ShaderType::Vertex => Some(rust_struct(
+      &format!("{}Attributes", output_type_name),
+      &shader_data.ins,
+      shader_data,
+      &mut structs,
+    )),
+    _ => None,
+  };
+
+  for definition in structs {
+    output_file.push('\n');
+    output_file.push_str(&definition);
+  }
+
+  output_file.push('\n');
+  output_file.push_str(&uniforms);
+
+  if let Some(attributes) = attributes {
+    output_file.push('\n');
+    output_file.push_str(&attributes);
+  }
+
+  return output_file;
+}
+
+/// Renders `pub struct <name>` and its `impl Default`. Struct types used by the fields are
+/// rendered into `structs` first so they are declared before being used.
+fn rust_struct(
+  name: &str,
+  variables: &[TypedVariable],
+  shader_data: &ShaderData,
+  structs: &mut Vec<String>,
+) -> String {
+  let fields = variables
+    .iter()
+    .map(|variable| {
+      (
+        variable.identifier.clone(),
+        shader_data.resolve_variable_type(variable),
+        variable.doc_comment.clone(),
+      )
+    })
+    .collect::<Vec<(String, GlslType, Option<String>)>>();
+
+  return render_struct(name, &fields, structs);
+}
+
+/// `fields` are the name, type and doc comment of each field.
+fn render_struct(
+  name: &str,
+  fields: &[(String, GlslType, Option<String>)],
+  structs: &mut Vec<String>,
+) -> String {
+  let mut definition = String::new();
+  let mut defaults = String::new();
+
+  for (identifier, ty, doc_comment) in fields {
+    let (rust_type, default_value) = match rust_field_type(name, identifier, ty, structs) {
+      Some(field_type) => field_type,
+      None => continue,
+    };
+
+    let identifier = rust_identifier(identifier);
+
+    if let Some(doc_comment) = doc_comment {
+      definition.push_str(&format!("  /// {}\n", doc_comment));
+    }
+
+    definition.push_str(&format!("  pub {}: {},\n", identifier, rust_type));
+    defaults.push_str(&format!("      {}: {},\n", identifier, default_value));
+  }
+
+  return format!(
+    r#"#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct {0} {{
+{1}}}
+
+impl Default for {0} {{
+  fn default() -> Self {{
+    Self {{
+{2}    }}
+  }}
+}}
+"#,
+    name, definition, defaults
+  );
+}
+
+/// Rust type and default value of a field, `None` when the type has no Rust equivalent.
+fn rust_field_type(
+  owner: &str,
+  identifier: &str,
+  ty: &GlslType,
+  structs: &mut Vec<String>,
+) -> Option<(String, String)> {
+  let field_type = match ty {
+    GlslType::Struct {
+      name: struct_name,
+      fields: struct_fields,
+    } => {
+      let struct_name = match struct_name {
+        Some(struct_name) => common::capitalize_first_letter(struct_name),
+        None => format!("{}{}", owner, common::capitalize_first_letter(identifier)),
+      };
+
+      let struct_fields = struct_fields
+        .iter()
+        .map(|field| (field.identifier.clone(), field.ty.clone(), None))
+        .collect::<Vec<(String, GlslType, Option<String>)>>();
+
+      let struct_definition = render_struct(&struct_name, &struct_fields, structs);
+
+      if !structs.contains(&struct_definition) {
+        structs.push(struct_definition);
+      }
+
+      (struct_name.clone(), format!("{}::default()", struct_name))
+    }
+    GlslType::Array(element, ArraySize::Sized(size)) => {
+      let (element_type, element_default) = rust_field_type(owner, identifier, element, structs)?;
+      (
+        format!("[{}; {}]", element_type, size),
+        format!("[{}; {}]", element_default, size),
+      )
+    }
+    // Runtime-sized arrays cannot be part of a `Copy` struct
+    GlslType::Array(_, ArraySize::Unsized) => return None,
+    // Samplers are bound to texture units
+    GlslType::Opaque(_) => ("i32".to_string(), "0".to_string()),
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) => {
+      (common::convert_glsl_to_rust_type(ty)?, default_value(ty))
+    }
+    GlslType::Unknown(_) => return None,
+  };
+
+  return Some(field_type);
+}
+
+/// Zero for every type except matrices, which default to the identity.
+fn default_value(ty: &TypeSpecifierNonArray) -> String {
+  let zero = match common::scalar_kind(ty) {
+    Some(common::ScalarKind::Float) => "0.0",
+    Some(common::ScalarKind::Bool) => "false",
+    _ => "0",
+  };
+
+  if let Some((columns, rows)) = common::matrix_dimensions(ty) {
+    let columns = (0..columns)
+      .map(|column| {
+        let values = (0..rows)
+          .map(|row| if row == column { "1.0" } else { "0.0" })
+          .collect::<Vec<&str>>();
+        format!("[{}]", values.join(", "))
+      })
+      .collect::<Vec<String>>();
+
+    return format!("[{}]", columns.join(", "));
+  }
+
+  let count = common::component_count(ty).unwrap_or(1);
+
+  if count == 1 {
+    return zero.to_string();
+  }
+
+  return format!("[{}; {}]", zero, count);
+}
+
+fn rust_identifier(identifier: &str) -> String {
+  if RUST_KEYWORDS.contains(&identifier) {
+    return format!("r#{}", identifier);
+  }
+
+  return identifier.to_string();
+}
+
+/// Number of `#` needed so the raw string literal is not terminated early by the content.
+fn raw_string_hashes(content: &str) -> usize {
+  let mut hashes = 1;
+
+  while content.contains(&format!("\"{}", "#".repeat(hashes))) {
+    hashes += 1;
+  }
+
+  return hashes;
+}
@@ -0,0 +1,56 @@
+use super::common::{self, GeneratorError};
+use super::json;
+use crate::options::Options;
+use crate::utils::json::Json;
+use crate::utils::template;
+use crate::write_file;
+
+/// Renders the `--template` given in the options instead of a built-in backend.
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let (template, extension) = match &options.template {
+    Some(template) => template,
+    None => {
+      return Err(GeneratorError::InvalidTemplate(
+        "no template given".to_string(),
+      ))
+    }
+  };
+
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_file = template::render(
+    template,
+    &context(&source.embedded, output_file_name, &shader_data),
+  )
+  .map_err(GeneratorError::InvalidTemplate)?;
+
+  let output_file_path = output_folder.join(format!("{}.{}", output_file_name, extension));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+/// The `json` backend output with the `name` types are named after and the `fileName` of the
+/// shader without its extension.
+pub fn context(content: &str, output_file_name: &str, shader_data: &common::ShaderData) -> Json {
+  let mut fields = vec![
+    (
+      "name".to_string(),
+      Json::String(common::to_pascal_case(output_file_name)),
+    ),
+    ("fileName".to_string(), Json::string(output_file_name)),
+  ];
+
+  if let Json::Object(shader_fields) = json::shader_json(content, shader_data) {
+    fields.extend(shader_fields);
+  }
+
+  return Json::Object(fields);
+}
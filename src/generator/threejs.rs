@@ -0,0 +1,91 @@
+use super::common::{self, GlslType, ScalarKind, ShaderData};
+use super::webgl_setters;
+use crate::utils::get_shader_type::ShaderType;
+
+/// Lines at the top of a TypeScript file that uses the material factory.
+pub const IMPORTS: &str =
+  "/// <reference types=\"three\" />\nimport * as THREE from \"three\";\n\n";
+
+/// `export function create<Name>Material(uniforms, parameters)`, a Three.js material with the
+/// shader as its stage and the uniforms wrapped as `{ value }` objects. The other stage comes
+/// from `parameters`. `None` for stages other than vertex and fragment, which Three.js
+/// materials do not have.
+pub fn material_factory(type_name: &str, shader_data: &ShaderData) -> Option<String> {
+  let stage = match shader_data.shader_type {
+    ShaderType::Vertex => "vertexShader",
+    ShaderType::Fragment => "fragmentShader",
+    _ => return None,
+  };
+
+  // Texture units in the uniforms interface, Three.js binds textures itself
+  let mut textures = Vec::new();
+
+  let uniforms = shader_data
+    .uniforms
+    .iter()
+    .map(|uniform| {
+      let ty = shader_data.resolve_variable_type(uniform);
+      let value = format!("uniforms.{}", uniform.identifier);
+
+      let value = match &ty {
+        GlslType::Opaque(ty) if webgl_setters::is_sampler(ty) => {
+          textures.push(format!("{}: THREE.Texture", uniform.identifier));
+          value
+        }
+        GlslType::Array(element, _) if is_sampler(element) => {
+          textures.push(format!("{}: THREE.Texture[]", uniform.identifier));
+          value
+        }
+        GlslType::Vector(ty) if common::scalar_kind(ty) == Some(ScalarKind::Float) => format!(
+          "new THREE.Vector{}().fromArray({})",
+          common::component_count(ty).unwrap(),
+          value
+        ),
+        // Both store matrices in column-major order
+        GlslType::Matrix(ty) => match common::matrix_dimensions(ty) {
+          Some((3, 3)) => format!("new THREE.Matrix3().fromArray({})", value),
+          Some((4, 4)) => format!("new THREE.Matrix4().fromArray({})", value),
+          _ => value,
+        },
+        // Three.js also accepts the remaining types as numbers and arrays
+        _ => value,
+      };
+
+      format!("      {}: {{ value: {} }},\n", uniform.identifier, value)
+    })
+    .collect::<String>();
+
+  let uniforms_type = if textures.is_empty() {
+    format!("{}Uniforms", type_name)
+  } else {
+    let names = textures
+      .iter()
+      .map(|texture| format!("\"{}\"", texture.split(':').next().unwrap()))
+      .collect::<Vec<String>>()
+      .join(" | ");
+
+    format!(
+      "Omit<{}Uniforms, {}> & {{ {} }}",
+      type_name,
+      names,
+      textures.join("; ")
+    )
+  };
+
+  // ShaderMaterial prepends its own `#version` and declarations, so shaders that declare a
+  // version are passed unchanged through RawShaderMaterial, a subclass of it
+  let material = if shader_data.glsl_version.is_some() {
+    "RawShaderMaterial"
+  } else {
+    "ShaderMaterial"
+  };
+
+  return Some(format!(
+    "export function create{0}Material(\n  uniforms: {1},\n  parameters: THREE.ShaderMaterialParameters = {{}}\n): THREE.ShaderMaterial {{\n  return new THREE.{2}({{\n    ...parameters,\n    {3}: SOURCE,\n    uniforms: {{\n{4}    }},\n  }});\n}}\n",
+    type_name, uniforms_type, material, stage, uniforms
+  ));
+}
+
+fn is_sampler(ty: &GlslType) -> bool {
+  return matches!(ty, GlslType::Opaque(ty) if webgl_setters::is_sampler(ty));
+}
@@ -0,0 +1,128 @@
+use super::common::{self, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let table_name = format!("{}_uniforms", common::to_snake_case(output_file_name));
+  let uniforms = common::value_uniforms(&shader_data);
+
+  // CloudFormation table resource
+  let mut template_file = String::new();
+  template_file.push_str("# DO NOT EDIT THIS FILE\n");
+  template_file.push_str("# This file is generated by glsl-types\n\n");
+  template_file.push_str(&format!(
+    r#"Resources:
+  {}UniformsTable:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      TableName: {}
+      BillingMode: PAY_PER_REQUEST
+      AttributeDefinitions:
+        - AttributeName: id
+          AttributeType: S
+      KeySchema:
+        - AttributeName: id
+          KeyType: HASH
+"#,
+    output_type_name, table_name
+  ));
+
+  let template_file_path = output_folder.join(format!("{}.dynamodb.yml", output_file_name));
+  write_file(template_file_path.to_str().unwrap(), template_file.as_str());
+
+  // TypeScript command builders
+  let mut item = String::new();
+  let mut from_item = String::new();
+
+  for uniform in &uniforms {
+    let components = common::flatten_components(uniform);
+    let mut values = Vec::new();
+
+    for component in &components {
+      let name = component.camel_case_name();
+
+      match component.kind {
+        ScalarKind::Bool => {
+          item.push_str(&format!(
+            "      {}: {{ BOOL: uniforms.{} }},\n",
+            name,
+            component.accessor()
+          ));
+          values.push(format!("item.{}.BOOL!", name));
+        }
+        _ => {
+          item.push_str(&format!(
+            "      {}: {{ N: uniforms.{}.toString() }},\n",
+            name,
+            component.accessor()
+          ));
+          values.push(format!("Number(item.{}.N)", name));
+        }
+      }
+    }
+
+    let value = if values.len() == 1 {
+      values.remove(0)
+    } else {
+      format!("[{}]", values.join(", "))
+    };
+
+    from_item.push_str(&format!("    {}: {},\n", uniform.identifier, value));
+  }
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str(
+    "import type {\n  AttributeValue,\n  GetItemCommandInput,\n  PutItemCommandInput,\n} from \"@aws-sdk/client-dynamodb\";\n\n",
+  );
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+export const TABLE_NAME = "{1}";
+
+export function buildPutCommand(id: string, uniforms: {0}Uniforms): PutItemCommandInput {{
+  return {{
+    TableName: TABLE_NAME,
+    Item: {{
+      id: {{ S: id }},
+{2}    }},
+  }};
+}}
+
+export function buildGetCommand(id: string): GetItemCommandInput {{
+  return {{
+    TableName: TABLE_NAME,
+    Key: {{
+      id: {{ S: id }},
+    }},
+  }};
+}}
+
+export function fromItem(item: Record<string, AttributeValue>): {0}Uniforms {{
+  return {{
+{3}  }};
+}}
+"#,
+    output_type_name, table_name, item, from_item
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.dynamodb.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
@@ -0,0 +1,111 @@
+use super::common::{self, Component, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let object_type_name = format!("{}Uniforms", output_type_name);
+
+  let components = common::value_uniforms(&shader_data)
+    .into_iter()
+    .flat_map(common::flatten_components)
+    .collect::<Vec<Component>>();
+
+  // EdgeDB schema
+  let properties = components
+    .iter()
+    .map(|component| {
+      format!(
+        "    required property {} -> {};\n",
+        component.snake_case_name(),
+        convert_kind_to_edgedb_type(component.kind)
+      )
+    })
+    .collect::<String>();
+
+  let mut schema_file = String::new();
+  schema_file.push_str("# DO NOT EDIT THIS FILE\n");
+  schema_file.push_str("# This file is generated by glsl-types\n\n");
+  schema_file.push_str(&format!(
+    "module default {{\n  type {} {{\n{}  }}\n}}\n",
+    object_type_name, properties
+  ));
+
+  let schema_file_path = output_folder.join(format!("{}.esdl", output_file_name));
+  write_file(schema_file_path.to_str().unwrap(), schema_file.as_str());
+
+  // TypeScript query builder
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import type { Client } from \"edgedb\";\n");
+  output_file.push_str("import e, { type $infer } from \"./dbschema/edgeql-js\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  let shape = components
+    .iter()
+    .map(|component| format!("  {}: true,\n", component.snake_case_name()))
+    .collect::<String>();
+
+  let insert_values = components
+    .iter()
+    .map(|component| {
+      format!(
+        "      {}: uniforms.{},\n",
+        component.snake_case_name(),
+        component.accessor()
+      )
+    })
+    .collect::<String>();
+
+  output_file.push_str(&format!(
+    r#"
+export const select{0} = e.select(e.{0}, () => ({{
+  id: true,
+{1}}}));
+
+export type {0}Record = $infer<typeof select{0}>[number];
+
+export async function insertUniforms(
+  client: Client,
+  uniforms: {0}
+): Promise<{{ id: string }}> {{
+  return e
+    .insert(e.{0}, {{
+{2}    }})
+    .run(client);
+}}
+
+export async function selectUniforms(client: Client): Promise<{0}Record[]> {{
+  return select{0}.run(client);
+}}
+"#,
+    object_type_name, shape, insert_values
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.edgedb.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+fn convert_kind_to_edgedb_type(kind: ScalarKind) -> &'static str {
+  match kind {
+    ScalarKind::Float => "float64",
+    ScalarKind::Int => "int32",
+    ScalarKind::UInt => "int64",
+    ScalarKind::Bool => "bool",
+  }
+}
@@ -0,0 +1,119 @@
+use super::common::{self, Component, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let collection_name = format!("{}Uniforms", output_type_name);
+
+  let components = common::value_uniforms(&shader_data)
+    .into_iter()
+    .flat_map(common::flatten_components)
+    .collect::<Vec<Component>>();
+
+  // Fauna schema (FSL)
+  let fields = components
+    .iter()
+    .map(|component| {
+      format!(
+        "  {}: {}\n",
+        component.snake_case_name(),
+        convert_kind_to_fauna_type(component.kind)
+      )
+    })
+    .collect::<String>();
+
+  let mut schema_file = String::new();
+  schema_file.push_str(common::GENERATED_FILE_HEADER);
+  schema_file.push_str(&format!(
+    "collection {} {{\n{}}}\n",
+    collection_name, fields
+  ));
+
+  let schema_file_path = output_folder.join(format!("{}.fsl", output_file_name));
+  write_file(schema_file_path.to_str().unwrap(), schema_file.as_str());
+
+  // TypeScript queries
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import { fql, type Client, type Document } from \"fauna\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  let document_fields = components
+    .iter()
+    .map(|component| {
+      format!(
+        "  {}: {};\n",
+        component.snake_case_name(),
+        match component.kind {
+          ScalarKind::Bool => "boolean",
+          _ => "number",
+        }
+      )
+    })
+    .collect::<String>();
+
+  let create_values = components
+    .iter()
+    .map(|component| {
+      format!(
+        "{}: ${{data.{}}}",
+        component.snake_case_name(),
+        component.accessor()
+      )
+    })
+    .collect::<Vec<String>>()
+    .join(", ");
+
+  output_file.push_str(&format!(
+    r#"
+export type {0}Document = Document & {{
+{1}}};
+
+export const createUniforms = (data: {0}) =>
+  fql`{0}.create({{ {2} }})`;
+
+export const getUniforms = (id: string) => fql`{0}.byId(${{id}})`;
+
+export const allUniforms = () => fql`{0}.all()`;
+
+export async function createUniformSet(client: Client, data: {0}): Promise<{0}Document> {{
+  const response = await client.query<{0}Document>(createUniforms(data));
+  return response.data;
+}}
+
+export async function getUniformSet(client: Client, id: string): Promise<{0}Document | null> {{
+  const response = await client.query<{0}Document | null>(getUniforms(id));
+  return response.data;
+}}
+"#,
+    collection_name, document_fields, create_values
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.fauna.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+fn convert_kind_to_fauna_type(kind: ScalarKind) -> &'static str {
+  match kind {
+    ScalarKind::Float => "Double",
+    ScalarKind::Int => "Int",
+    ScalarKind::UInt => "Long",
+    ScalarKind::Bool => "Boolean",
+  }
+}
@@ -0,0 +1,164 @@
+use super::common::{self, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let package_name = common::to_snake_case(output_file_name);
+  let uniforms = common::value_uniforms(&shader_data);
+
+  let mut proto_fields = String::new();
+  let mut message_fields = String::new();
+  let mut to_proto = String::new();
+  let mut from_proto = String::new();
+
+  for (index, uniform) in uniforms.iter().enumerate() {
+    let proto_type = match common::scalar_kind(&uniform.type_label) {
+      Some(ScalarKind::Float) => "float",
+      Some(ScalarKind::Int) => "int32",
+      Some(ScalarKind::UInt) => "uint32",
+      Some(ScalarKind::Bool) | None => "bool",
+    };
+
+    let ts_type = match common::scalar_kind(&uniform.type_label) {
+      Some(ScalarKind::Bool) => "boolean",
+      _ => "number",
+    };
+
+    let repeated = common::component_count(&uniform.type_label) != Some(1);
+
+    if repeated {
+      proto_fields.push_str(&format!(
+        "  repeated {} {} = {};\n",
+        proto_type,
+        uniform.identifier,
+        index + 1
+      ));
+      message_fields.push_str(&format!("  {}: {}[];\n", uniform.identifier, ts_type));
+      to_proto.push_str(&format!(
+        "    {0}: [...uniforms.{0}],\n",
+        uniform.identifier
+      ));
+      from_proto.push_str(&format!(
+        "    {0}: [...message.{0}] as {1}Uniforms[\"{0}\"],\n",
+        uniform.identifier, output_type_name
+      ));
+    } else {
+      proto_fields.push_str(&format!(
+        "  {} {} = {};\n",
+        proto_type,
+        uniform.identifier,
+        index + 1
+      ));
+      message_fields.push_str(&format!("  {}: {};\n", uniform.identifier, ts_type));
+      to_proto.push_str(&format!("    {0}: uniforms.{0},\n", uniform.identifier));
+      from_proto.push_str(&format!("    {0}: message.{0},\n", uniform.identifier));
+    }
+  }
+
+  // Protocol buffers service definition
+  let mut proto_file = String::new();
+  proto_file.push_str(common::GENERATED_FILE_HEADER);
+  proto_file.push_str(&format!(
+    r#"syntax = "proto3";
+
+package {1};
+
+message {0}UniformsProto {{
+{2}}}
+
+message SetUniformsRequest {{
+{2}}}
+
+message SetUniformsResponse {{}}
+
+message GetUniformsRequest {{}}
+
+service {0}ShaderService {{
+  rpc SetUniforms (SetUniformsRequest) returns (SetUniformsResponse);
+  rpc GetUniforms (GetUniformsRequest) returns ({0}UniformsProto);
+}}
+"#,
+    output_type_name, package_name, proto_fields
+  ));
+
+  let proto_file_path = output_folder.join(format!("{}.proto", output_file_name));
+  write_file(proto_file_path.to_str().unwrap(), proto_file.as_str());
+
+  // TypeScript stubs
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+export interface {0}UniformsProto {{
+{2}}}
+
+export interface SetUniformsRequest {{
+{2}}}
+
+export interface SetUniformsResponse {{}}
+
+export interface GetUniformsRequest {{}}
+
+export const {0}ShaderServiceName = "{1}.{0}ShaderService";
+
+export interface {0}ShaderService {{
+  SetUniforms(request: SetUniformsRequest): Promise<SetUniformsResponse>;
+  GetUniforms(request: GetUniformsRequest): Promise<{0}UniformsProto>;
+}}
+
+export interface Rpc {{
+  request<Request, Response>(service: string, method: string, request: Request): Promise<Response>;
+}}
+
+export class {0}ShaderServiceClientImpl implements {0}ShaderService {{
+  private readonly rpc: Rpc;
+  private readonly service: string;
+
+  constructor(rpc: Rpc, options?: {{ service?: string }}) {{
+    this.rpc = rpc;
+    this.service = options?.service ?? {0}ShaderServiceName;
+  }}
+
+  SetUniforms(request: SetUniformsRequest): Promise<SetUniformsResponse> {{
+    return this.rpc.request(this.service, "SetUniforms", request);
+  }}
+
+  GetUniforms(request: GetUniformsRequest): Promise<{0}UniformsProto> {{
+    return this.rpc.request(this.service, "GetUniforms", request);
+  }}
+}}
+
+export function toUniformsProto(uniforms: {0}Uniforms): {0}UniformsProto {{
+  return {{
+{3}  }};
+}}
+
+export function fromUniformsProto(message: {0}UniformsProto): {0}Uniforms {{
+  return {{
+{4}  }};
+}}
+"#,
+    output_type_name, package_name, message_fields, to_proto, from_proto
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.grpc.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
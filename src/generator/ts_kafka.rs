@@ -0,0 +1,105 @@
+use super::common::{self, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let fields = common::value_uniforms(&shader_data)
+    .iter()
+    .map(|uniform| {
+      let avro_type = match common::scalar_kind(&uniform.type_label) {
+        Some(ScalarKind::Float) => "double",
+        Some(ScalarKind::Int) => "int",
+        Some(ScalarKind::UInt) => "long",
+        Some(ScalarKind::Bool) => "boolean",
+        None => "null",
+      };
+
+      let field_type = match common::component_count(&uniform.type_label) {
+        Some(1) | None => format!("\"{}\"", avro_type),
+        Some(count) => format!(
+          "{{ type: \"array\", items: \"{}\", size: {} }}",
+          avro_type, count
+        ),
+      };
+
+      format!(
+        "    {{ name: \"{}\", type: {} }},\n",
+        uniform.identifier, field_type
+      )
+    })
+    .collect::<String>();
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import type { Schema } from \"avsc\";\n");
+  output_file
+    .push_str("import type { Consumer, EachMessagePayload, Producer } from \"kafkajs\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+// Avro schema for the Confluent Schema Registry
+export const {0}UniformsSchema: Schema = {{
+  type: "record",
+  name: "{0}Uniforms",
+  fields: [
+{1}  ],
+}};
+
+export type UniformsEncoder = (uniforms: {0}Uniforms) => Promise<Buffer> | Buffer;
+export type UniformsDecoder = (value: Buffer) => Promise<{0}Uniforms> | {0}Uniforms;
+
+const jsonEncoder: UniformsEncoder = (uniforms) => Buffer.from(JSON.stringify(uniforms));
+const jsonDecoder: UniformsDecoder = (value) => JSON.parse(value.toString()) as {0}Uniforms;
+
+export async function sendUniforms(
+  producer: Producer,
+  topic: string,
+  uniforms: {0}Uniforms,
+  encode: UniformsEncoder = jsonEncoder
+): Promise<void> {{
+  await producer.send({{
+    topic,
+    messages: [{{ value: await encode(uniforms) }}],
+  }});
+}}
+
+export async function runUniformsConsumer(
+  consumer: Consumer,
+  handler: (uniforms: {0}Uniforms, payload: EachMessagePayload) => Promise<void> | void,
+  decode: UniformsDecoder = jsonDecoder
+): Promise<void> {{
+  await consumer.run({{
+    eachMessage: async (payload) => {{
+      if (!payload.message.value) {{
+        return;
+      }}
+
+      await handler(await decode(payload.message.value), payload);
+    }},
+  }});
+}}
+"#,
+    output_type_name, fields
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.kafka.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
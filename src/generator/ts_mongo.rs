@@ -0,0 +1,98 @@
+use super::common::{self, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let uniforms = common::value_uniforms(&shader_data);
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import { Schema, model, type Document } from \"mongoose\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  let mut document_fields = String::new();
+  let mut schema_fields = String::new();
+  let mut to_document = String::new();
+  let mut from_document = String::new();
+
+  for uniform in &uniforms {
+    let components = common::flatten_components(uniform);
+
+    for component in &components {
+      let (ts_type, mongoose_type) = match component.kind {
+        ScalarKind::Bool => ("boolean", "Boolean"),
+        _ => ("number", "Number"),
+      };
+
+      document_fields.push_str(&format!(
+        "  {}: {};\n",
+        component.camel_case_name(),
+        ts_type
+      ));
+      schema_fields.push_str(&format!(
+        "  {}: {{ type: {}, required: true }},\n",
+        component.camel_case_name(),
+        mongoose_type
+      ));
+      to_document.push_str(&format!(
+        "    {}: uniforms.{},\n",
+        component.camel_case_name(),
+        component.accessor()
+      ));
+    }
+
+    let value = if components.len() == 1 {
+      format!("document.{}", components[0].camel_case_name())
+    } else {
+      let values = components
+        .iter()
+        .map(|component| format!("document.{}", component.camel_case_name()))
+        .collect::<Vec<String>>();
+      format!("[{}]", values.join(", "))
+    };
+
+    from_document.push_str(&format!("    {}: {},\n", uniform.identifier, value));
+  }
+
+  output_file.push_str(&format!(
+    r#"
+export interface {0}UniformsDocument extends Document {{
+{1}}}
+
+const {0}UniformsSchema = new Schema<{0}UniformsDocument>({{
+{2}}});
+
+export const {0}UniformsModel = model<{0}UniformsDocument>("{0}Uniforms", {0}UniformsSchema);
+
+export function toUniformsDocument(uniforms: {0}Uniforms) {{
+  return new {0}UniformsModel({{
+{3}  }});
+}}
+
+export function fromUniformsDocument(document: {0}UniformsDocument): {0}Uniforms {{
+  return {{
+{4}  }};
+}}
+"#,
+    output_type_name, document_fields, schema_fields, to_document, from_document
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.mongo.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
@@ -0,0 +1,76 @@
+use super::common::{self, GeneratorError};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import type { MqttClient } from \"mqtt\";\n");
+  output_file.push_str("import { z } from \"zod\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+  output_file.push('\n');
+  output_file.push_str(&type_script::zod_schema_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+export function publishUniforms(
+  client: MqttClient,
+  topic: string,
+  uniforms: {0}Uniforms
+): void {{
+  client.publish(topic, JSON.stringify(uniforms));
+}}
+
+export function subscribeToUniforms(
+  client: MqttClient,
+  topic: string,
+  callback: (uniforms: {0}Uniforms) => void
+): void {{
+  client.subscribe(topic);
+  client.on("message", (receivedTopic, payload) => {{
+    if (receivedTopic !== topic) {{
+      return;
+    }}
+
+    let message: unknown;
+
+    try {{
+      message = JSON.parse(payload.toString());
+    }} catch {{
+      return;
+    }}
+
+    const result = {0}UniformsSchema.safeParse(message);
+
+    if (result.success) {{
+      callback(result.data);
+    }}
+  }});
+}}
+"#,
+    output_type_name
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.mqtt.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
@@ -0,0 +1,148 @@
+use super::common::{self, Component, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let table_name = format!("{}_uniforms", common::to_snake_case(output_file_name));
+
+  let components = common::value_uniforms(&shader_data)
+    .into_iter()
+    .flat_map(common::flatten_components)
+    .collect::<Vec<Component>>();
+
+  let columns = components
+    .iter()
+    .map(|component| component.snake_case_name())
+    .collect::<Vec<String>>();
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import type { NeonQueryFunction } from \"@neondatabase/serverless\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  let row_fields = components
+    .iter()
+    .map(|component| {
+      format!(
+        "  {}: {};\n",
+        component.snake_case_name(),
+        convert_kind_to_ts_type(component.kind)
+      )
+    })
+    .collect::<Vec<String>>();
+
+  output_file.push_str(&format!(
+    "\nexport type {}UniformsRow = {{\n  id: number;\n{}}};\n",
+    output_type_name,
+    row_fields.join("")
+  ));
+
+  let column_definitions = components
+    .iter()
+    .map(|component| {
+      format!(
+        "  {} {} NOT NULL",
+        component.snake_case_name(),
+        convert_kind_to_sql_type(component.kind)
+      )
+    })
+    .collect::<Vec<String>>();
+
+  output_file.push_str(&format!(
+    r#"
+export const CREATE_TABLE_SQL = /* sql */ `CREATE TABLE IF NOT EXISTS {} (
+  id SERIAL PRIMARY KEY{}
+);`;
+"#,
+    table_name,
+    column_definitions
+      .iter()
+      .map(|definition| format!(",\n{}", definition))
+      .collect::<String>()
+  ));
+
+  let values = components
+    .iter()
+    .map(|component| format!("${{uniforms.{}}}", component.accessor()))
+    .collect::<Vec<String>>();
+
+  output_file.push_str(&format!(
+    r#"
+export async function insertUniforms(
+  sql: NeonQueryFunction<false, false>,
+  uniforms: {}Uniforms
+): Promise<void> {{
+  await sql`INSERT INTO {} ({}) VALUES ({})`;
+}}
+"#,
+    output_type_name,
+    table_name,
+    columns.join(", "),
+    values.join(", ")
+  ));
+
+  let mut selected_columns = vec!["id".to_string()];
+  selected_columns.extend(columns);
+
+  output_file.push_str(&format!(
+    r#"
+export async function selectUniforms(
+  sql: NeonQueryFunction<false, false>
+): Promise<{}UniformsRow[]> {{
+  const rows = await sql`SELECT {} FROM {}`;
+  return rows as {}UniformsRow[];
+}}
+
+export async function selectUniformsById(
+  sql: NeonQueryFunction<false, false>,
+  id: number
+): Promise<{}UniformsRow | undefined> {{
+  const rows = await sql`SELECT {} FROM {} WHERE id = ${{id}}`;
+  return rows[0] as {}UniformsRow | undefined;
+}}
+"#,
+    output_type_name,
+    selected_columns.join(", "),
+    table_name,
+    output_type_name,
+    output_type_name,
+    selected_columns.join(", "),
+    table_name,
+    output_type_name,
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.neon.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+fn convert_kind_to_ts_type(kind: ScalarKind) -> &'static str {
+  match kind {
+    ScalarKind::Bool => "boolean",
+    _ => "number",
+  }
+}
+
+fn convert_kind_to_sql_type(kind: ScalarKind) -> &'static str {
+  match kind {
+    ScalarKind::Float => "DOUBLE PRECISION",
+    ScalarKind::Int => "INTEGER",
+    ScalarKind::UInt => "BIGINT",
+    ScalarKind::Bool => "BOOLEAN",
+  }
+}
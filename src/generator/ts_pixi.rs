@@ -0,0 +1,140 @@
+use super::common::{self, ArraySize, GeneratorError, ScalarKind, TypedVariable};
+use super::type_script;
+use crate::options::Options;
+use crate::utils::get_shader_type::ShaderType;
+use crate::write_file;
+use glsl::syntax::TypeSpecifierNonArray;
+
+// Members of `PIXI.Filter` that a generated accessor would clash with; these uniforms are
+// still reachable through `filter.uniforms`.
+const FILTER_MEMBERS: [&str; 10] = [
+  "autoFit",
+  "blendMode",
+  "enabled",
+  "legacy",
+  "multisample",
+  "padding",
+  "program",
+  "resolution",
+  "state",
+  "uniforms",
+];
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type.clone())?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let mut defaults = String::new();
+  let mut accessors = String::new();
+
+  for uniform in &shader_data.uniforms {
+    let (ts_type, default_value) = match convert_glsl_to_pixi_type(uniform) {
+      Some(pixi_type) => pixi_type,
+      None => continue,
+    };
+
+    defaults.push_str(&format!(
+      "      {}: {},\n",
+      uniform.identifier, default_value
+    ));
+
+    if FILTER_MEMBERS.contains(&uniform.identifier.as_str()) {
+      continue;
+    }
+
+    accessors.push_str(&format!(
+      r#"
+{2}  get {0}(): {1} {{
+    return this.uniforms.{0};
+  }}
+
+  set {0}(value: {1}) {{
+    this.uniforms.{0} = value;
+  }}
+"#,
+      uniform.identifier,
+      ts_type,
+      type_script::js_doc(&uniform.doc_comment, "  ")
+    ));
+  }
+
+  let (vertex_source, fragment_source) = match shader_type {
+    ShaderType::Vertex => ("SOURCE", "undefined"),
+    ShaderType::Fragment => ("undefined", "SOURCE"),
+    _ => return Err(GeneratorError::UnsupportedShaderStage(shader_type)),
+  };
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import * as PIXI from \"pixi.js\";\n\n");
+  output_file.push_str(&format!(
+    "const SOURCE = /* glsl */ `{}`;\n",
+    &source.embedded
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+export class {0}Filter extends PIXI.Filter {{
+  constructor() {{
+    super({1}, {2}, {{
+{3}    }});
+  }}
+{4}}}
+"#,
+    output_type_name, vertex_source, fragment_source, defaults, accessors
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.pixi.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+/// TypeScript type accepted by PixiJS for a uniform and the value it starts with.
+/// Arrays are uploaded as a single flat typed array.
+fn convert_glsl_to_pixi_type(uniform: &TypedVariable) -> Option<(String, String)> {
+  let ty = &uniform.type_label;
+
+  let length = match uniform.array_size {
+    None => 1,
+    Some(ArraySize::Sized(length)) => length,
+    Some(ArraySize::Unsized) => return None,
+  };
+
+  if *ty == TypeSpecifierNonArray::Sampler2D && uniform.array_size.is_none() {
+    return Some(("PIXI.Texture".to_string(), "PIXI.Texture.EMPTY".to_string()));
+  }
+
+  let kind = common::scalar_kind(ty)?;
+  let count = common::component_count(ty)? * length;
+
+  let result = match (kind, count) {
+    (ScalarKind::Bool, 1) if length == 1 => ("boolean".to_string(), "false".to_string()),
+    (_, 1) if length == 1 => ("number".to_string(), "0".to_string()),
+    (ScalarKind::Float, 2) if *ty == TypeSpecifierNonArray::Vec2 && length == 1 => {
+      ("PIXI.Point".to_string(), "new PIXI.Point(0, 0)".to_string())
+    }
+    (ScalarKind::Float, count) => (
+      "Float32Array".to_string(),
+      format!("new Float32Array({})", count),
+    ),
+    (ScalarKind::UInt, count) => (
+      "Uint32Array".to_string(),
+      format!("new Uint32Array({})", count),
+    ),
+    (ScalarKind::Int | ScalarKind::Bool, count) => (
+      "Int32Array".to_string(),
+      format!("new Int32Array({})", count),
+    ),
+  };
+
+  return Some(result);
+}
@@ -0,0 +1,75 @@
+use super::common::{self, GeneratorError};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import type { RedisClientType } from \"@redis/client\";\n");
+  output_file.push_str("import type { RedisJSON } from \"@redis/json/dist/commands\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+// Fails to compile if the uniforms can not be stored as a RedisJSON document
+type AssertRedisJSON<T extends RedisJSON> = T;
+export type {0}UniformsJSON = AssertRedisJSON<{0}Uniforms>;
+
+export async function saveUniforms(
+  client: RedisClientType,
+  key: string,
+  uniforms: {0}Uniforms
+): Promise<void> {{
+  await client.json.set(key, "$", uniforms as unknown as RedisJSON);
+}}
+
+export async function saveUniform<K extends keyof {0}Uniforms>(
+  client: RedisClientType,
+  key: string,
+  name: K,
+  value: {0}Uniforms[K]
+): Promise<void> {{
+  await client.json.set(key, `$.${{String(name)}}`, value as unknown as RedisJSON);
+}}
+
+export async function getUniforms(
+  client: RedisClientType,
+  key: string
+): Promise<{0}Uniforms | null> {{
+  const value = await client.json.get(key);
+  return value as unknown as {0}Uniforms | null;
+}}
+
+export async function mGetUniforms(
+  client: RedisClientType,
+  keys: string[]
+): Promise<({0}Uniforms | null)[]> {{
+  const values = await client.json.mGet(keys, "$");
+  return values.map((value) =>
+    Array.isArray(value) && value.length > 0 ? (value[0] as unknown as {0}Uniforms) : null
+  );
+}}
+"#,
+    output_type_name
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.redis.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
@@ -0,0 +1,154 @@
+use super::common::{self, GeneratorError, ScalarKind};
+use super::layout;
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let file_name = file_path.file_name().unwrap().to_str().unwrap();
+
+  let mut accessors = String::new();
+  let mut snapshot = String::new();
+  let mut index = 0;
+
+  for uniform in layout::buffer_uniforms(&shader_data, file_name, "ts-shared-arraybuffer") {
+    let ts_type = type_script::convert_glsl_to_ts_value_type(&uniform.type_label);
+    let components = common::flatten_components(uniform);
+
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+    let mut parameters = Vec::new();
+
+    for (component_index, component) in components.iter().enumerate() {
+      let parameter = if components.len() == 1 {
+        "value".to_string()
+      } else {
+        format!("v{}", component_index)
+      };
+
+      let (read, write) = match component.kind {
+        ScalarKind::Float => (
+          format!("bitsToFloat(Atomics.load(this.view, {}))", index),
+          format!(
+            "Atomics.store(this.view, {}, floatToBits({}));",
+            index, parameter
+          ),
+        ),
+        ScalarKind::Int => (
+          format!("Atomics.load(this.view, {})", index),
+          format!("Atomics.store(this.view, {}, {});", index, parameter),
+        ),
+        ScalarKind::UInt => (
+          format!("Atomics.load(this.view, {}) >>> 0", index),
+          format!("Atomics.store(this.view, {}, {} | 0);", index, parameter),
+        ),
+        ScalarKind::Bool => (
+          format!("Atomics.load(this.view, {}) !== 0", index),
+          format!(
+            "Atomics.store(this.view, {}, {} ? 1 : 0);",
+            index, parameter
+          ),
+        ),
+      };
+
+      reads.push(read);
+      writes.push(format!("    {}\n", write));
+      parameters.push(parameter);
+      index += 1;
+    }
+
+    let (read, parameter) = if components.len() == 1 {
+      (reads.remove(0), parameters.remove(0))
+    } else {
+      (
+        format!("[{}]", reads.join(", ")),
+        format!("[{}]", parameters.join(", ")),
+      )
+    };
+
+    accessors.push_str(&format!(
+      r#"
+  get {0}(): {1} {{
+    return {2};
+  }}
+
+  set {0}({3}: {1}) {{
+{4}  }}
+"#,
+      uniform.identifier,
+      ts_type,
+      read,
+      parameter,
+      writes.join("")
+    ));
+
+    snapshot.push_str(&format!("      {0}: this.{0},\n", uniform.identifier));
+  }
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+// Atomics only operate on integer arrays, so floats are stored as their bit pattern
+const scratch = new ArrayBuffer(4);
+const scratchFloat = new Float32Array(scratch);
+const scratchInt = new Int32Array(scratch);
+
+function floatToBits(value: number): number {{
+  scratchFloat[0] = value;
+  return scratchInt[0];
+}}
+
+function bitsToFloat(bits: number): number {{
+  scratchInt[0] = bits;
+  return scratchFloat[0];
+}}
+
+export class {0}SharedUniforms {{
+  static readonly BYTE_LENGTH = {1};
+
+  readonly buffer: SharedArrayBuffer;
+  private readonly view: Int32Array;
+
+  constructor(buffer: SharedArrayBuffer = new SharedArrayBuffer({0}SharedUniforms.BYTE_LENGTH)) {{
+    if (buffer.byteLength < {0}SharedUniforms.BYTE_LENGTH) {{
+      throw new RangeError("SharedArrayBuffer is too small for {0}SharedUniforms");
+    }}
+
+    this.buffer = buffer;
+    this.view = new Int32Array(buffer, 0, {2});
+  }}
+{3}
+  snapshot(): {0}Uniforms {{
+    return {{
+{4}    }};
+  }}
+}}
+"#,
+    output_type_name,
+    index * 4,
+    index,
+    accessors,
+    snapshot
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.shared.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
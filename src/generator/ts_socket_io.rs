@@ -0,0 +1,65 @@
+use super::common::{self, GeneratorError};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import type { Namespace, Server } from \"socket.io\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+export interface ServerToClientEvents {{
+  uniformsUpdated: (uniforms: {0}Uniforms) => void;
+}}
+
+export interface ClientToServerEvents {{
+  setUniform: <K extends keyof {0}Uniforms>(name: K, value: {0}Uniforms[K]) => void;
+}}
+
+export type {0}Namespace = Namespace<ClientToServerEvents, ServerToClientEvents>;
+
+export function createShaderNamespace(
+  io: Server,
+  initialUniforms: {0}Uniforms,
+  name: string = "/{1}"
+): {0}Namespace {{
+  const namespace: {0}Namespace = io.of(name);
+  let uniforms = {{ ...initialUniforms }};
+
+  namespace.on("connection", (socket) => {{
+    socket.emit("uniformsUpdated", uniforms);
+
+    socket.on("setUniform", (uniformName, value) => {{
+      uniforms = {{ ...uniforms, [uniformName]: value }};
+      namespace.emit("uniformsUpdated", uniforms);
+    }});
+  }});
+
+  return namespace;
+}}
+"#,
+    output_type_name, output_file_name
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.socket-io.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
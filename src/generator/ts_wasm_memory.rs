@@ -0,0 +1,173 @@
+use super::common::{self, GeneratorError, ScalarKind};
+use super::layout::{self, LayoutRules};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let file_name = file_path.file_name().unwrap().to_str().unwrap();
+
+  let uniforms = layout::buffer_uniforms(&shader_data, file_name, "ts-wasm-memory");
+  let uniforms_layout = layout::compute_layout(&uniforms, LayoutRules::Std140);
+
+  let mut accessors = String::new();
+  let mut rust_fields = String::new();
+  let mut rust_offset = 0;
+
+  for (uniform, field) in uniforms.iter().zip(&uniforms_layout.fields) {
+    let kind = common::scalar_kind(&uniform.type_label).unwrap();
+    let ts_type = type_script::convert_glsl_to_ts_value_type(&uniform.type_label);
+
+    let (getter, setter) = match kind {
+      ScalarKind::Float => ("getFloat32", "setFloat32"),
+      ScalarKind::Int => ("getInt32", "setInt32"),
+      ScalarKind::UInt | ScalarKind::Bool => ("getUint32", "setUint32"),
+    };
+
+    let reads = field
+      .component_offsets
+      .iter()
+      .map(|offset| {
+        let read = format!("view.{}({}, true)", getter, field.offset + offset);
+
+        match kind {
+          ScalarKind::Bool => format!("{} !== 0", read),
+          _ => read,
+        }
+      })
+      .collect::<Vec<String>>();
+
+    let single = reads.len() == 1;
+
+    let writes = field
+      .component_offsets
+      .iter()
+      .enumerate()
+      .map(|(index, offset)| {
+        let value = if single {
+          "value".to_string()
+        } else {
+          format!("value[{}]", index)
+        };
+
+        let value = match kind {
+          ScalarKind::Bool => format!("{} ? 1 : 0", value),
+          _ => value,
+        };
+
+        format!(
+          "    view.{}({}, {}, true);\n",
+          setter,
+          field.offset + offset,
+          value
+        )
+      })
+      .collect::<String>();
+
+    let read = if single {
+      reads[0].clone()
+    } else {
+      format!("[{}]", reads.join(", "))
+    };
+
+    accessors.push_str(&format!(
+      r#"
+  get {0}(): {1} {{
+    const view = this.view();
+    return {2};
+  }}
+
+  set {0}(value: {1}) {{
+    const view = this.view();
+{3}  }}
+"#,
+      uniform.identifier, ts_type, read, writes
+    ));
+
+    // Rust mirror of the same layout, padded explicitly so it can live in linear memory as is
+    if field.offset > rust_offset {
+      rust_fields.push_str(&format!(
+        "  _padding_{}: [u8; {}],\n",
+        rust_offset,
+        field.offset - rust_offset
+      ));
+    }
+
+    let rust_scalar = match kind {
+      ScalarKind::Float => "f32",
+      ScalarKind::Int => "i32",
+      ScalarKind::UInt | ScalarKind::Bool => "u32",
+    };
+
+    let rust_type = match common::matrix_dimensions(&uniform.type_label) {
+      Some((columns, rows)) => format!(
+        "[[{}; {}]; {}]",
+        rust_scalar,
+        layout::matrix_column_stride(rows, LayoutRules::Std140) / 4,
+        columns
+      ),
+      None if single => rust_scalar.to_string(),
+      None => format!("[{}; {}]", rust_scalar, reads.len()),
+    };
+
+    rust_fields.push_str(&format!("  pub {}: {},\n", uniform.identifier, rust_type));
+    rust_offset = field.offset + field.size;
+  }
+
+  if uniforms_layout.size > rust_offset {
+    rust_fields.push_str(&format!(
+      "  _padding_{}: [u8; {}],\n",
+      rust_offset,
+      uniforms_layout.size - rust_offset
+    ));
+  }
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+// Offsets follow the std140 layout rules
+export class {0}WasmUniforms {{
+  static readonly BYTE_LENGTH = {1};
+
+  constructor(private memory: WebAssembly.Memory, private offset: number) {{}}
+
+  // The buffer is replaced whenever the memory grows, so the view is created on every access
+  private view(): DataView {{
+    return new DataView(this.memory.buffer, this.offset, {0}WasmUniforms.BYTE_LENGTH);
+  }}
+{2}}}
+"#,
+    output_type_name, uniforms_layout.size, accessors
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.wasm.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  let mut rust_file = String::new();
+  rust_file.push_str(common::GENERATED_FILE_HEADER);
+  rust_file.push_str(&format!(
+    "/// std140 layout of the `{0}` uniforms, matching `{1}WasmUniforms` on the JavaScript side.\n#[repr(C)]\n#[derive(Clone, Copy, Debug, Default)]\npub struct {1}Uniforms {{\n{2}}}\n\nconst _: () = assert!(std::mem::size_of::<{1}Uniforms>() == {3});\n",
+    output_file_name, output_type_name, rust_fields, uniforms_layout.size
+  ));
+
+  let rust_file_path = output_folder.join(format!("{}.wasm.rs", output_file_name));
+  write_file(rust_file_path.to_str().unwrap(), rust_file.as_str());
+
+  return Ok(());
+}
@@ -0,0 +1,111 @@
+use super::common::{self, GeneratorError, ScalarKind};
+use super::layout::{self, LayoutRules};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let file_name = file_path.file_name().unwrap().to_str().unwrap();
+
+  let uniforms = layout::buffer_uniforms(&shader_data, file_name, "ts-webgpu-buffer");
+  let uniforms_layout = layout::compute_layout(&uniforms, LayoutRules::Std430);
+
+  let mut writes = String::new();
+  let mut used_views = Vec::new();
+
+  for (uniform, field) in uniforms.iter().zip(&uniforms_layout.fields) {
+    let kind = common::scalar_kind(&uniform.type_label).unwrap();
+    let single = field.component_offsets.len() == 1;
+
+    let view = match kind {
+      ScalarKind::Float => "floats",
+      ScalarKind::Int => "ints",
+      ScalarKind::UInt | ScalarKind::Bool => "uints",
+    };
+
+    if !used_views.contains(&view) {
+      used_views.push(view);
+    }
+
+    for (index, offset) in field.component_offsets.iter().enumerate() {
+      let value = if single {
+        format!("uniforms.{}", uniform.identifier)
+      } else {
+        format!("uniforms.{}[{}]", uniform.identifier, index)
+      };
+
+      let value = match kind {
+        ScalarKind::Bool => format!("{} ? 1 : 0", value),
+        _ => value,
+      };
+
+      writes.push_str(&format!(
+        "  {}[{}] = {};\n",
+        view,
+        (field.offset + offset) / 4,
+        value
+      ));
+    }
+  }
+
+  let views = used_views
+    .iter()
+    .map(|view| {
+      let array = match *view {
+        "floats" => "Float32Array",
+        "ints" => "Int32Array",
+        _ => "Uint32Array",
+      };
+
+      format!("  const {} = new {}(data);\n", view, array)
+    })
+    .collect::<String>();
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+// Offsets follow the std430 layout rules
+export const BUFFER_SIZE = {1} * 4;
+
+export function createUniformBuffer(device: GPUDevice): GPUBuffer {{
+  return device.createBuffer({{
+    label: "{2} uniforms",
+    size: BUFFER_SIZE,
+    usage: GPUBufferUsage.UNIFORM | GPUBufferUsage.COPY_DST,
+  }});
+}}
+
+export function writeUniforms(device: GPUDevice, buffer: GPUBuffer, uniforms: {0}Uniforms): void {{
+  const data = new ArrayBuffer(BUFFER_SIZE);
+{3}{4}  device.queue.writeBuffer(buffer, 0, data);
+}}
+"#,
+    output_type_name,
+    // Uniform buffer bindings are sized in multiples of 16 bytes
+    uniforms_layout.size.div_ceil(16) * 4,
+    output_file_name,
+    views,
+    writes
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.webgpu.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
@@ -0,0 +1,132 @@
+use super::common::{self, GeneratorError};
+use crate::options::Options;
+use crate::utils::get_shader_type::ShaderType;
+use crate::write_file;
+use glsl::syntax::TypeSpecifierNonArray;
+use std::collections::BTreeMap;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let visibility = match shader_data.shader_type {
+    ShaderType::Vertex => "GPUShaderStage.VERTEX",
+    ShaderType::Fragment => "GPUShaderStage.FRAGMENT",
+    ShaderType::Compute => "GPUShaderStage.COMPUTE",
+    // WebGPU has no geometry or tessellation stages
+    _ => {
+      return Err(GeneratorError::UnsupportedShaderStage(
+        shader_data.shader_type,
+      ))
+    }
+  };
+
+  // set -> binding -> resource layout
+  let mut groups: BTreeMap<usize, BTreeMap<usize, String>> = BTreeMap::new();
+
+  for block in &shader_data.uniform_blocks {
+    if let Some(binding) = block.binding {
+      groups
+        .entry(block.set.unwrap_or(0))
+        .or_default()
+        .insert(binding, "buffer: { type: \"uniform\" }".to_string());
+    }
+  }
+
+  for buffer in &shader_data.buffers {
+    if let Some(binding) = buffer.binding {
+      groups
+        .entry(buffer.set.unwrap_or(0))
+        .or_default()
+        .insert(binding, "buffer: { type: \"storage\" }".to_string());
+    }
+  }
+
+  for uniform in &shader_data.uniforms {
+    if let (Some(binding), Some(texture)) = (uniform.binding, texture_layout(&uniform.type_label)) {
+      groups
+        .entry(uniform.set.unwrap_or(0))
+        .or_default()
+        .insert(binding, texture);
+    }
+  }
+
+  let groups = groups
+    .iter()
+    .map(|(set, entries)| {
+      let entries = entries
+        .iter()
+        .map(|(binding, resource)| {
+          format!(
+            "      {{\n        binding: {},\n        visibility: {},\n        {},\n      }},\n",
+            binding, visibility, resource
+          )
+        })
+        .collect::<String>();
+
+      format!("  {}: {{\n    entries: [\n{}    ],\n  }},\n", set, entries)
+    })
+    .collect::<String>();
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str(&format!(
+    "// GPUBindGroupLayoutDescriptor of every descriptor set, keyed by set\nexport const {}BindGroupLayouts = {{\n{}}};\n",
+    output_type_name, groups
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), &output_file);
+
+  return Ok(());
+}
+
+/// `texture: { ... }` layout of a combined image sampler. WebGPU binds the sampler itself
+/// separately, so only the texture half is described.
+fn texture_layout(ty: &TypeSpecifierNonArray) -> Option<String> {
+  let (sample_type, view_dimension, multisampled) = match ty {
+    TypeSpecifierNonArray::Sampler1D => ("float", "1d", false),
+    TypeSpecifierNonArray::Sampler2D => ("float", "2d", false),
+    TypeSpecifierNonArray::Sampler3D => ("float", "3d", false),
+    TypeSpecifierNonArray::SamplerCube => ("float", "cube", false),
+    TypeSpecifierNonArray::Sampler2DArray => ("float", "2d-array", false),
+    TypeSpecifierNonArray::SamplerCubeArray => ("float", "cube-array", false),
+    TypeSpecifierNonArray::Sampler2DMS => ("float", "2d", true),
+
+    TypeSpecifierNonArray::Sampler2DShadow => ("depth", "2d", false),
+    TypeSpecifierNonArray::Sampler2DArrayShadow => ("depth", "2d-array", false),
+    TypeSpecifierNonArray::SamplerCubeShadow => ("depth", "cube", false),
+    TypeSpecifierNonArray::SamplerCubeArrayShadow => ("depth", "cube-array", false),
+
+    TypeSpecifierNonArray::ISampler1D => ("sint", "1d", false),
+    TypeSpecifierNonArray::ISampler2D => ("sint", "2d", false),
+    TypeSpecifierNonArray::ISampler3D => ("sint", "3d", false),
+    TypeSpecifierNonArray::ISamplerCube => ("sint", "cube", false),
+    TypeSpecifierNonArray::ISampler2DArray => ("sint", "2d-array", false),
+    TypeSpecifierNonArray::ISamplerCubeArray => ("sint", "cube-array", false),
+    TypeSpecifierNonArray::ISampler2DMS => ("sint", "2d", true),
+
+    TypeSpecifierNonArray::USampler1D => ("uint", "1d", false),
+    TypeSpecifierNonArray::USampler2D => ("uint", "2d", false),
+    TypeSpecifierNonArray::USampler3D => ("uint", "3d", false),
+    TypeSpecifierNonArray::USamplerCube => ("uint", "cube", false),
+    TypeSpecifierNonArray::USampler2DArray => ("uint", "2d-array", false),
+    TypeSpecifierNonArray::USamplerCubeArray => ("uint", "cube-array", false),
+    TypeSpecifierNonArray::USampler2DMS => ("uint", "2d", true),
+
+    _ => return None,
+  };
+
+  return Some(format!(
+    "texture: {{ sampleType: \"{}\", viewDimension: \"{}\", multisampled: {} }}",
+    sample_type, view_dimension, multisampled
+  ));
+}
@@ -0,0 +1,139 @@
+use super::common::{self, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+// "GLST" in ASCII
+const FRAME_MAGIC: u32 = 0x474c5354;
+const FRAME_VERSION: u8 = 1;
+const HEADER_SIZE: usize = 8;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let mut encode = String::new();
+  let mut decode = String::new();
+  let mut offset = HEADER_SIZE;
+
+  for uniform in common::value_uniforms(&shader_data) {
+    let mut values = Vec::new();
+
+    for component in common::flatten_components(uniform) {
+      let accessor = format!("uniforms.{}", component.accessor());
+
+      let (write, read) = match component.kind {
+        ScalarKind::Float => (
+          format!("view.setFloat32({}, {}, true);", offset, accessor),
+          format!("view.getFloat32({}, true)", offset),
+        ),
+        ScalarKind::Int => (
+          format!("view.setInt32({}, {}, true);", offset, accessor),
+          format!("view.getInt32({}, true)", offset),
+        ),
+        ScalarKind::UInt => (
+          format!("view.setUint32({}, {}, true);", offset, accessor),
+          format!("view.getUint32({}, true)", offset),
+        ),
+        ScalarKind::Bool => (
+          format!("view.setUint32({}, {} ? 1 : 0, true);", offset, accessor),
+          format!("view.getUint32({}, true) !== 0", offset),
+        ),
+      };
+
+      encode.push_str(&format!("  {}\n", write));
+      values.push(read);
+
+      // Every GLSL scalar (including bool) occupies 4 bytes
+      offset += 4;
+    }
+
+    let value = if values.len() == 1 {
+      values.remove(0)
+    } else {
+      format!("[{}]", values.join(", "))
+    };
+
+    decode.push_str(&format!("    {}: {},\n", uniform.identifier, value));
+  }
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+// Frame layout: magic (uint32), version (uint8), 3 reserved bytes, payload (little endian)
+export const FRAME_MAGIC = 0x{1:08x};
+export const FRAME_VERSION = {2};
+export const HEADER_SIZE = {3};
+export const FRAME_SIZE = {4};
+
+export function encodeUniforms(uniforms: {0}Uniforms): ArrayBuffer {{
+  const buffer = new ArrayBuffer(FRAME_SIZE);
+  const view = new DataView(buffer);
+  view.setUint32(0, FRAME_MAGIC, true);
+  view.setUint8(4, FRAME_VERSION);
+{5}  return buffer;
+}}
+
+export function decodeUniforms(buffer: ArrayBuffer): {0}Uniforms | null {{
+  if (buffer.byteLength < FRAME_SIZE) {{
+    return null;
+  }}
+
+  const view = new DataView(buffer);
+
+  if (view.getUint32(0, true) !== FRAME_MAGIC || view.getUint8(4) !== FRAME_VERSION) {{
+    return null;
+  }}
+
+  return {{
+{6}  }};
+}}
+
+export function sendUniforms(channel: RTCDataChannel, uniforms: {0}Uniforms): void {{
+  channel.send(encodeUniforms(uniforms));
+}}
+
+export function onUniforms(
+  channel: RTCDataChannel,
+  handler: (uniforms: {0}Uniforms) => void
+): () => void {{
+  channel.binaryType = "arraybuffer";
+
+  const listener = (event: MessageEvent) => {{
+    if (!(event.data instanceof ArrayBuffer)) {{
+      return;
+    }}
+
+    const uniforms = decodeUniforms(event.data);
+
+    if (uniforms) {{
+      handler(uniforms);
+    }}
+  }};
+
+  channel.addEventListener("message", listener);
+  return () => channel.removeEventListener("message", listener);
+}}
+"#,
+    output_type_name, FRAME_MAGIC, FRAME_VERSION, HEADER_SIZE, offset, encode, decode
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.datachannel.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
@@ -0,0 +1,122 @@
+use super::common::{self, GeneratorError};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+
+  let value_uniforms = common::value_uniforms(&shader_data);
+
+  let message_schemas = value_uniforms
+    .iter()
+    .map(|uniform| {
+      format!(
+        "  z.object({{\n    type: z.literal(\"set_uniform\"),\n    name: z.literal(\"{0}\"),\n    value: {1}UniformsSchema.shape.{0},\n  }}),\n",
+        uniform.identifier, output_type_name
+      )
+    })
+    .collect::<String>();
+
+  // The messages can only set the uniforms the schema validates
+  let uniform_names = if value_uniforms.is_empty() {
+    "never".to_string()
+  } else {
+    value_uniforms
+      .iter()
+      .map(|uniform| format!("\"{}\"", uniform.identifier))
+      .collect::<Vec<String>>()
+      .join(" | ")
+  };
+
+  // `z.union` needs at least two options
+  let message_schema = if message_schemas.is_empty() {
+    "z.object({ type: z.literal(\"reset\") })".to_string()
+  } else {
+    format!(
+      "z.union([\n{}  z.object({{ type: z.literal(\"reset\") }}),\n])",
+      message_schemas
+    )
+  };
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import { z } from \"zod\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+  output_file.push('\n');
+  output_file.push_str(&type_script::zod_schema_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  output_file.push_str(&format!(
+    r#"
+export type {0}SetUniformMessage = {{
+  [K in {2}]: {{ type: "set_uniform"; name: K; value: {0}Uniforms[K] }};
+}}[{2}];
+
+export type {0}ShaderMessage = {0}SetUniformMessage | {{ type: "reset" }};
+
+export const {0}ShaderMessageSchema = {1};
+
+export function serializeMessage(message: {0}ShaderMessage): string {{
+  return JSON.stringify(message);
+}}
+
+export function deserializeMessage(data: string): {0}ShaderMessage | null {{
+  let message: unknown;
+
+  try {{
+    message = JSON.parse(data);
+  }} catch {{
+    return null;
+  }}
+
+  const result = {0}ShaderMessageSchema.safeParse(message);
+  return result.success ? (result.data as {0}ShaderMessage) : null;
+}}
+
+export function sendMessage(socket: WebSocket, message: {0}ShaderMessage): void {{
+  socket.send(serializeMessage(message));
+}}
+
+export function onMessage(
+  socket: WebSocket,
+  handler: (message: {0}ShaderMessage) => void
+): () => void {{
+  const listener = (event: MessageEvent) => {{
+    if (typeof event.data !== "string") {{
+      return;
+    }}
+
+    const message = deserializeMessage(event.data);
+
+    if (message) {{
+      handler(message);
+    }}
+  }};
+
+  socket.addEventListener("message", listener);
+  return () => socket.removeEventListener("message", listener);
+}}
+"#,
+    output_type_name, message_schema, uniform_names
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.websocket.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
@@ -0,0 +1,120 @@
+use super::common::{self, Component, GeneratorError, ScalarKind};
+use super::type_script;
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let table_name = format!("{}_uniforms", common::to_snake_case(output_file_name));
+
+  let components = common::value_uniforms(&shader_data)
+    .into_iter()
+    .flat_map(common::flatten_components)
+    .collect::<Vec<Component>>();
+
+  // Xata schema
+  let columns = components
+    .iter()
+    .map(|component| {
+      format!(
+        "      - name: {}\n        type: {}\n        notNull: true\n",
+        component.snake_case_name(),
+        convert_kind_to_xata_type(component.kind)
+      )
+    })
+    .collect::<String>();
+
+  let mut schema_file = String::new();
+  schema_file.push_str("# DO NOT EDIT THIS FILE\n");
+  schema_file.push_str("# This file is generated by glsl-types\n\n");
+  schema_file.push_str(&format!(
+    "tables:\n  - name: {}\n    columns:\n{}",
+    table_name, columns
+  ));
+
+  let schema_file_path = output_folder.join(format!("{}.xata.yml", output_file_name));
+  write_file(schema_file_path.to_str().unwrap(), schema_file.as_str());
+
+  // TypeScript client
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+  output_file.push_str("import type { XataRecord } from \"@xata.io/client\";\n");
+  output_file.push_str("import { getXataClient } from \"./xata\";\n\n");
+  output_file.push_str(&type_script::uniforms_type_declaration(
+    &output_type_name,
+    &shader_data,
+  ));
+
+  let record_fields = components
+    .iter()
+    .map(|component| {
+      format!(
+        "  {}: {};\n",
+        component.snake_case_name(),
+        match component.kind {
+          ScalarKind::Bool => "boolean",
+          _ => "number",
+        }
+      )
+    })
+    .collect::<String>();
+
+  let create_values = components
+    .iter()
+    .map(|component| {
+      format!(
+        "    {}: data.{},\n",
+        component.snake_case_name(),
+        component.accessor()
+      )
+    })
+    .collect::<String>();
+
+  output_file.push_str(&format!(
+    r#"
+export type {0}UniformsRecord = XataRecord & {{
+{1}}};
+
+export async function createUniformSet(data: {0}Uniforms): Promise<{0}UniformsRecord> {{
+  const xata = getXataClient();
+  const record = await xata.db.{2}.create({{
+{3}  }});
+  return record as unknown as {0}UniformsRecord;
+}}
+
+export async function getUniformSet(id: string): Promise<{0}UniformsRecord | null> {{
+  const xata = getXataClient();
+  const record = await xata.db.{2}.read(id);
+  return record as unknown as {0}UniformsRecord | null;
+}}
+
+export async function deleteUniformSet(id: string): Promise<void> {{
+  const xata = getXataClient();
+  await xata.db.{2}.delete(id);
+}}
+"#,
+    output_type_name, record_fields, table_name, create_values
+  ));
+
+  let output_file_path = output_folder.join(format!("{}.xata.ts", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+fn convert_kind_to_xata_type(kind: ScalarKind) -> &'static str {
+  match kind {
+    ScalarKind::Float => "float",
+    ScalarKind::Int | ScalarKind::UInt => "int",
+    ScalarKind::Bool => "bool",
+  }
+}
@@ -1,60 +1,205 @@
-use super::common;
+use super::changes;
+use super::common::{
+  self, ArraySize, GeneratorError, GlslType, Interpolation, ScalarKind, ShaderData, TypedVariable,
+  UniformBlock,
+};
+use super::json_schema;
+use super::layout;
+use super::threejs;
+use super::webgl_setters;
+use crate::options::{NamingConvention, Options};
 use crate::{
-  log, log_with_color, logln,
   utils::{
-    get_shader_type::{get_shader_type, ShaderType},
-    log::{print_level, Level},
+    get_shader_type::ShaderType,
+    json::Json,
+    log::{print_diagnostic, Level, Segment},
   },
   write_file,
 };
-use glsl::syntax::TypeSpecifierNonArray;
+use glsl::syntax::{PrecisionQualifier, TypeSpecifierNonArray};
 
 pub fn generate_types_file(
-  content: String,
+  source: &common::ShaderSource,
   file_path: &std::path::PathBuf,
   output_folder: &std::path::PathBuf,
-) -> bool {
-  let shader_type = get_shader_type(file_path).unwrap();
-  let shader_data = common::extract_shader_data(&content, shader_type);
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
 
   // Show a warning if the vertex shader has more than 16 attributes (This is webgl2)
   if shader_data.ins.len() > 16 && shader_data.shader_type == ShaderType::Vertex {
-    print_level(Level::WARN);
-    log("The vertex shader ");
-    log_with_color(file_path.to_str().unwrap(), "blue");
-    logln(" has more than 16 attributes. This can cause issues in some devices.");
+    print_diagnostic(
+      Level::WARN,
+      "TOO_MANY_ATTRIBUTES",
+      &[
+        Segment::Text("The vertex shader "),
+        Segment::Highlight(file_path.to_str().unwrap()),
+        Segment::Text(" has more than 16 attributes. This can cause issues in some devices."),
+      ],
+      vec![("file", Json::string(file_path.to_str().unwrap()))],
+    );
+  }
+
+  // Integer varyings cannot be interpolated, GLSL requires them to be declared `flat`
+  let varyings = match shader_data.shader_type {
+    ShaderType::Vertex => shader_data.outs.iter().collect::<Vec<_>>(),
+    ShaderType::Fragment => shader_data.ins.iter().collect(),
+    ShaderType::Geometry => shader_data.ins.iter().chain(&shader_data.outs).collect(),
+    // Only the last stage before rasterization feeds interpolated values to the fragment shader
+    ShaderType::TessEvaluation => shader_data.outs.iter().collect(),
+    ShaderType::Compute | ShaderType::TessControl => Vec::new(),
+  };
+
+  for varying in varyings {
+    let is_integer = matches!(
+      common::scalar_kind(&varying.type_label),
+      Some(ScalarKind::Int | ScalarKind::UInt)
+    );
+
+    if is_integer && varying.interpolation != Some(Interpolation::Flat) {
+      print_diagnostic(
+        Level::WARN,
+        "INTEGER_VARYING_NOT_FLAT",
+        &[
+          Segment::Text("The integer varying "),
+          Segment::Highlight(&varying.identifier),
+          Segment::Text(" in "),
+          Segment::Highlight(file_path.to_str().unwrap()),
+          Segment::Text(" must be declared flat."),
+        ],
+        vec![
+          ("file", Json::string(file_path.to_str().unwrap())),
+          ("name", Json::string(&varying.identifier)),
+        ],
+      );
+    }
+  }
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let (mut output_file, shader_object) =
+    render(&source.embedded, output_file_name, &shader_data, options);
+
+  if options.emit_json_schema {
+    let export_name = common::export_name(
+      &common::prefixed_file_stem(output_file_name, options),
+      options.naming_convention,
+    );
+    let file_name = file_path.file_name().unwrap().to_str().unwrap();
+
+    if let Some(schema) = json_schema::render_schema_file(&export_name, file_name, &shader_object) {
+      let schema_path = output_folder.join(format!("{}.schema.json", output_file_name));
+      write_file(schema_path.to_str().unwrap(), &schema);
+    }
+  }
+
+  if options.source_map {
+    output_file.push_str(&format!(
+      "//# sourceMappingURL={}.glsl.map\n",
+      output_file_name
+    ));
+  }
+
+  let output_file_path = output_folder.join(format!(
+    "{}{}",
+    output_file_name,
+    options.output_extension.as_deref().unwrap_or(".ts")
+  ));
+  // std::fs::write(output_file_path, output_file).unwrap();
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  // Nothing is written by a dry run, so there is no previous generation to compare with
+  if !options.dry_run {
+    changes::report_changes(file_path, &shader_data);
   }
 
+  return Ok(());
+}
+
+/// Contents of the generated `.ts` file for a shader named `output_file_name`.
+pub fn render_types_file(
+  content: &str,
+  output_file_name: &str,
+  shader_data: &ShaderData,
+  options: &Options,
+) -> String {
+  return render(content, output_file_name, shader_data, options).0;
+}
+
+// The `.ts` file and the literal of its shader object, without the type assertion on the
+// uniforms, which `--emit-json-schema` describes
+fn render(
+  content: &str,
+  output_file_name: &str,
+  shader_data: &ShaderData,
+  options: &Options,
+) -> (String, String) {
+  // A `.d.ts` file only declares the types of what another build emits
+  let declaration_only = options
+    .output_extension
+    .as_deref()
+    .is_some_and(|extension| extension.starts_with(".d."));
+
   let mut output_file = String::new();
   output_file.push_str("// DO NOT EDIT THIS FILE\n");
   output_file.push_str("// This file is generated by glsl-types\n\n");
 
-  output_file.push_str(&format!("const SOURCE = /* glsl */ `{}`;\n\n", &content));
+  if options.emit_threejs {
+    output_file.push_str(threejs::IMPORTS);
+  }
 
-  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
-  let output_type_name = common::capitalize_first_letter(output_file_name);
+  if options.emit_zod && !declaration_only {
+    output_file.push_str("import { z } from \"zod\";\n\n");
+  }
 
-  let uniforms = shader_data
+  if !declaration_only {
+    output_file.push_str(&format!("const SOURCE = /* glsl */ `{}`;\n\n", content));
+  }
+
+  let output_file_name = &common::prefixed_file_stem(output_file_name, options);
+  let output_type_name = common::to_pascal_case(output_file_name);
+  let export_name = common::export_name(output_file_name, options.naming_convention);
+
+  let uniform_labels = shader_data
     .uniforms
     .iter()
     .map(|value| {
-      format!(
-        r#"    {}: "{}","#,
+      (
         &value.identifier,
-        convert_glsl_to_ts_label(&value.type_label)
+        convert_glsl_to_ts_type(&shader_data.resolve_variable_type(value), 2, options),
+      )
+    })
+    .collect::<Vec<(&String, String)>>();
+
+  let uniforms = shader_data
+    .uniforms
+    .iter()
+    .zip(&uniform_labels)
+    .map(|(uniform, (identifier, label))| {
+      format!(
+        "{}    {}: {},",
+        js_doc(&uniform.doc_comment, "    "),
+        identifier,
+        label
       )
     })
     .collect::<Vec<String>>()
     .join("\n");
 
+  let uniform_blocks = shader_data
+    .uniform_blocks
+    .iter()
+    .map(|block| uniform_block_descriptor(block, options))
+    .collect::<String>();
+
   let ins = shader_data
     .ins
     .iter()
     .map(|value| {
       format!(
-        r#"    {}: "{}","#,
+        "    {}: {},",
         &value.identifier,
-        convert_glsl_to_ts_label(&value.type_label)
+        convert_glsl_to_ts_type(&shader_data.resolve_variable_type(value), 2, options)
       )
     })
     .collect::<Vec<String>>()
@@ -65,43 +210,674 @@ pub fn generate_types_file(
     .iter()
     .map(|value| {
       format!(
-        r#"    {}: "{}","#,
+        "    {}: {},",
         &value.identifier,
-        convert_glsl_to_ts_label(&value.type_label)
+        convert_glsl_to_ts_type(&shader_data.resolve_variable_type(value), 2, options)
       )
     })
     .collect::<Vec<String>>()
     .join("\n");
 
+  let io_fields = format!("  ins: {{\n{}\n  }},\n  outs: {{\n{}\n  }},\n", ins, outs);
+
+  let stage_fields = match shader_data.shader_type {
+    ShaderType::Vertex => io_fields + &vertex_fields(shader_data),
+    ShaderType::Fragment => io_fields + &fragment_fields(shader_data),
+    ShaderType::Geometry => io_fields + &geometry_fields(shader_data),
+    ShaderType::Compute => compute_fields(shader_data, options),
+    ShaderType::TessControl => io_fields + &tess_control_fields(shader_data),
+    ShaderType::TessEvaluation => io_fields + &tess_evaluation_fields(shader_data),
+  } + &precision_fields(shader_data)
+    + &interpolation_fields(shader_data)
+    + &image_format_fields(shader_data)
+    + &subroutine_fields(shader_data)
+    + &atomic_counter_fields(shader_data)
+    + &version_fields(shader_data)
+    + &extension_fields(shader_data);
+
+  // The setters take the uniforms interface as their argument and the locations are keyed by it
+  if options.emit_interfaces
+    || options.emit_setters
+    || options.emit_location_cache
+    || options.emit_threejs
+  {
+    output_file.push_str(&uniforms_interface_declaration(
+      &output_type_name,
+      shader_data,
+    ));
+  }
+
+  let uniforms_type = if options.emit_interfaces && !declaration_only {
+    // Struct uniforms are described by nested objects instead of a label
+    let has_objects = uniform_labels
+      .iter()
+      .any(|(_, label)| label.starts_with('{'));
+
+    let label_type = if has_objects {
+      "string | object"
+    } else {
+      "string"
+    };
+    format!(
+      " as Record<keyof {}Uniforms, {}>",
+      output_type_name, label_type
+    )
+  } else {
+    String::new()
+  };
+
+  let shader_object = |uniforms_type: &str, source: &str| {
+    format!(
+      "{{\n  uniforms: {{\n{}\n  }}{},\n  uniformBlocks: {{\n{}  }},\n{}  source: {}\n}}",
+      uniforms, uniforms_type, uniform_blocks, stage_fields, source
+    )
+  };
+
+  // The object literal is also a valid type literal, so a declaration reuses it as its type
+  let (declaration, source) = match declaration_only {
+    true => (format!("export declare const {}:", export_name), "string"),
+    false => (format!("export const {} =", export_name), "SOURCE"),
+  };
+
   // Export a type that contains all the uniforms
   output_file.push_str(&format!(
-    r#"
-
-export const {} = {{
-  uniforms: {{
-{}
-  }},
-  ins: {{
-{}
-  }},
-  outs: {{
-{}
-  }},
-  source: SOURCE
-}};
-
-"#,
-    output_type_name, uniforms, ins, outs
+    "\n\n{} {};\n\n",
+    declaration,
+    shader_object(&uniforms_type, source)
   ));
 
-  let output_file_path = output_folder.join(format!("{}.ts", output_file_name));
-  // std::fs::write(output_file_path, output_file).unwrap();
-  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+  let mut functions = String::new();
+
+  if options.emit_named_exports {
+    let constants = named_exports(output_file_name, shader_data);
+
+    match declaration_only {
+      true => output_file.push_str(&constants.replace("export const ", "export declare const ")),
+      false => output_file.push_str(&constants),
+    }
+  }
+
+  if options.emit_setters {
+    functions.push_str(&webgl_setters::setter_function(
+      &output_type_name,
+      shader_data,
+    ));
+  }
+
+  if options.emit_location_cache {
+    functions.push_str(&webgl_setters::location_cache(
+      &output_type_name,
+      shader_data,
+    ));
+  }
+
+  if options.emit_threejs {
+    if let Some(factory) = threejs::material_factory(&output_type_name, shader_data) {
+      functions.push_str(&factory);
+    }
+  }
+
+  match declaration_only {
+    true => output_file.push_str(&declare_functions(&functions)),
+    false => output_file.push_str(&functions),
+  }
+
+  // The type of a schema is spelled out by zod itself, there is nothing to declare it with
+  if options.emit_zod && declaration_only {
+    print_diagnostic(
+      Level::WARN,
+      "ZOD_SCHEMA_NOT_DECLARED",
+      &[
+        Segment::Text("A declaration file cannot hold a zod schema, "),
+        Segment::Highlight(output_file_name),
+        Segment::Text(" is declared without its schema"),
+      ],
+      vec![("file", Json::string(output_file_name))],
+    );
+  } else if options.emit_zod {
+    output_file.push_str(&uniforms_zod_schema_declaration(
+      &output_type_name,
+      shader_data,
+    ));
+  }
+
+  return (output_file, shader_object("", "SOURCE"));
+}
+
+/// `export function`s of `code` as `export declare function` signatures, for a `.d.ts` file.
+/// Parameters with a default value become optional.
+fn declare_functions(code: &str) -> String {
+  let mut output = String::new();
+  let mut in_signature = false;
+  let mut in_body = false;
+
+  for line in code.lines() {
+    if in_body {
+      in_body = line != "}";
+      continue;
+    }
+
+    let line = match line.strip_prefix("export function ") {
+      Some(rest) => {
+        in_signature = true;
+        format!("export declare function {}", rest)
+      }
+      None => line.to_string(),
+    };
+
+    if !in_signature {
+      output.push_str(&line);
+      output.push('\n');
+      continue;
+    }
+
+    // e.g. `  parameters: THREE.ShaderMaterialParameters = {}`
+    let line = match line.split_once(" = ") {
+      Some((parameter, default)) => {
+        let separator = if default.ends_with(',') { "," } else { "" };
+        format!("{}{}", parameter.replacen(':', "?:", 1), separator)
+      }
+      None => line,
+    };
+
+    match line.strip_suffix(" {") {
+      Some(signature) => {
+        output.push_str(&format!("{};\n", signature));
+        in_signature = false;
+        in_body = true;
+      }
+      None => {
+        output.push_str(&line);
+        output.push('\n');
+      }
+    }
+  }
+
+  return output;
+}
+
+/// One `export const` per uniform and vertex attribute name, e.g.
+/// `export const WAVE_UNIFORM_MODEL_MATRIX = "modelMatrix";`, so bundlers can drop the unused ones.
+fn named_exports(output_file_name: &str, shader_data: &ShaderData) -> String {
+  let prefix = common::export_name(output_file_name, NamingConvention::ScreamingSnake);
+  let constant = |kind: &str, identifier: &str| {
+    format!(
+      "export const {}_{}_{} = \"{}\";\n",
+      prefix,
+      kind,
+      common::to_snake_case(identifier)
+        .trim_matches('_')
+        .to_uppercase(),
+      identifier
+    )
+  };
+
+  let uniforms = shader_data
+    .uniforms
+    .iter()
+    .map(|uniform| constant("UNIFORM", &uniform.identifier));
+
+  // Only the inputs of a vertex shader are attributes, the others are varyings
+  let attributes = match shader_data.shader_type {
+    ShaderType::Vertex => shader_data.ins.as_slice(),
+    _ => &[],
+  }
+  .iter()
+  .map(|input| constant("ATTRIB", &input.identifier));
+
+  let mut output = uniforms.chain(attributes).collect::<String>();
+
+  if !output.is_empty() {
+    output.push('\n');
+  }
+
+  return output;
+}
+
+/// Members of a uniform block followed by their std140 byte offsets under `offsetOf`.
+fn uniform_block_descriptor(block: &UniformBlock, options: &Options) -> String {
+  let members = block
+    .fields
+    .iter()
+    .map(|field| {
+      format!(
+        "      {}: {},\n",
+        field.identifier,
+        convert_glsl_to_ts_type(&field.ty, 3, options)
+      )
+    })
+    .collect::<String>();
+
+  let offsets = layout::compute_std140_offsets(block)
+    .iter()
+    .map(|(name, offset)| format!("        {}: {},\n", name, offset))
+    .collect::<String>();
+
+  return format!(
+    "    {}: {{\n{}      offsetOf: {{\n{}      }},\n    }},\n",
+    block.name, members, offsets
+  );
+}
+
+/// `buffers`, `bindings` and `workgroupSize` of a compute shader.
+fn compute_fields(shader_data: &ShaderData, options: &Options) -> String {
+  let buffers = shader_data
+    .buffers
+    .iter()
+    .map(|buffer| {
+      let ty = GlslType::Struct {
+        name: Some(buffer.name.clone()),
+        fields: buffer.fields.clone(),
+      };
+
+      format!(
+        "    {}: {},\n",
+        buffer.name,
+        convert_glsl_to_ts_type(&ty, 2, options)
+      )
+    })
+    .collect::<String>();
+
+  let uniform_bindings = shader_data
+    .uniforms
+    .iter()
+    .filter_map(|uniform| Some((&uniform.identifier, uniform.binding?)));
+
+  let uniform_block_bindings = shader_data
+    .uniform_blocks
+    .iter()
+    .filter_map(|block| Some((&block.name, block.binding?)));
+
+  let buffer_bindings = shader_data
+    .buffers
+    .iter()
+    .filter_map(|buffer| Some((&buffer.name, buffer.binding?)));
+
+  let bindings = uniform_bindings
+    .chain(uniform_block_bindings)
+    .chain(buffer_bindings)
+    .map(|(name, binding)| format!("    {}: {},\n", name, binding))
+    .collect::<String>();
+
+  // GLSL defaults every dimension of the workgroup to 1
+  let [x, y, z] = shader_data.workgroup_size.unwrap_or([1, 1, 1]);
+
+  return format!(
+    "  buffers: {{\n{}  }},\n  bindings: {{\n{}  }},\n  workgroupSize: [{}, {}, {}],\n",
+    buffers, bindings, x, y, z
+  );
+}
+
+/// `glslVersion` and `glslProfile` from the `#version` directive, `null` when not declared.
+fn version_fields(shader_data: &ShaderData) -> String {
+  let version = match shader_data.glsl_version {
+    Some(version) => version.to_string(),
+    None => "null".to_string(),
+  };
+
+  let profile = match &shader_data.glsl_profile {
+    Some(profile) => format!("\"{}\"", profile),
+    None => "null".to_string(),
+  };
+
+  return format!("  glslVersion: {},\n  glslProfile: {},\n", version, profile);
+}
+
+/// `requiredExtensions`, the extensions enabled by an `#extension` directive with the
+/// `enable`, `require` or `warn` behavior.
+fn extension_fields(shader_data: &ShaderData) -> String {
+  let mut extensions: Vec<String> = Vec::new();
+
+  // `#extension all` only sets how unsupported extensions are reported
+  for extension in shader_data
+    .extensions
+    .iter()
+    .filter(|directive| directive.name != "all")
+  {
+    let name = format!("\"{}\"", extension.name);
+
+    // A later directive overrides the behavior of an earlier one
+    extensions.retain(|previous| *previous != name);
+
+    if extension.behavior.is_required() {
+      extensions.push(name);
+    }
+  }
+
+  return format!("  requiredExtensions: [{}],\n", extensions.join(", "));
+}
+
+/// `precision` of the uniforms and inputs declared with a precision qualifier. Omitted when
+/// none are, as in desktop GLSL.
+fn precision_fields(shader_data: &ShaderData) -> String {
+  let precisions = shader_data
+    .uniforms
+    .iter()
+    .chain(&shader_data.ins)
+    .filter_map(|variable| {
+      let precision = match variable.precision.as_ref()? {
+        PrecisionQualifier::High => "highp",
+        PrecisionQualifier::Medium => "mediump",
+        PrecisionQualifier::Low => "lowp",
+      };
+
+      Some(format!("    {}: \"{}\",\n", variable.identifier, precision))
+    })
+    .collect::<String>();
+
+  if precisions.is_empty() {
+    return String::new();
+  }
+
+  return format!("  precision: {{\n{}  }},\n", precisions);
+}
+
+/// `interpolation` of the inputs and outputs declared with an interpolation qualifier, omitted
+/// when none are.
+fn interpolation_fields(shader_data: &ShaderData) -> String {
+  let interpolations = shader_data
+    .ins
+    .iter()
+    .chain(&shader_data.outs)
+    .filter_map(|variable| {
+      let interpolation = variable.interpolation.as_ref()?;
 
-  return true;
+      Some(format!(
+        "    {}: \"{}\",\n",
+        variable.identifier,
+        interpolation.as_str()
+      ))
+    })
+    .collect::<String>();
+
+  if interpolations.is_empty() {
+    return String::new();
+  }
+
+  return format!("  interpolation: {{\n{}  }},\n", interpolations);
+}
+
+/// `imageFormats` of the image uniforms declared with a format qualifier, omitted when none are.
+fn image_format_fields(shader_data: &ShaderData) -> String {
+  let formats = shader_data
+    .uniforms
+    .iter()
+    .filter_map(|uniform| {
+      Some(format!(
+        "    {}: \"{}\",\n",
+        uniform.identifier,
+        uniform.format?.as_str()
+      ))
+    })
+    .collect::<String>();
+
+  if formats.is_empty() {
+    return String::new();
+  }
+
+  return format!("  imageFormats: {{\n{}  }},\n", formats);
+}
+
+/// `subroutines`, the type of every subroutine uniform and the functions that can be selected
+/// for it. Omitted when there are none.
+fn subroutine_fields(shader_data: &ShaderData) -> String {
+  let subroutines = shader_data
+    .subroutines
+    .iter()
+    .map(|subroutine| {
+      let functions = subroutine
+        .functions
+        .iter()
+        .map(|function| format!("\"{}\"", function))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+      format!(
+        "    {}: {{ type: \"{}\", functions: [{}] }},\n",
+        subroutine.uniform, subroutine.type_name, functions
+      )
+    })
+    .collect::<String>();
+
+  if subroutines.is_empty() {
+    return String::new();
+  }
+
+  return format!("  subroutines: {{\n{}  }},\n", subroutines);
+}
+
+/// `atomicCounters`, the binding point of the buffer backing every atomic counter. Omitted when
+/// there are none.
+fn atomic_counter_fields(shader_data: &ShaderData) -> String {
+  let atomic_counters = shader_data
+    .atomic_counters
+    .iter()
+    .map(|counter| format!("    {}: {},\n", counter.name, counter.binding))
+    .collect::<String>();
+
+  if atomic_counters.is_empty() {
+    return String::new();
+  }
+
+  return format!("  atomicCounters: {{\n{}  }},\n", atomic_counters);
+}
+
+/// `attributeLocations` of a vertex shader, `null` for attributes without `layout(location = N)`.
+fn vertex_fields(shader_data: &ShaderData) -> String {
+  let locations = shader_data
+    .ins
+    .iter()
+    .map(|attribute| {
+      let location = match attribute.location {
+        Some(location) => location.to_string(),
+        None => "null".to_string(),
+      };
+
+      format!("    {}: {},\n", attribute.identifier, location)
+    })
+    .collect::<String>();
+
+  return format!("  attributeLocations: {{\n{}  }},\n", locations);
+}
+
+/// `outputs` of a fragment shader: the render target location of each `out` declared with
+/// `layout(location = N)`.
+fn fragment_fields(shader_data: &ShaderData) -> String {
+  let outputs = shader_data
+    .outs
+    .iter()
+    .filter_map(|output| Some((&output.identifier, output.location?)))
+    .map(|(name, location)| format!("    {}: {},\n", name, location))
+    .collect::<String>();
+
+  return format!("  outputs: {{\n{}  }},\n", outputs);
+}
+
+/// `patchVertices` of a tessellation control shader, `null` when not declared.
+fn tess_control_fields(shader_data: &ShaderData) -> String {
+  let patch_vertices = match shader_data.patch_vertices {
+    Some(patch_vertices) => patch_vertices.to_string(),
+    None => "null".to_string(),
+  };
+
+  return format!("  patchVertices: {},\n", patch_vertices);
+}
+
+/// `domain`, `spacing`, `winding` and `pointMode` of a tessellation evaluation shader. The domain
+/// is `null` when not declared, the others default to what GLSL uses.
+fn tess_evaluation_fields(shader_data: &ShaderData) -> String {
+  let domain = match &shader_data.tessellation_domain {
+    Some(domain) => format!("\"{}\"", domain),
+    None => "null".to_string(),
+  };
+
+  return format!(
+    "  domain: {},\n  spacing: \"{}\",\n  winding: \"{}\",\n  pointMode: {},\n",
+    domain,
+    shader_data
+      .tessellation_spacing
+      .as_deref()
+      .unwrap_or("equal_spacing"),
+    shader_data.tessellation_winding.as_deref().unwrap_or("ccw"),
+    shader_data.point_mode
+  );
+}
+
+/// `primitiveIn`, `primitiveOut` and `maxVertices` of a geometry shader, `null` when not declared.
+fn geometry_fields(shader_data: &ShaderData) -> String {
+  let quoted = |value: &Option<String>| match value {
+    Some(value) => format!("\"{}\"", value),
+    None => "null".to_string(),
+  };
+
+  let max_vertices = match shader_data.max_vertices {
+    Some(max_vertices) => max_vertices.to_string(),
+    None => "null".to_string(),
+  };
+
+  return format!(
+    "  primitiveIn: {},\n  primitiveOut: {},\n  maxVertices: {},\n",
+    quoted(&shader_data.primitive_in),
+    quoted(&shader_data.primitive_out),
+    max_vertices
+  );
 }
 
-fn convert_glsl_to_ts_label(uniform: &TypeSpecifierNonArray) -> String {
+/// Value used to describe a type in the generated constant: a string label such as `"vec3"`
+/// or `"vec3[16]"`, or a nested object literal for structs. Labels and struct names with a
+/// configured type override are replaced by it. `depth` is the indentation level of the field.
+pub fn convert_glsl_to_ts_type(ty: &GlslType, depth: usize, options: &Options) -> String {
+  match ty {
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) | GlslType::Opaque(ty) => {
+      // Looked up first so types without a label can be overridden without a warning
+      let label = match options.type_overrides.get(&glsl_type_name(ty)) {
+        Some(label) => label.clone(),
+        None => convert_glsl_to_ts_label(ty),
+      };
+
+      format!("\"{}\"", label)
+    }
+    GlslType::Struct {
+      name: Some(name), ..
+    } if options.type_overrides.contains_key(name) => {
+      format!("\"{}\"", options.type_overrides[name])
+    }
+    GlslType::Struct { fields, .. } => {
+      let indent = "  ".repeat(depth + 1);
+
+      let fields = fields
+        .iter()
+        .map(|field| {
+          format!(
+            "{}{}: {},\n",
+            indent,
+            field.identifier,
+            convert_glsl_to_ts_type(&field.ty, depth + 1, options)
+          )
+        })
+        .collect::<String>();
+
+      format!("{{\n{}{}}}", fields, "  ".repeat(depth))
+    }
+    GlslType::Array(element, size) => {
+      let length = match size {
+        ArraySize::Sized(size) => size.to_string(),
+        ArraySize::Unsized => String::new(),
+      };
+
+      match element.as_ref() {
+        GlslType::Struct { .. } | GlslType::Array(..) => {
+          let indent = "  ".repeat(depth + 1);
+          let length = if length.is_empty() {
+            "null".to_string()
+          } else {
+            length
+          };
+
+          format!(
+            "{{\n{0}element: {1},\n{0}length: {2},\n{3}}}",
+            indent,
+            convert_glsl_to_ts_type(element, depth + 1, options),
+            length,
+            "  ".repeat(depth)
+          )
+        }
+        _ => {
+          let label = convert_glsl_to_ts_type(element, depth, options);
+          format!("\"{}[{}]\"", label.trim_matches('"'), length)
+        }
+      }
+    }
+    GlslType::Unknown(name) => match options.type_overrides.get(name) {
+      Some(label) => format!("\"{}\"", label),
+      None => "\"UNKNOWN\"".to_string(),
+    },
+  }
+}
+
+pub fn convert_glsl_to_ts_label(uniform: &TypeSpecifierNonArray) -> String {
+  return match glsl_type_label(uniform) {
+    Some(label) => label.to_string(),
+    None => {
+      let type_name = format!("{:?}", uniform);
+
+      print_diagnostic(
+        Level::WARN,
+        "UNSUPPORTED_TYPE",
+        &[
+          Segment::Text("Unsupported type "),
+          Segment::Highlight(&type_name),
+          Segment::Text(", it will be typed as UNKNOWN."),
+        ],
+        vec![("type", Json::String(type_name.clone()))],
+      );
+      "UNKNOWN".to_string()
+    }
+  };
+}
+
+/// GLSL type of a variable with its array size, e.g. `vec3` or `mat4[2]`.
+pub fn variable_type_label(variable: &TypedVariable) -> String {
+  let label = glsl_type_name(&variable.type_label);
+
+  return match variable.array_size {
+    Some(ArraySize::Sized(size)) => format!("{}[{}]", label, size),
+    Some(ArraySize::Unsized) => format!("{}[]", label),
+    None => label,
+  };
+}
+
+/// GLSL spelling of a type, including the ones `glsl_type_label` does not support such as
+/// `mat2x3`, used as the key of the type overrides.
+pub fn glsl_type_name(ty: &TypeSpecifierNonArray) -> String {
+  if let Some(label) = glsl_type_label(ty) {
+    return label.to_string();
+  }
+
+  let name = match ty {
+    TypeSpecifierNonArray::Void => "void",
+    TypeSpecifierNonArray::Mat23 => "mat2x3",
+    TypeSpecifierNonArray::Mat24 => "mat2x4",
+    TypeSpecifierNonArray::Mat32 => "mat3x2",
+    TypeSpecifierNonArray::Mat34 => "mat3x4",
+    TypeSpecifierNonArray::Mat42 => "mat4x2",
+    TypeSpecifierNonArray::Mat43 => "mat4x3",
+    TypeSpecifierNonArray::DMat23 => "dmat2x3",
+    TypeSpecifierNonArray::DMat24 => "dmat2x4",
+    TypeSpecifierNonArray::DMat32 => "dmat3x2",
+    TypeSpecifierNonArray::DMat34 => "dmat3x4",
+    TypeSpecifierNonArray::DMat42 => "dmat4x2",
+    TypeSpecifierNonArray::DMat43 => "dmat4x3",
+    TypeSpecifierNonArray::AtomicUInt => "atomic_uint",
+    TypeSpecifierNonArray::TypeName(name) => return name.as_str().to_string(),
+    _ => return format!("{:?}", ty),
+  };
+
+  return name.to_string();
+}
+
+/// GLSL name of a built-in type, `None` for the types that are not supported.
+pub fn glsl_type_label(uniform: &TypeSpecifierNonArray) -> Option<&'static str> {
   let result = match uniform {
     TypeSpecifierNonArray::Float => "float",
     TypeSpecifierNonArray::Vec2 => "vec2",
@@ -126,8 +902,310 @@ fn convert_glsl_to_ts_label(uniform: &TypeSpecifierNonArray) -> String {
     TypeSpecifierNonArray::Mat2 => "mat2",
     TypeSpecifierNonArray::Mat3 => "mat3",
     TypeSpecifierNonArray::Mat4 => "mat4",
-    _ => "UNKNOWN",
+
+    TypeSpecifierNonArray::Double => "double",
+    TypeSpecifierNonArray::DVec2 => "dvec2",
+    TypeSpecifierNonArray::DVec3 => "dvec3",
+    TypeSpecifierNonArray::DVec4 => "dvec4",
+
+    TypeSpecifierNonArray::DMat2 => "dmat2",
+    TypeSpecifierNonArray::DMat3 => "dmat3",
+    TypeSpecifierNonArray::DMat4 => "dmat4",
+
+    TypeSpecifierNonArray::Sampler1D => "sampler1D",
+    TypeSpecifierNonArray::Sampler2D => "sampler2D",
+    TypeSpecifierNonArray::Sampler3D => "sampler3D",
+    TypeSpecifierNonArray::SamplerCube => "samplerCube",
+    TypeSpecifierNonArray::Sampler2DRect => "sampler2DRect",
+    TypeSpecifierNonArray::Sampler1DArray => "sampler1DArray",
+    TypeSpecifierNonArray::Sampler2DArray => "sampler2DArray",
+    TypeSpecifierNonArray::SamplerBuffer => "samplerBuffer",
+    TypeSpecifierNonArray::Sampler2DMS => "sampler2DMS",
+    TypeSpecifierNonArray::Sampler2DMSArray => "sampler2DMSArray",
+    TypeSpecifierNonArray::SamplerCubeArray => "samplerCubeArray",
+
+    TypeSpecifierNonArray::Sampler1DShadow => "sampler1DShadow",
+    TypeSpecifierNonArray::Sampler2DShadow => "sampler2DShadow",
+    TypeSpecifierNonArray::Sampler2DRectShadow => "sampler2DRectShadow",
+    TypeSpecifierNonArray::Sampler1DArrayShadow => "sampler1DArrayShadow",
+    TypeSpecifierNonArray::Sampler2DArrayShadow => "sampler2DArrayShadow",
+    TypeSpecifierNonArray::SamplerCubeShadow => "samplerCubeShadow",
+    TypeSpecifierNonArray::SamplerCubeArrayShadow => "samplerCubeArrayShadow",
+
+    TypeSpecifierNonArray::ISampler1D => "isampler1D",
+    TypeSpecifierNonArray::ISampler2D => "isampler2D",
+    TypeSpecifierNonArray::ISampler3D => "isampler3D",
+    TypeSpecifierNonArray::ISamplerCube => "isamplerCube",
+    TypeSpecifierNonArray::ISampler2DRect => "isampler2DRect",
+    TypeSpecifierNonArray::ISampler1DArray => "isampler1DArray",
+    TypeSpecifierNonArray::ISampler2DArray => "isampler2DArray",
+    TypeSpecifierNonArray::ISamplerBuffer => "isamplerBuffer",
+    TypeSpecifierNonArray::ISampler2DMS => "isampler2DMS",
+    TypeSpecifierNonArray::ISampler2DMSArray => "isampler2DMSArray",
+    TypeSpecifierNonArray::ISamplerCubeArray => "isamplerCubeArray",
+
+    TypeSpecifierNonArray::USampler1D => "usampler1D",
+    TypeSpecifierNonArray::USampler2D => "usampler2D",
+    TypeSpecifierNonArray::USampler3D => "usampler3D",
+    TypeSpecifierNonArray::USamplerCube => "usamplerCube",
+    TypeSpecifierNonArray::USampler2DRect => "usampler2DRect",
+    TypeSpecifierNonArray::USampler1DArray => "usampler1DArray",
+    TypeSpecifierNonArray::USampler2DArray => "usampler2DArray",
+    TypeSpecifierNonArray::USamplerBuffer => "usamplerBuffer",
+    TypeSpecifierNonArray::USampler2DMS => "usampler2DMS",
+    TypeSpecifierNonArray::USampler2DMSArray => "usampler2DMSArray",
+    TypeSpecifierNonArray::USamplerCubeArray => "usamplerCubeArray",
+
+    TypeSpecifierNonArray::Image1D => "image1D",
+    TypeSpecifierNonArray::Image2D => "image2D",
+    TypeSpecifierNonArray::Image3D => "image3D",
+    TypeSpecifierNonArray::ImageCube => "imageCube",
+    TypeSpecifierNonArray::Image2DRect => "image2DRect",
+    TypeSpecifierNonArray::Image1DArray => "image1DArray",
+    TypeSpecifierNonArray::Image2DArray => "image2DArray",
+    TypeSpecifierNonArray::ImageBuffer => "imageBuffer",
+    TypeSpecifierNonArray::Image2DMS => "image2DMS",
+    TypeSpecifierNonArray::Image2DMSArray => "image2DMSArray",
+    TypeSpecifierNonArray::ImageCubeArray => "imageCubeArray",
+
+    TypeSpecifierNonArray::IImage1D => "iimage1D",
+    TypeSpecifierNonArray::IImage2D => "iimage2D",
+    TypeSpecifierNonArray::IImage3D => "iimage3D",
+    TypeSpecifierNonArray::IImageCube => "iimageCube",
+    TypeSpecifierNonArray::IImage2DRect => "iimage2DRect",
+    TypeSpecifierNonArray::IImage1DArray => "iimage1DArray",
+    TypeSpecifierNonArray::IImage2DArray => "iimage2DArray",
+    TypeSpecifierNonArray::IImageBuffer => "iimageBuffer",
+    TypeSpecifierNonArray::IImage2DMS => "iimage2DMS",
+    TypeSpecifierNonArray::IImage2DMSArray => "iimage2DMSArray",
+    TypeSpecifierNonArray::IImageCubeArray => "iimageCubeArray",
+
+    TypeSpecifierNonArray::UImage1D => "uimage1D",
+    TypeSpecifierNonArray::UImage2D => "uimage2D",
+    TypeSpecifierNonArray::UImage3D => "uimage3D",
+    TypeSpecifierNonArray::UImageCube => "uimageCube",
+    TypeSpecifierNonArray::UImage2DRect => "uimage2DRect",
+    TypeSpecifierNonArray::UImage1DArray => "uimage1DArray",
+    TypeSpecifierNonArray::UImage2DArray => "uimage2DArray",
+    TypeSpecifierNonArray::UImageBuffer => "uimageBuffer",
+    TypeSpecifierNonArray::UImage2DMS => "uimage2DMS",
+    TypeSpecifierNonArray::UImage2DMSArray => "uimage2DMSArray",
+    TypeSpecifierNonArray::UImageCubeArray => "uimageCubeArray",
+
+    _ => return None,
+  };
+
+  return Some(result);
+}
+
+/// `/** ... */` line above a uniform with a doc comment, empty for the others.
+pub fn js_doc(doc_comment: &Option<String>, indent: &str) -> String {
+  return match doc_comment {
+    Some(doc_comment) => format!("{}/** {} */\n", indent, doc_comment.replace("*/", "*\\/")),
+    None => String::new(),
   };
+}
+
+/// `export interface <Name>Uniforms { ... }` with the types accepted by the WebGL `uniform*` calls.
+fn uniforms_interface_declaration(type_name: &str, shader_data: &ShaderData) -> String {
+  let fields = shader_data
+    .uniforms
+    .iter()
+    .map(|uniform| {
+      format!(
+        "{}  {}: {};\n",
+        js_doc(&uniform.doc_comment, "  "),
+        uniform.identifier,
+        convert_glsl_to_ts_interface_type(&shader_data.resolve_variable_type(uniform), 1)
+      )
+    })
+    .collect::<String>();
+
+  return format!("export interface {}Uniforms {{\n{}}}\n", type_name, fields);
+}
 
-  return result.to_string();
+/// TypeScript type of a uniform in the generated interface: typed arrays for vectors, matrices
+/// and arrays of them, `number` for scalars and texture units and object types for structs.
+pub fn convert_glsl_to_ts_interface_type(ty: &GlslType, depth: usize) -> String {
+  let typed_array = |ty: &TypeSpecifierNonArray| match common::scalar_kind(ty) {
+    Some(ScalarKind::Float) => "Float32Array",
+    Some(ScalarKind::Int) => "Int32Array",
+    Some(ScalarKind::UInt) => "Uint32Array",
+    Some(ScalarKind::Bool) => "boolean[]",
+    None => "number[]",
+  };
+
+  match ty {
+    GlslType::Scalar(ty) => match common::scalar_kind(ty) {
+      Some(ScalarKind::Bool) => "boolean".to_string(),
+      _ => "number".to_string(),
+    },
+    GlslType::Vector(ty) | GlslType::Matrix(ty) => typed_array(ty).to_string(),
+    GlslType::Opaque(ty) => match common::double_component_count(ty) {
+      Some(1) => "number".to_string(),
+      Some(_) => "Float64Array".to_string(),
+      // Samplers and images are set to the index of a texture or image unit
+      None if glsl_type_label(ty).is_some() => "number".to_string(),
+      None => "unknown".to_string(),
+    },
+    GlslType::Array(element, _) => match element.as_ref() {
+      GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) => {
+        typed_array(ty).to_string()
+      }
+      GlslType::Opaque(ty) if common::double_component_count(ty).is_some() => {
+        "Float64Array".to_string()
+      }
+      GlslType::Opaque(_) => "Int32Array".to_string(),
+      element => format!(
+        "Array<{}>",
+        convert_glsl_to_ts_interface_type(element, depth)
+      ),
+    },
+    GlslType::Struct { fields, .. } => {
+      let fields = fields
+        .iter()
+        .map(|field| {
+          format!(
+            "{}{}: {};\n",
+            "  ".repeat(depth + 1),
+            field.identifier,
+            convert_glsl_to_ts_interface_type(&field.ty, depth + 1)
+          )
+        })
+        .collect::<String>();
+
+      format!("{{\n{}{}}}", fields, "  ".repeat(depth))
+    }
+    GlslType::Unknown(_) => "unknown".to_string(),
+  }
+}
+
+/// TypeScript type of a uniform value, e.g. `[number, number]` for `vec2`.
+pub fn convert_glsl_to_ts_value_type(ty: &TypeSpecifierNonArray) -> String {
+  let element = match common::scalar_kind(ty) {
+    Some(ScalarKind::Bool) => "boolean",
+    Some(_) => "number",
+    None => return "unknown".to_string(),
+  };
+
+  return match common::component_count(ty) {
+    Some(1) => element.to_string(),
+    Some(count) => format!("[{}]", vec![element; count].join(", ")),
+    None => "unknown".to_string(),
+  };
+}
+
+/// `export type <Name>Uniforms = { ... };` for the uniforms that hold plain values.
+pub fn uniforms_type_declaration(type_name: &str, shader_data: &ShaderData) -> String {
+  let fields = common::value_uniforms(shader_data)
+    .iter()
+    .map(|uniform| {
+      format!(
+        "{}  {}: {};",
+        js_doc(&uniform.doc_comment, "  "),
+        uniform.identifier,
+        convert_glsl_to_ts_value_type(&uniform.type_label)
+      )
+    })
+    .collect::<Vec<String>>()
+    .join("\n");
+
+  if fields.is_empty() {
+    return format!("export type {}Uniforms = {{}};\n", type_name);
+  }
+
+  return format!("export type {}Uniforms = {{\n{}\n}};\n", type_name, fields);
+}
+
+pub fn convert_glsl_to_zod_type(ty: &TypeSpecifierNonArray) -> String {
+  let element = match common::scalar_kind(ty) {
+    Some(ScalarKind::Bool) => "z.boolean()",
+    Some(ScalarKind::Float) => "z.number()",
+    Some(_) => "z.number().int()",
+    None => return "z.unknown()".to_string(),
+  };
+
+  return match common::component_count(ty) {
+    Some(1) => element.to_string(),
+    Some(count) => format!("z.tuple([{}])", vec![element; count].join(", ")),
+    None => "z.unknown()".to_string(),
+  };
+}
+
+/// `export const <Name>UniformsSchema = z.object({ ... });` matching `uniforms_type_declaration`.
+pub fn zod_schema_declaration(type_name: &str, shader_data: &ShaderData) -> String {
+  let fields = common::value_uniforms(shader_data)
+    .iter()
+    .map(|uniform| {
+      format!(
+        "  {}: {},\n",
+        uniform.identifier,
+        convert_glsl_to_zod_type(&uniform.type_label)
+      )
+    })
+    .collect::<String>();
+
+  return format!(
+    "export const {}UniformsSchema = z.object({{\n{}}});\n",
+    type_name, fields
+  );
+}
+
+/// `export const <Name>UniformsSchema = z.object({ ... });` covering every uniform, for the
+/// `--emit-zod` output. Samplers and images are validated as texture or image unit indices.
+fn uniforms_zod_schema_declaration(type_name: &str, shader_data: &ShaderData) -> String {
+  let fields = shader_data
+    .uniforms
+    .iter()
+    .map(|uniform| {
+      format!(
+        "  {}: {},\n",
+        uniform.identifier,
+        convert_glsl_type_to_zod(&shader_data.resolve_variable_type(uniform), 1)
+      )
+    })
+    .collect::<String>();
+
+  return format!(
+    "export const {}UniformsSchema = z.object({{\n{}}});\n",
+    type_name, fields
+  );
+}
+
+fn convert_glsl_type_to_zod(ty: &GlslType, depth: usize) -> String {
+  match ty {
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) => {
+      convert_glsl_to_zod_type(ty)
+    }
+    GlslType::Opaque(ty) => match common::double_component_count(ty) {
+      Some(1) => "z.number()".to_string(),
+      Some(count) => format!("z.tuple([{}])", vec!["z.number()"; count].join(", ")),
+      None if glsl_type_label(ty).is_some() => "z.number()".to_string(),
+      None => "z.unknown()".to_string(),
+    },
+    GlslType::Unknown(_) => "z.unknown()".to_string(),
+    GlslType::Array(element, ArraySize::Sized(size)) => format!(
+      "z.array({}).length({})",
+      convert_glsl_type_to_zod(element, depth),
+      size
+    ),
+    GlslType::Array(element, ArraySize::Unsized) => {
+      format!("z.array({})", convert_glsl_type_to_zod(element, depth))
+    }
+    GlslType::Struct { fields, .. } => {
+      let fields = fields
+        .iter()
+        .map(|field| {
+          format!(
+            "{}{}: {},\n",
+            "  ".repeat(depth + 1),
+            field.identifier,
+            convert_glsl_type_to_zod(&field.ty, depth + 1)
+          )
+        })
+        .collect::<String>();
+
+      format!("z.object({{\n{}{}}})", fields, "  ".repeat(depth))
+    }
+  }
 }
@@ -0,0 +1,99 @@
+//! Finds the uniforms and attributes a shader declares but never reads, for `--warn-unused`.
+
+use super::common;
+use crate::utils::get_shader_type::ShaderType;
+use glsl::parser::Parse as _;
+use glsl::syntax::{Expr, ShaderStage};
+use glsl::visitor::{Host, Visit, Visitor};
+use std::collections::HashSet;
+
+/// Every identifier used as a variable in an expression.
+#[derive(Default)]
+struct ReferenceCollector {
+  identifiers: HashSet<String>,
+}
+
+impl Visitor for ReferenceCollector {
+  fn visit_expr(&mut self, expr: &Expr) -> Visit {
+    if let Expr::Variable(identifier) = expr {
+      self.identifiers.insert(identifier.0.clone());
+    }
+
+    return Visit::Children;
+  }
+}
+
+/// `("uniform", name)` and `("attribute", name)` of the variables no expression refers to, in
+/// declaration order. Shaders that do not parse have none, the backend reports the error.
+pub fn unused_variables(content: &str, shader_type: ShaderType) -> Vec<(&'static str, String)> {
+  let stage = match ShaderStage::parse(content) {
+    Ok(stage) => stage,
+    Err(_) => return Vec::new(),
+  };
+
+  let shader_data = match common::shader_data_from_stage(&stage, shader_type) {
+    Ok(shader_data) => shader_data,
+    Err(_) => return Vec::new(),
+  };
+
+  let mut references = ReferenceCollector::default();
+  stage.visit(&mut references);
+
+  // Only the inputs of a vertex shader are attributes, the others are varyings
+  let attributes = match shader_data.shader_type {
+    ShaderType::Vertex => shader_data.ins.as_slice(),
+    _ => &[],
+  };
+
+  let mut unused = Vec::new();
+
+  for (kind, variables) in [
+    ("uniform", shader_data.uniforms.as_slice()),
+    ("attribute", attributes),
+  ] {
+    for variable in variables {
+      let is_reported = unused
+        .iter()
+        .any(|(_, identifier)| *identifier == variable.identifier);
+
+      // A uniform can be declared again by an imported file
+      if !references.identifiers.contains(&variable.identifier) && !is_reported {
+        unused.push((kind, variable.identifier.clone()));
+      }
+    }
+  }
+
+  return unused;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reports_uniforms_and_attributes_that_are_never_read() {
+    let source = "#version 300 es\nuniform mat4 model;\nuniform float time;\nin vec3 position;\nin vec2 uv;\nvoid main() {\n  gl_Position = model * vec4(position, 1.0);\n}\n";
+
+    assert_eq!(
+      unused_variables(source, ShaderType::Vertex),
+      vec![
+        ("uniform", "time".to_string()),
+        ("attribute", "uv".to_string())
+      ]
+    );
+  }
+
+  #[test]
+  fn reads_inside_functions_and_struct_members_count() {
+    let source = "#version 300 es\nprecision highp float;\nstruct Light { vec3 color; };\nuniform Light light;\nuniform sampler2D albedo;\nin vec2 vUv;\nout vec4 color;\nvec3 shade() { return light.color; }\nvoid main() {\n  color = texture(albedo, vUv) * vec4(shade(), 1.0);\n}\n";
+
+    assert!(unused_variables(source, ShaderType::Fragment).is_empty());
+  }
+
+  #[test]
+  fn fragment_inputs_are_not_attributes() {
+    let source = "#version 300 es\nprecision highp float;\nin vec2 vUv;\nout vec4 color;\nvoid main() {\n  color = vec4(1.0);\n}\n";
+
+    assert!(unused_variables(source, ShaderType::Fragment).is_empty());
+  }
+}
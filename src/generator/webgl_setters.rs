@@ -0,0 +1,145 @@
+use super::common::{self, GlslType, ScalarKind, ShaderData};
+use super::type_script;
+use glsl::syntax::TypeSpecifierNonArray;
+
+/// WebGL2 function that sets a uniform of type `ty`, or an array of them when `array` is set,
+/// e.g. `uniform3fv` for `vec3` and `uniformMatrix4fv` for `mat4`. `None` for types WebGL
+/// cannot set, such as doubles.
+pub fn setter_name(ty: &TypeSpecifierNonArray, array: bool) -> Option<String> {
+  if let Some((columns, rows)) = common::matrix_dimensions(ty) {
+    let size = if columns == rows {
+      columns.to_string()
+    } else {
+      format!("{}x{}", columns, rows)
+    };
+
+    return Some(format!("uniformMatrix{}fv", size));
+  }
+
+  let suffix = match common::scalar_kind(ty) {
+    Some(ScalarKind::Float) => "f",
+    Some(ScalarKind::Int) | Some(ScalarKind::Bool) => "i",
+    Some(ScalarKind::UInt) => "ui",
+    // Samplers are set to the index of a texture unit
+    None if is_sampler(ty) => "i",
+    None => return None,
+  };
+
+  let count = common::component_count(ty).unwrap_or(1);
+
+  if count == 1 && !array {
+    return Some(format!("uniform1{}", suffix));
+  }
+
+  return Some(format!("uniform{}{}v", count, suffix));
+}
+
+pub fn is_sampler(ty: &TypeSpecifierNonArray) -> bool {
+  return common::double_component_count(ty).is_none()
+    && type_script::glsl_type_label(ty).is_some_and(|label| label.contains("sampler"));
+}
+
+/// `export function set<Name>Uniforms(gl, program, uniforms)`, which sets every uniform of
+/// the `<Name>Uniforms` interface on `program`.
+pub fn setter_function(type_name: &str, shader_data: &ShaderData) -> String {
+  let statements = shader_data
+    .uniforms
+    .iter()
+    .map(|uniform| {
+      setter_statements(
+        &shader_data.resolve_variable_type(uniform),
+        &uniform.identifier,
+        &format!("uniforms.{}", uniform.identifier),
+        1,
+      )
+    })
+    .collect::<String>();
+
+  return format!(
+    "export function set{0}Uniforms(\n  gl: WebGL2RenderingContext,\n  program: WebGLProgram,\n  uniforms: {0}Uniforms\n): void {{\n{1}}}\n",
+    type_name, statements
+  );
+}
+
+/// `<Name>UniformLocations` and `get<Name>Locations(gl, program)`, which looks up the location
+/// of every uniform once so callers do not repeat the names. Struct uniforms have no location
+/// of their own and are `null`, their members are set by the setter function.
+pub fn location_cache(type_name: &str, shader_data: &ShaderData) -> String {
+  let locations = shader_data
+    .uniforms
+    .iter()
+    .map(|uniform| {
+      format!(
+        "    {0}: gl.getUniformLocation(program, \"{0}\"),\n",
+        uniform.identifier
+      )
+    })
+    .collect::<String>();
+
+  return format!(
+    "export type {0}UniformLocations = {{\n  [K in keyof {0}Uniforms]: WebGLUniformLocation | null;\n}};\n\nexport function get{0}Locations(\n  gl: WebGL2RenderingContext,\n  program: WebGLProgram\n): {0}UniformLocations {{\n  return {{\n{1}  }};\n}}\n",
+    type_name, locations
+  );
+}
+
+// `name` is the GLSL name of the uniform, which can contain `${i}` for array indices
+fn setter_statements(ty: &GlslType, name: &str, value: &str, depth: usize) -> String {
+  let indent = "  ".repeat(depth);
+  let location = if name.contains("${") {
+    format!("gl.getUniformLocation(program, `{}`)", name)
+  } else {
+    format!("gl.getUniformLocation(program, \"{}\")", name)
+  };
+
+  let call = |ty: &TypeSpecifierNonArray, array: bool, value: String| match setter_name(ty, array) {
+    Some(setter) if common::is_matrix(ty) => {
+      format!("{}gl.{}({}, false, {});\n", indent, setter, location, value)
+    }
+    Some(setter) => format!("{}gl.{}({}, {});\n", indent, setter, location, value),
+    None => String::new(),
+  };
+
+  let is_bool = |ty: &TypeSpecifierNonArray| common::scalar_kind(ty) == Some(ScalarKind::Bool);
+
+  match ty {
+    GlslType::Scalar(ty) if is_bool(ty) => call(ty, false, format!("{} ? 1 : 0", value)),
+    GlslType::Vector(ty) if is_bool(ty) => call(ty, false, format!("{}.map(Number)", value)),
+    GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) | GlslType::Opaque(ty) => {
+      call(ty, false, value.to_string())
+    }
+    GlslType::Array(element, _) => match element.as_ref() {
+      GlslType::Scalar(ty) | GlslType::Vector(ty) if is_bool(ty) => {
+        call(ty, true, format!("{}.map(Number)", value))
+      }
+      GlslType::Scalar(ty) | GlslType::Vector(ty) | GlslType::Matrix(ty) | GlslType::Opaque(ty) => {
+        call(ty, true, value.to_string())
+      }
+      element => {
+        let index = format!("i{}", depth - 1);
+        let body = setter_statements(
+          element,
+          &format!("{}[${{{}}}]", name, index),
+          "element",
+          depth + 1,
+        );
+
+        format!(
+          "{0}{1}.forEach((element, {2}) => {{\n{3}{0}}});\n",
+          indent, value, index, body
+        )
+      }
+    },
+    GlslType::Struct { fields, .. } => fields
+      .iter()
+      .map(|field| {
+        setter_statements(
+          &field.ty,
+          &format!("{}.{}", name, field.identifier),
+          &format!("{}.{}", value, field.identifier),
+          depth,
+        )
+      })
+      .collect(),
+    GlslType::Unknown(_) => String::new(),
+  }
+}
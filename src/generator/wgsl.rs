@@ -0,0 +1,220 @@
+use super::common::{
+  self, ArraySize, GeneratorError, GlslType, ScalarKind, ShaderData, StructField,
+};
+use super::layout::{self, LayoutRules};
+use crate::options::Options;
+use crate::write_file;
+
+pub fn generate_types_file(
+  source: &common::ShaderSource,
+  file_path: &std::path::PathBuf,
+  output_folder: &std::path::PathBuf,
+  options: &Options,
+) -> Result<(), GeneratorError> {
+  let shader_type = common::shader_type(file_path, options)?;
+  let shader_data = common::extract_shader_data(&source.original, shader_type)?;
+
+  let output_file_name = file_path.file_stem().unwrap().to_str().unwrap();
+  let output_file = render_bindings_file(&shader_data);
+
+  let output_file_path = output_folder.join(format!("{}.wgsl", output_file_name));
+  write_file(output_file_path.to_str().unwrap(), output_file.as_str());
+
+  return Ok(());
+}
+
+/// WGSL structs with the memory layout of the uniform and storage blocks of the shader and a
+/// `var` declaration binding each of them.
+pub fn render_bindings_file(shader_data: &ShaderData) -> String {
+  let mut structs = Vec::new();
+  let mut bindings = String::new();
+
+  // Uniform blocks use the std140 layout, storage buffers std430 which WGSL has by default
+  let blocks = shader_data
+    .uniform_blocks
+    .iter()
+    .map(|block| {
+      (
+        &block.name,
+        block.instance_name.as_ref(),
+        block.binding,
+        block.set,
+        &block.fields,
+        true,
+      )
+    })
+    .chain(shader_data.buffers.iter().map(|buffer| {
+      (
+        &buffer.name,
+        None,
+        buffer.binding,
+        buffer.set,
+        &buffer.fields,
+        false,
+      )
+    }));
+
+  for (name, instance_name, binding, set, fields, uniform) in blocks {
+    if render_struct(name, fields, uniform, &mut structs).is_none() {
+      bindings.push_str(&format!(
+        "// {} has members without a WGSL equivalent\n",
+        name
+      ));
+      continue;
+    }
+
+    let binding = match binding {
+      Some(binding) => binding,
+      None => {
+        bindings.push_str(&format!("// {} has no layout(binding = N)\n", name));
+        continue;
+      }
+    };
+
+    // Module scope names are shared between structs and variables
+    let mut variable = match instance_name {
+      Some(instance_name) => instance_name.clone(),
+      None => common::to_snake_case(name).trim_matches('_').to_string(),
+    };
+
+    if variable == *name {
+      variable.push('_');
+    }
+
+    let address_space = if uniform {
+      "uniform"
+    } else {
+      "storage, read_write"
+    };
+
+    bindings.push_str(&format!(
+      "@group({}) @binding({}) var<{}> {}: {};\n",
+      set.unwrap_or(0),
+      binding,
+      address_space,
+      variable,
+      name
+    ));
+  }
+
+  let mut output_file = String::new();
+  output_file.push_str(common::GENERATED_FILE_HEADER);
+
+  for definition in structs {
+    output_file.push_str(&definition);
+    output_file.push('\n');
+  }
+
+  output_file.push_str(&bindings);
+
+  return output_file;
+}
+
+/// Renders `struct <name>` into `structs`, after the structs of its fields. Under std140 each
+/// member gets a `@size` when the padding after it differs from WGSL's, so the offsets match.
+/// `None` when a field has no WGSL type.
+fn render_struct(
+  name: &str,
+  fields: &[StructField],
+  uniform: bool,
+  structs: &mut Vec<String>,
+) -> Option<()> {
+  let mut members = Vec::new();
+
+  for field in fields {
+    let ty = wgsl_type(&field.identifier, &field.ty, uniform, structs)?;
+    members.push((field, ty));
+  }
+
+  let mut sizes = vec![None; members.len()];
+
+  if uniform {
+    let mut offsets = Vec::new();
+    let mut offset: usize = 0;
+
+    for (field, _) in &members {
+      let (alignment, size) = layout::type_alignment_and_size(&field.ty, LayoutRules::Std140)?;
+      offset = offset.div_ceil(alignment) * alignment;
+      offsets.push((offset, size));
+      offset += size;
+    }
+
+    let struct_type = GlslType::Struct {
+      name: None,
+      fields: fields.to_vec(),
+    };
+    let (_, struct_size) = layout::type_alignment_and_size(&struct_type, LayoutRules::Std140)?;
+
+    for (i, (offset, size)) in offsets.iter().enumerate() {
+      let next = offsets.get(i + 1).map_or(struct_size, |(next, _)| *next);
+
+      if next - offset != *size {
+        sizes[i] = Some(next - offset);
+      }
+    }
+  }
+
+  let members = members
+    .iter()
+    .zip(sizes)
+    .map(|((field, ty), size)| match size {
+      Some(size) => format!("  @size({}) {}: {},\n", size, field.identifier, ty),
+      None => format!("  {}: {},\n", field.identifier, ty),
+    })
+    .collect::<String>();
+
+  let definition = format!("struct {} {{\n{}}}\n", name, members);
+
+  if !structs.contains(&definition) {
+    structs.push(definition);
+  }
+
+  return Some(());
+}
+
+// Under std140 (`uniform`) the types are chosen so their size is the std140 size
+fn wgsl_type(
+  identifier: &str,
+  ty: &GlslType,
+  uniform: bool,
+  structs: &mut Vec<String>,
+) -> Option<String> {
+  match ty {
+    GlslType::Scalar(ty) | GlslType::Vector(ty) => common::convert_glsl_to_wgsl_type(ty),
+    // std140 pads the columns of a mat2 to 16 bytes
+    GlslType::Matrix(ty) if uniform && common::matrix_dimensions(ty) == Some((2, 2)) => {
+      Some("mat2x4f".to_string())
+    }
+    GlslType::Matrix(ty) => common::convert_glsl_to_wgsl_type(ty),
+    GlslType::Array(element, size) => {
+      let element_type = match element.as_ref() {
+        // std140 pads every array element to 16 bytes, so small elements are stored in a vec4
+        GlslType::Scalar(ty) | GlslType::Vector(ty)
+          if uniform && common::component_count(ty)? <= 2 =>
+        {
+          match common::scalar_kind(ty)? {
+            ScalarKind::Float => "vec4f".to_string(),
+            ScalarKind::Int => "vec4i".to_string(),
+            ScalarKind::UInt | ScalarKind::Bool => "vec4u".to_string(),
+          }
+        }
+        element => wgsl_type(identifier, element, uniform, structs)?,
+      };
+
+      match size {
+        ArraySize::Sized(size) => Some(format!("array<{}, {}>", element_type, size)),
+        ArraySize::Unsized => Some(format!("array<{}>", element_type)),
+      }
+    }
+    GlslType::Struct { name, fields } => {
+      let name = match name {
+        Some(name) => name.clone(),
+        None => common::capitalize_first_letter(identifier),
+      };
+
+      render_struct(&name, fields, uniform, structs)?;
+      Some(name)
+    }
+    GlslType::Opaque(_) | GlslType::Unknown(_) => None,
+  }
+}
@@ -17,7 +17,10 @@ pub fn get_file_data(file_path: &PathBuf) -> Result<ImportedFile, ImportError> {
     return Err(ImportError::FileNotFound(file_path.to_path_buf()));
   }
 
-  let contents = read_file(file_path_string);
+  let contents = match read_file(file_path_string) {
+    Some(contents) => contents,
+    None => return Err(ImportError::CouldNotReadFile(file_path.to_path_buf())),
+  };
 
   let ast = match ShaderStage::parse(&contents) {
     Ok(ast) => ast,
@@ -42,6 +45,7 @@ pub fn get_file_data(file_path: &PathBuf) -> Result<ImportedFile, ImportError> {
 }
 
 #[derive(Clone, Debug)]
+#[allow(dead_code)]
 pub struct ImportedFile {
   pub path: PathBuf,
   pub structs: Vec<String>,
@@ -91,9 +95,11 @@ impl Visitor for ImportedFileVisitor {
 
   fn visit_preprocessor_include(&mut self, import: &PreprocessorInclude) -> Visit {
     let path = match &import.path {
-      glsl::syntax::Path::Absolute(path) => PathBuf::from(path),
+      // Canonicalized too, so the same file is always the same path in the import graph
+      glsl::syntax::Path::Absolute(path) => PathBuf::from(canonicalize(path)),
       glsl::syntax::Path::Relative(path) => {
-        PathBuf::from(canonicalize(self.parent_path.join(path).to_str().unwrap()))
+        let path = join_import_path(&self.parent_path, path);
+        PathBuf::from(canonicalize(path.to_str().unwrap()))
       }
     };
 
@@ -107,3 +113,42 @@ impl Visitor for ImportedFileVisitor {
     Visit::Parent
   }
 }
+
+/// `path` of an `#include` relative to `base_path`. GLSL paths are separated by `/`, but shaders
+/// written on Windows can use `\`, so each component is pushed on its own instead of relying on
+/// how the platform parses the whole string.
+fn join_import_path(base_path: &PathBuf, path: &str) -> PathBuf {
+  let mut joined = base_path.clone();
+
+  for component in path.replace('\\', "/").split('/') {
+    if !component.is_empty() {
+      joined.push(component);
+    }
+  }
+
+  return joined;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn joins_each_component_of_an_import_path() {
+    let base_path = PathBuf::from("shaders");
+    let expected = base_path.join("lib").join("noise").join("simplex.glsl");
+
+    assert_eq!(
+      join_import_path(&base_path, "lib/noise/simplex.glsl"),
+      expected
+    );
+    assert_eq!(
+      join_import_path(&base_path, "lib\\noise\\simplex.glsl"),
+      expected
+    );
+    assert_eq!(
+      join_import_path(&base_path, "lib//noise/simplex.glsl"),
+      expected
+    );
+  }
+}
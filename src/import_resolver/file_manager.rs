@@ -1,6 +1,18 @@
 use super::file::{get_file_data, ImportedFile};
 use super::import_resolver::ImportError;
-use std::{collections::HashMap, path::PathBuf};
+use crate::utils::json::Json;
+use crate::utils::log::{print_diagnostic, Level, Segment};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf};
+
+thread_local! {
+  // Parsed files shared by every resolution, so a file imported by several shaders is only
+  // read and parsed once. The watcher invalidates entries when files change.
+  static PARSE_CACHE: RefCell<HashMap<PathBuf, ImportedFile>> = RefCell::new(HashMap::new());
+}
+
+pub fn invalidate_cached_file(file_path: &PathBuf) {
+  PARSE_CACHE.with(|cache| cache.borrow_mut().remove(file_path));
+}
 
 #[derive(Debug)]
 pub struct FileManager {
@@ -19,7 +31,29 @@ impl FileManager {
       return Ok(file.clone());
     }
 
-    let file = get_file_data(file_path)?;
+    let file = match PARSE_CACHE.with(|cache| cache.borrow().get(file_path).cloned()) {
+      Some(file) => {
+        let file_name = file_path.file_name().unwrap().to_str().unwrap();
+
+        print_diagnostic(
+          Level::DEBUG,
+          "CACHE_HIT",
+          &[
+            Segment::Text("Cache hit for "),
+            Segment::Highlight(file_name),
+          ],
+          vec![("file", Json::string(file_path.to_str().unwrap()))],
+        );
+
+        file
+      }
+      None => {
+        let file = get_file_data(file_path)?;
+        PARSE_CACHE.with(|cache| cache.borrow_mut().insert(file_path.clone(), file.clone()));
+        file
+      }
+    };
+
     self.files.insert(file_path.clone(), file.clone());
 
     return Ok(file);
@@ -1,5 +1,6 @@
-use crate::utils::log::{print_level, Level};
-use crate::{file_exists, log, log_with_color, logln};
+use crate::utils::json::Json;
+use crate::utils::log::{print_diagnostic, Level, Segment};
+use crate::{canonicalize, file_exists};
 use core::fmt;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
@@ -25,8 +26,13 @@ impl Display for DefinitionErrorType {
 
 #[derive(Debug, Clone)]
 pub enum ImportError {
-  CycleDetected(PathBuf, PathBuf),
+  /// Files in import order, starting and ending with the same file.
+  Cycle {
+    chain: Vec<PathBuf>,
+  },
   CouldNotParseFile(PathBuf),
+  /// The file exists but reading it failed, e.g. because of its permissions.
+  CouldNotReadFile(PathBuf),
   FileNotFound(PathBuf),
   ImportNotFound(PathBuf, PathBuf),
   DuplicateImport(PathBuf, PathBuf),
@@ -38,54 +44,141 @@ pub enum ImportError {
   },
 }
 
-pub fn try_resolve_imports(file: &PathBuf, input_folder_parent: &PathBuf) -> Option<String> {
+/// File and zero based line number a line of the combined source was copied from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLine {
+  pub file: PathBuf,
+  pub line: usize,
+}
+
+/// Combined source and, for each of its lines, the line it came from. Lines added by the
+/// resolver, like the `// File:` headers, have no origin.
+#[derive(Debug, Clone)]
+pub struct ResolvedSource {
+  pub source: String,
+  pub lines: Vec<Option<SourceLine>>,
+}
+
+pub fn try_resolve_imports(
+  file: &PathBuf,
+  input_folder_parent: &PathBuf,
+) -> Option<ResolvedSource> {
   match resolve_imports(file) {
     Ok(output) => Some(output),
     Err(err) => {
+      // Absolute imports can point outside of the input folder, those are shown in full
+      let relative = |path: &PathBuf| {
+        path
+          .strip_prefix(input_folder_parent)
+          .unwrap_or(path)
+          .to_str()
+          .unwrap()
+          .to_string()
+      };
+
       match err {
         ImportError::ImportNotFound(file_path, import_path) => {
-          let file_path = file_path.strip_prefix(input_folder_parent).unwrap();
-          let import_path = import_path.strip_prefix(input_folder_parent).unwrap();
-          print_level(Level::ERROR);
-          log("The file ");
-          log_with_color(import_path.to_str().unwrap(), "blue");
-          log(" is trying to import a file that does not exist: ");
-          log_with_color(file_path.to_str().unwrap(), "blue");
-          logln("");
+          let file_path = relative(&file_path);
+          let import_path = relative(&import_path);
+
+          print_diagnostic(
+            Level::ERROR,
+            "IMPORT_NOT_FOUND",
+            &[
+              Segment::Text("The file "),
+              Segment::Highlight(&file_path),
+              Segment::Text(" is trying to import a file that does not exist: "),
+              Segment::Highlight(&import_path),
+            ],
+            vec![
+              ("file", Json::string(&file_path)),
+              ("import", Json::string(&import_path)),
+            ],
+          );
         }
         ImportError::CouldNotParseFile(file_path) => {
-          let file_path = file_path.strip_prefix(input_folder_parent).unwrap();
-          print_level(Level::ERROR);
-          log("Could not parse file: ");
-          log_with_color(file_path.to_str().unwrap(), "blue");
-          logln("");
+          let file_path = relative(&file_path);
+
+          print_diagnostic(
+            Level::ERROR,
+            "PARSE_ERROR",
+            &[
+              Segment::Text("Could not parse file: "),
+              Segment::Highlight(&file_path),
+            ],
+            vec![("file", Json::string(&file_path))],
+          );
+        }
+        ImportError::Cycle { chain } => {
+          let chain = chain.iter().map(relative).collect::<Vec<String>>();
+
+          let mut segments = vec![Segment::Text("Circular import detected: ")];
+
+          for (i, file_path) in chain.iter().enumerate() {
+            if i > 0 {
+              segments.push(Segment::Text(" -> "));
+            }
+
+            segments.push(Segment::Highlight(file_path));
+          }
+
+          print_diagnostic(
+            Level::ERROR,
+            "IMPORT_CYCLE",
+            &segments,
+            vec![
+              ("file", Json::string(&chain[0])),
+              (
+                "chain",
+                Json::Array(chain.iter().map(|path| Json::string(path)).collect()),
+              ),
+            ],
+          );
         }
-        ImportError::CycleDetected(file_path, import_path) => {
-          let file_path = file_path.strip_prefix(input_folder_parent).unwrap();
-          let import_path = import_path.strip_prefix(input_folder_parent).unwrap();
-          print_level(Level::ERROR);
-          log("Cycle detected between files: ");
-          log_with_color(file_path.to_str().unwrap(), "blue");
-          log(" and ");
-          log_with_color(import_path.to_str().unwrap(), "blue");
-          logln("");
+        ImportError::CouldNotReadFile(file_path) => {
+          let file_path = relative(&file_path);
+
+          print_diagnostic(
+            Level::ERROR,
+            "FILE_NOT_READABLE",
+            &[
+              Segment::Text("Could not read file: "),
+              Segment::Highlight(&file_path),
+            ],
+            vec![("file", Json::string(&file_path))],
+          );
         }
         ImportError::FileNotFound(file_path) => {
-          let file_path = file_path.strip_prefix(input_folder_parent).unwrap();
-          print_level(Level::ERROR);
-          log("File not found: ");
-          log_with_color(file_path.to_str().unwrap(), "blue");
-          logln("");
+          let file_path = relative(&file_path);
+
+          print_diagnostic(
+            Level::ERROR,
+            "FILE_NOT_FOUND",
+            &[
+              Segment::Text("File not found: "),
+              Segment::Highlight(&file_path),
+            ],
+            vec![("file", Json::string(&file_path))],
+          );
         }
         ImportError::DuplicateImport(file_path, import_path) => {
-          let file_path = file_path.strip_prefix(input_folder_parent).unwrap();
-          let import_path = import_path.strip_prefix(input_folder_parent).unwrap();
-          print_level(Level::ERROR);
-          log("Duplicate import of file: ");
-          log_with_color(import_path.to_str().unwrap(), "blue");
-          log(" in file: ");
-          log_with_color(file_path.to_str().unwrap(), "blue");
-          logln("");
+          let file_path = relative(&file_path);
+          let import_path = relative(&import_path);
+
+          print_diagnostic(
+            Level::ERROR,
+            "DUPLICATE_IMPORT",
+            &[
+              Segment::Text("Duplicate import of file: "),
+              Segment::Highlight(&import_path),
+              Segment::Text(" in file: "),
+              Segment::Highlight(&file_path),
+            ],
+            vec![
+              ("file", Json::string(&file_path)),
+              ("import", Json::string(&import_path)),
+            ],
+          );
         }
         ImportError::DuplicateDefinition {
           name,
@@ -93,17 +186,27 @@ pub fn try_resolve_imports(file: &PathBuf, input_folder_parent: &PathBuf) -> Opt
           second_file,
           definition_type,
         } => {
-          let first_file = first_file.strip_prefix(input_folder_parent).unwrap();
-          let second_file = second_file.strip_prefix(input_folder_parent).unwrap();
-
-          print_level(Level::ERROR);
-          log(&format!("Duplicate definition of {} ", definition_type));
-          log_with_color(&name, "blue");
-          log(" in files: ");
-          log_with_color(first_file.to_str().unwrap(), "blue");
-          log(" and ");
-          log_with_color(second_file.to_str().unwrap(), "blue");
-          logln("");
+          let first_file = relative(&first_file);
+          let second_file = relative(&second_file);
+
+          print_diagnostic(
+            Level::ERROR,
+            "DUPLICATE_DEFINITION",
+            &[
+              Segment::Text(&format!("Duplicate definition of {} ", definition_type)),
+              Segment::Highlight(&name),
+              Segment::Text(" in files: "),
+              Segment::Highlight(&first_file),
+              Segment::Text(" and "),
+              Segment::Highlight(&second_file),
+            ],
+            vec![
+              ("file", Json::string(&second_file)),
+              ("name", Json::string(&name)),
+              ("definitionType", Json::String(definition_type.to_string())),
+              ("firstFile", Json::string(&first_file)),
+            ],
+          );
         }
       }
 
@@ -112,15 +215,35 @@ pub fn try_resolve_imports(file: &PathBuf, input_folder_parent: &PathBuf) -> Opt
   }
 }
 
-pub fn resolve_imports(file: &PathBuf) -> Result<String, ImportError> {
+pub fn resolve_imports(file: &PathBuf) -> Result<ResolvedSource, ImportError> {
   let mut resolver = ImportResolver::new();
 
-  resolver.build_import_graph(file)?;
-  let output = resolver.combine_files(file, &mut HashSet::new());
+  // Imports are canonicalized, so the entry file has to be too for cycles through it to be found
+  let file = &PathBuf::from(canonicalize(file.to_str().unwrap()));
+
+  resolver.build_import_graph(file, &mut Vec::new(), &mut HashSet::new())?;
+  let mut output = String::new();
+  let mut origins = Vec::new();
+  resolver.combine_files(file, &mut HashSet::new(), &mut output, &mut origins);
   resolver.check_for_duplicate_definitions()?;
-  let output = move_glsl_version_to_top(output);
-  let output = comment_include(output);
-  return Ok(output);
+
+  let lines = output
+    .lines()
+    .enumerate()
+    .map(|(i, line)| (line.to_string(), origins.get(i).cloned().flatten()))
+    .collect::<Vec<(String, Option<SourceLine>)>>();
+
+  let lines = move_glsl_version_to_top(lines);
+  let lines = comment_include(lines);
+
+  return Ok(ResolvedSource {
+    source: lines
+      .iter()
+      .map(|(line, _)| line.as_str())
+      .collect::<Vec<&str>>()
+      .join("\n"),
+    lines: lines.into_iter().map(|(_, origin)| origin).collect(),
+  });
 }
 
 #[derive(Debug)]
@@ -179,31 +302,52 @@ impl ImportResolver {
     return Ok(());
   }
 
-  fn build_import_graph(&mut self, file_path: &PathBuf) -> Result<&Graph, ImportError> {
-    let file_imports = self.file_manager.get_file_imports(&file_path)?;
+  // `stack` is the chain of files currently being resolved and `visited` the files whose
+  // imports have already been resolved, so shared imports are only walked once
+  fn build_import_graph(
+    &mut self,
+    file_path: &PathBuf,
+    stack: &mut Vec<PathBuf>,
+    visited: &mut HashSet<PathBuf>,
+  ) -> Result<&Graph, ImportError> {
+    if visited.contains(file_path) {
+      return Ok(&self.graph);
+    }
+
+    let file_imports = self.file_manager.get_file_imports(file_path)?;
+    stack.push(file_path.clone());
 
     for path in file_imports {
       if !file_exists(path.to_str().unwrap()) {
         return Err(ImportError::ImportNotFound(file_path.clone(), path.clone()));
       }
 
-      self.graph.add_edge(file_path.clone(), path.clone());
-
-      if self.graph.has_cycle() {
-        return Err(ImportError::CycleDetected(file_path.clone(), path.clone()));
+      if let Some(position) = stack.iter().position(|file| file == &path) {
+        let mut chain = stack[position..].to_vec();
+        chain.push(path.clone());
+        return Err(ImportError::Cycle { chain });
       }
 
-      self.build_import_graph(&path)?;
+      self.graph.add_edge(file_path.clone(), path.clone());
+      self.build_import_graph(&path, stack, visited)?;
     }
 
+    stack.pop();
+    visited.insert(file_path.clone());
+
     return Ok(&self.graph);
   }
 
-  fn combine_files(&mut self, node: &PathBuf, visited: &mut HashSet<PathBuf>) -> String {
-    let mut output = String::new();
-
+  // `origins` gets the source line of every line of `output` that was copied from a file
+  fn combine_files(
+    &mut self,
+    node: &PathBuf,
+    visited: &mut HashSet<PathBuf>,
+    output: &mut String,
+    origins: &mut Vec<Option<SourceLine>>,
+  ) {
     if visited.contains(node) {
-      return output;
+      return;
     }
 
     visited.insert(node.clone());
@@ -211,57 +355,175 @@ impl ImportResolver {
     let file = self.file_manager.get_file(node).unwrap();
 
     for neighbor in file.imports {
-      output += &self.combine_files(&neighbor, visited);
+      self.combine_files(&neighbor, visited, output, origins);
     }
 
     let file_name = node.file_name().unwrap().to_str().unwrap();
-    output += &format!("\n// File: {}\n", file_name);
-    output += &file.contents;
+    *output += &format!("\n// File: {}\n", file_name);
 
-    return output;
+    // The header ends with a newline, so the file starts on a line of its own
+    let first_line = output.matches('\n').count();
+    origins.resize(first_line, None);
+
+    for line in 0..file.contents.lines().count() {
+      origins.push(Some(SourceLine {
+        file: node.clone(),
+        line,
+      }));
+    }
+
+    *output += &file.contents;
   }
 }
 
-fn move_glsl_version_to_top(content: String) -> String {
-  let mut lines = content.lines().collect::<Vec<&str>>();
+fn move_glsl_version_to_top(
+  mut lines: Vec<(String, Option<SourceLine>)>,
+) -> Vec<(String, Option<SourceLine>)> {
   let mut version_line = None;
 
-  for (i, line) in lines.iter().enumerate() {
-    let parts = line.trim().split_whitespace().collect::<Vec<&str>>();
+  for (i, (line, _)) in lines.iter().enumerate() {
+    let parts = line.split_whitespace().collect::<Vec<&str>>();
 
-    if parts.len() >= 2 {
-      if parts[0] == "#" && parts[1] == "version" {
-        version_line = Some(i);
-        break;
-      } else if parts[0] == "#version" {
-        version_line = Some(i);
-        break;
-      }
+    if parts.len() >= 2 && ((parts[0] == "#" && parts[1] == "version") || parts[0] == "#version") {
+      version_line = Some(i);
+      break;
     }
   }
 
   if let Some(version_line) = version_line {
     let version_line_content = lines.remove(version_line);
-    lines.insert(version_line, "\n");
+    lines.insert(version_line, (String::new(), None));
+    lines.insert(version_line, (String::new(), None));
     lines.insert(0, version_line_content);
   }
 
-  return lines.join("\n");
+  return lines;
 }
 
-fn comment_include(content: String) -> String {
-  let lines = content.lines();
+fn comment_include(lines: Vec<(String, Option<SourceLine>)>) -> Vec<(String, Option<SourceLine>)> {
   let mut new_lines = Vec::new();
 
-  for line in lines {
-    let parts = line.trim().split_whitespace().collect::<Vec<&str>>();
+  for (line, origin) in lines {
+    let parts = line.split_whitespace().collect::<Vec<&str>>();
 
     if parts.len() >= 2 && ((parts[0] == "#" && parts[1] == "include") || parts[0] == "#include") {
-      new_lines.push(format!("// {}", line));
+      new_lines.push((format!("// {}", line), origin));
     } else {
-      new_lines.push(line.to_string());
+      new_lines.push((line, origin));
     }
   }
 
-  new_lines.join("\n")
+  new_lines
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::temp_dir::TempDir;
+
+  #[test]
+  fn resolves_a_file_without_imports() {
+    let dir = TempDir::new("no-imports");
+    let shader = dir.write(
+      "shader.frag",
+      "#version 300 es\nuniform float time;\nvoid main() {}\n",
+    );
+
+    let resolved = resolve_imports(&shader).unwrap();
+
+    assert!(resolved.source.starts_with("#version 300 es\n"));
+    assert!(resolved.source.contains("// File: shader.frag\n"));
+    assert!(resolved
+      .source
+      .contains("uniform float time;\nvoid main() {}"));
+  }
+
+  #[test]
+  fn resolves_a_relative_import() {
+    let dir = TempDir::new("relative-import");
+    dir.write("lib/noise.glsl", "float noise(float x) { return x; }\n");
+    let shader = dir.write(
+      "shader.frag",
+      "#version 300 es\n#include \"lib/noise.glsl\"\nvoid main() {}\n",
+    );
+
+    let resolved = resolve_imports(&shader).unwrap();
+
+    let noise = resolved.source.find("float noise(float x)").unwrap();
+    let main = resolved.source.find("void main()").unwrap();
+    assert!(noise < main);
+    assert!(resolved.source.contains("// #include \"lib/noise.glsl\""));
+  }
+
+  #[test]
+  fn resolves_an_absolute_import() {
+    let dir = TempDir::new("absolute-import");
+    let noise = dir.write("noise.glsl", "float noise(float x) { return x; }\n");
+    let shader = dir.write(
+      "shaders/shader.frag",
+      &format!(
+        "#version 300 es\n#include <{}>\nvoid main() {{}}\n",
+        noise.display()
+      ),
+    );
+
+    let resolved = resolve_imports(&shader).unwrap();
+
+    assert!(resolved.source.contains("// File: noise.glsl\n"));
+    assert!(resolved.source.contains("float noise(float x)"));
+  }
+
+  #[test]
+  fn reports_a_missing_import() {
+    let dir = TempDir::new("missing-import");
+    let shader = dir.write(
+      "shader.frag",
+      "#version 300 es\n#include \"missing.glsl\"\nvoid main() {}\n",
+    );
+
+    match resolve_imports(&shader) {
+      Err(ImportError::ImportNotFound(file, import)) => {
+        assert_eq!(file.file_name().unwrap(), "shader.frag");
+        assert_eq!(import, dir.path().join("missing.glsl"));
+      }
+      result => panic!("expected ImportNotFound, got {:?}", result),
+    }
+  }
+
+  #[test]
+  fn reports_a_missing_import_outside_of_the_input_folder() {
+    let dir = TempDir::new("missing-absolute-import");
+    let missing = dir.path().join("missing.glsl");
+    let shader = dir.write(
+      "shaders/shader.frag",
+      &format!(
+        "#version 300 es\n#include <{}>\nvoid main() {{}}\n",
+        missing.display()
+      ),
+    );
+
+    assert!(try_resolve_imports(&shader, &dir.path().join("shaders")).is_none());
+  }
+
+  #[test]
+  fn reports_a_mutual_import() {
+    let dir = TempDir::new("mutual-import");
+    dir.write("a.glsl", "#include \"b.glsl\"\nfloat a() { return 1.0; }\n");
+    dir.write("b.glsl", "#include \"a.glsl\"\nfloat b() { return 2.0; }\n");
+    let shader = dir.write(
+      "shader.frag",
+      "#version 300 es\n#include \"a.glsl\"\nvoid main() {}\n",
+    );
+
+    match resolve_imports(&shader) {
+      Err(ImportError::Cycle { chain }) => {
+        let names = chain
+          .iter()
+          .map(|path| path.file_name().unwrap().to_str().unwrap())
+          .collect::<Vec<&str>>();
+        assert_eq!(names, ["a.glsl", "b.glsl", "a.glsl"]);
+      }
+      result => panic!("expected Cycle, got {:?}", result),
+    }
+  }
 }
@@ -1,15 +1,39 @@
 #![deny(clippy::all)]
+#![allow(
+  clippy::needless_return,
+  clippy::ptr_arg,
+  clippy::module_inception,
+  clippy::upper_case_acronyms
+)]
 
 use wasm_bindgen::prelude::*;
 
+pub mod api;
+#[cfg(feature = "build-script")]
+pub mod build;
 mod cli;
+mod config;
 mod generator;
 mod import_resolver;
+#[cfg(not(target_arch = "wasm32"))]
+mod native_host;
+mod options;
 mod utils;
 
+#[cfg(test)]
+#[path = "../tests/support/temp_dir.rs"]
+mod temp_dir;
+
+#[cfg(not(target_arch = "wasm32"))]
+use native_host::{
+  canonicalize, create_dir_all, file_exists, log, log_with_color, logln, now, read_file, write_file,
+};
+
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 extern "C" {
-  fn read_file(file: String) -> String;
+  /// `None` when the file cannot be read, e.g. while an editor is replacing it.
+  fn read_file(file: String) -> Option<String>;
   fn logln(message: &str);
   fn log(message: &str);
   fn log_with_color(message: &str, color: &str);
@@ -17,11 +41,85 @@ extern "C" {
   fn file_exists(path: &str) -> bool;
   fn create_dir_all(path: &str);
   fn write_file(path: &str, content: &str);
+  /// Milliseconds from a monotonic clock, used to time the debug messages.
+  fn now() -> f64;
+}
+
+#[wasm_bindgen]
+pub fn start_cli(
+  file_path: String,
+  input_folder: String,
+  output_folder: String,
+  language: String,
+  options: &options::Options,
+) -> bool {
+  return cli::generate(file_path, input_folder, output_folder, language, options);
 }
 
+/// Warns about the vertex attributes that `files` declare with different types, the extensions
+/// they give different behaviors, and the version, uniforms, uniform blocks and varyings that
+/// do not match between a vertex and fragment pair.
 #[wasm_bindgen]
-pub fn start_cli(file_path: String, input_folder: String, output_folder: String) {
-  cli::generate(file_path, input_folder, output_folder);
+pub fn report_conflicts(files: Vec<String>, options: &options::Options) {
+  cli::report_conflicts(files, options);
+}
+
+/// Version of the generator, part of the `--once` cache so upgrading it regenerates everything.
+#[wasm_bindgen]
+pub fn version() -> String {
+  return env!("CARGO_PKG_VERSION").to_string();
+}
+
+/// A parsed shader, for using the generator as a library from JavaScript, e.g. in a bundler
+/// plugin, instead of running the CLI.
+#[wasm_bindgen]
+pub struct ExtractedShader {
+  shader: api::Shader,
+}
+
+#[wasm_bindgen]
+impl ExtractedShader {
+  /// File name without the extension.
+  #[wasm_bindgen(getter)]
+  pub fn name(&self) -> String {
+    return self.shader.name.clone();
+  }
+
+  /// Source with its imports resolved, as embedded in the generated files.
+  #[wasm_bindgen(getter)]
+  pub fn source(&self) -> String {
+    return self.shader.source.clone();
+  }
+
+  /// Stage, uniforms, inputs, outputs and source of the shader, the same JSON as
+  /// `--language json`.
+  pub fn to_json(&self) -> String {
+    return generator::json::shader_json(&self.shader.source, &self.shader.data).to_pretty_string();
+  }
+}
+
+/// Reads `file_path` and the files it imports and parses the result. Throws instead of printing
+/// the diagnostics the CLI would print.
+#[wasm_bindgen]
+pub fn extract_shader(
+  file_path: String,
+  options: &options::Options,
+) -> Result<ExtractedShader, JsError> {
+  let path = std::path::PathBuf::from(&file_path);
+
+  let resolved = import_resolver::import_resolver::resolve_imports(&path)
+    .map_err(|error| JsError::new(&format!("{}: {:?}", file_path, error)))?;
+
+  let shader = api::extract_shader(&path, &resolved.source, options)
+    .map_err(|error| JsError::new(&format!("{}: {:?}", file_path, error)))?;
+
+  return Ok(ExtractedShader { shader });
+}
+
+/// Same output as `--language ts`.
+#[wasm_bindgen]
+pub fn generate_typescript(shader: &ExtractedShader, options: &options::Options) -> String {
+  return api::render_typescript(&shader.shader, options);
 }
 
 #[wasm_bindgen]
@@ -29,8 +127,33 @@ pub fn resolve_imports(file: String, input_folder: String) -> String {
   let file = std::path::PathBuf::from(file);
   let input_folder = std::path::PathBuf::from(input_folder);
 
-  match import_resolver::import_resolver::try_resolve_imports(&file, &input_folder) {
-    Some(output) => output,
-    None => String::from(""),
+  import_resolver::import_resolver::try_resolve_imports(&file, &input_folder)
+    .map(|resolved| resolved.source)
+    .unwrap_or_default()
+}
+
+/// Canonical paths of the files `file` imports directly. Empty when the file does not exist or
+/// cannot be parsed, the error is reported when the shader is generated.
+#[wasm_bindgen]
+pub fn direct_imports(file: String) -> Vec<String> {
+  if !file_exists(&file) {
+    return Vec::new();
   }
+
+  let file = std::path::PathBuf::from(canonicalize(&file));
+
+  return import_resolver::file_manager::FileManager::new()
+    .get_file_imports(&file)
+    .unwrap_or_default()
+    .iter()
+    .map(|import| import.to_str().unwrap().to_string())
+    .collect();
+}
+
+/// Drops the cached parse of a file so the next generation reads it again from disk.
+#[wasm_bindgen]
+pub fn invalidate_import_cache(file: String) {
+  let file = std::path::PathBuf::from(canonicalize(&file));
+
+  import_resolver::file_manager::invalidate_cached_file(&file);
 }
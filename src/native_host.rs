@@ -0,0 +1,65 @@
+//! Native versions of the functions the JavaScript host provides, so the generator also runs
+//! outside of wasm, e.g. in tests and build scripts.
+
+use std::path::{Component, Path, PathBuf};
+
+pub fn read_file(file: String) -> Option<String> {
+  return std::fs::read_to_string(file).ok();
+}
+
+pub fn logln(message: &str) {
+  println!("{}", message);
+}
+
+pub fn log(message: &str) {
+  print!("{}", message);
+}
+
+pub fn log_with_color(message: &str, _color: &str) {
+  print!("{}", message);
+}
+
+/// Absolute path with `.` and `..` removed, like `path.resolve`. The file does not have to
+/// exist and symbolic links are kept.
+pub fn canonicalize(path: &str) -> String {
+  let path = Path::new(path);
+  let path = if path.is_absolute() {
+    path.to_path_buf()
+  } else {
+    std::env::current_dir().unwrap().join(path)
+  };
+
+  let mut output = PathBuf::new();
+
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        output.pop();
+      }
+      component => output.push(component),
+    }
+  }
+
+  return output.to_str().unwrap().to_string();
+}
+
+pub fn file_exists(path: &str) -> bool {
+  return Path::new(path).exists();
+}
+
+pub fn create_dir_all(path: &str) {
+  std::fs::create_dir_all(path).unwrap();
+}
+
+pub fn write_file(path: &str, content: &str) {
+  std::fs::write(path, content).unwrap();
+}
+
+pub fn now() -> f64 {
+  thread_local! {
+    static START: std::time::Instant = std::time::Instant::now();
+  }
+
+  return START.with(|start| start.elapsed().as_secs_f64() * 1000.0);
+}
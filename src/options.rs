@@ -0,0 +1,244 @@
+use crate::utils::get_shader_type::ShaderType;
+use crate::utils::log::Level;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// Case used for the name of the constant exported for each shader.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NamingConvention {
+  /// `MySpecialShader`
+  #[default]
+  Pascal,
+  /// `mySpecialShader`
+  Camel,
+  /// `MY_SPECIAL_SHADER`
+  ScreamingSnake,
+}
+
+impl NamingConvention {
+  pub fn from_name(name: &str) -> Option<NamingConvention> {
+    match name {
+      "pascal" => Some(NamingConvention::Pascal),
+      "camel" => Some(NamingConvention::Camel),
+      "screaming-snake" => Some(NamingConvention::ScreamingSnake),
+      _ => None,
+    }
+  }
+}
+
+const OUTPUT_EXTENSIONS: [&str; 6] = [".ts", ".mts", ".cts", ".d.ts", ".d.mts", ".d.cts"];
+
+/// Settings that change what the generators emit, built from the config file and the CLI flags.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+  /// GLSL type or struct name -> label used in the TypeScript output.
+  pub(crate) type_overrides: HashMap<String, String>,
+  pub(crate) strip_comments: bool,
+  /// Minify the shader source before it is embedded in the output.
+  pub(crate) minify_source: bool,
+  /// Language -> folder its files are written to instead of the output folder, e.g. to send the
+  /// `rs` files of `--language ts,rs` to `src/generated`.
+  pub(crate) language_output_folders: HashMap<String, String>,
+  /// Extra file extensions (without the dot) and the stage they contain.
+  pub(crate) extensions: HashMap<String, ShaderType>,
+  /// Emit `export interface <Name>Uniforms` in the TypeScript output.
+  pub(crate) emit_interfaces: bool,
+  /// Emit a zod schema for the uniforms in the TypeScript output.
+  pub(crate) emit_zod: bool,
+  /// Emit a constant for the name of every uniform and attribute in the TypeScript output.
+  pub(crate) emit_named_exports: bool,
+  /// Emit a function that sets the uniforms on a WebGL2 program in the TypeScript output.
+  pub(crate) emit_setters: bool,
+  /// Emit a function that looks up the location of every uniform in the TypeScript output.
+  pub(crate) emit_location_cache: bool,
+  /// Emit a factory for a Three.js material in the TypeScript output.
+  pub(crate) emit_threejs: bool,
+  /// Warn about uniforms and attributes that no expression reads.
+  pub(crate) warn_unused: bool,
+  /// Write a Markdown table of the uniforms next to the TypeScript output.
+  pub(crate) emit_docs: bool,
+  /// The generated files are printed instead of written, `--dry-run` of the CLI.
+  pub(crate) dry_run: bool,
+  /// Write a JSON Schema of the shader object next to the TypeScript output.
+  pub(crate) emit_json_schema: bool,
+  /// Report warnings and errors as newline-delimited JSON.
+  pub(crate) json_errors: bool,
+  /// Write each output file into the same subfolder its shader has in the input folder.
+  pub(crate) mirror_structure: bool,
+  /// Write a source map of the embedded shader source next to the TypeScript output.
+  pub(crate) source_map: bool,
+  pub(crate) naming_convention: NamingConvention,
+  /// Extension of the TypeScript files, `.ts` when not set. `.d.ts` writes declarations only.
+  pub(crate) output_extension: Option<String>,
+  /// Put in front of the exported names of a shader whose file name starts with a digit, `_`
+  /// when not set.
+  pub(crate) export_prefix: Option<String>,
+  /// Handlebars template rendered instead of the built-in backends and the extension of the
+  /// files it generates.
+  pub(crate) template: Option<(String, String)>,
+  /// Least severe diagnostic that is printed.
+  pub(crate) verbosity: Level,
+}
+
+#[wasm_bindgen]
+impl Options {
+  #[wasm_bindgen(constructor)]
+  pub fn new() -> Options {
+    return Options::default();
+  }
+
+  pub fn set_type_override(&mut self, glsl_type: String, label: String) {
+    self.type_overrides.insert(glsl_type, label);
+  }
+
+  pub fn set_language_output_folder(&mut self, language: String, output_folder: String) {
+    self.language_output_folders.insert(language, output_folder);
+  }
+
+  pub fn set_strip_comments(&mut self, strip_comments: bool) {
+    self.strip_comments = strip_comments;
+  }
+
+  pub fn set_minify_source(&mut self, minify_source: bool) {
+    self.minify_source = minify_source;
+  }
+
+  pub fn set_emit_interfaces(&mut self, emit_interfaces: bool) {
+    self.emit_interfaces = emit_interfaces;
+  }
+
+  pub fn set_emit_zod(&mut self, emit_zod: bool) {
+    self.emit_zod = emit_zod;
+  }
+
+  pub fn set_emit_named_exports(&mut self, emit_named_exports: bool) {
+    self.emit_named_exports = emit_named_exports;
+  }
+
+  pub fn set_emit_setters(&mut self, emit_setters: bool) {
+    self.emit_setters = emit_setters;
+  }
+
+  pub fn set_emit_location_cache(&mut self, emit_location_cache: bool) {
+    self.emit_location_cache = emit_location_cache;
+  }
+
+  pub fn set_emit_threejs(&mut self, emit_threejs: bool) {
+    self.emit_threejs = emit_threejs;
+  }
+
+  pub fn set_template(&mut self, template: String, extension: String) {
+    self.template = Some((template, extension));
+  }
+
+  pub fn set_warn_unused(&mut self, warn_unused: bool) {
+    self.warn_unused = warn_unused;
+  }
+
+  pub fn set_emit_docs(&mut self, emit_docs: bool) {
+    self.emit_docs = emit_docs;
+  }
+
+  pub fn set_dry_run(&mut self, dry_run: bool) {
+    self.dry_run = dry_run;
+  }
+
+  pub fn set_emit_json_schema(&mut self, emit_json_schema: bool) {
+    self.emit_json_schema = emit_json_schema;
+  }
+
+  pub fn set_json_errors(&mut self, json_errors: bool) {
+    self.json_errors = json_errors;
+  }
+
+  pub fn set_mirror_structure(&mut self, mirror_structure: bool) {
+    self.mirror_structure = mirror_structure;
+  }
+
+  pub fn set_source_map(&mut self, source_map: bool) {
+    self.source_map = source_map;
+  }
+
+  /// Returns false when `naming_convention` is not one of `pascal`, `camel` or
+  /// `screaming-snake`.
+  pub fn set_naming_convention(&mut self, naming_convention: String) -> bool {
+    return match NamingConvention::from_name(&naming_convention) {
+      Some(naming_convention) => {
+        self.naming_convention = naming_convention;
+        true
+      }
+      None => false,
+    };
+  }
+
+  /// Returns false when `output_extension` is not one of `.ts`, `.mts`, `.cts`, `.d.ts`,
+  /// `.d.mts` or `.d.cts`.
+  pub fn set_output_extension(&mut self, output_extension: String) -> bool {
+    let is_supported = OUTPUT_EXTENSIONS.contains(&output_extension.as_str());
+
+    if is_supported {
+      self.output_extension = Some(output_extension);
+    }
+
+    return is_supported;
+  }
+
+  /// Returns false when `export_prefix` cannot start a JavaScript identifier.
+  pub fn set_export_prefix(&mut self, export_prefix: String) -> bool {
+    let mut chars = export_prefix.chars();
+    let is_identifier = chars
+      .next()
+      .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+      && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+      self.export_prefix = Some(export_prefix);
+    }
+
+    return is_identifier;
+  }
+
+  /// Returns false when `verbosity` is not one of `debug`, `info`, `warn` or `error`.
+  pub fn set_verbosity(&mut self, verbosity: String) -> bool {
+    return match Level::from_name(&verbosity) {
+      Some(level) => {
+        self.verbosity = level;
+        true
+      }
+      None => false,
+    };
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn mirror_structure(&self) -> bool {
+    return self.mirror_structure;
+  }
+
+  /// Extension of the TypeScript files, e.g. `.ts` or `.d.ts`.
+  #[wasm_bindgen(getter)]
+  pub fn output_extension(&self) -> String {
+    return self
+      .output_extension
+      .clone()
+      .unwrap_or_else(|| ".ts".to_string());
+  }
+
+  /// Returns false when `stage` is not one of `vertex`, `fragment`, `geometry`, `compute`,
+  /// `tess-control` or `tess-evaluation`.
+  pub fn set_extension(&mut self, extension: String, stage: String) -> bool {
+    return match ShaderType::from_name(&stage) {
+      Some(shader_type) => {
+        self.extensions.insert(extension, shader_type);
+        true
+      }
+      None => false,
+    };
+  }
+
+  pub fn extensions(&self) -> Vec<String> {
+    let mut extensions = self.extensions.keys().cloned().collect::<Vec<String>>();
+    extensions.sort();
+    return extensions;
+  }
+}
@@ -1,19 +1,49 @@
 pub const FRAGMENT_SHADER_EXTENSIONS: [&str; 2] = ["frag", "fs"];
 pub const VERTEX_SHADER_EXTENSIONS: [&str; 2] = ["vert", "vs"];
+pub const GEOMETRY_SHADER_EXTENSIONS: [&str; 2] = ["geom", "gs"];
+pub const COMPUTE_SHADER_EXTENSIONS: [&str; 1] = ["comp"];
+pub const TESS_CONTROL_SHADER_EXTENSIONS: [&str; 1] = ["tesc"];
+pub const TESS_EVALUATION_SHADER_EXTENSIONS: [&str; 1] = ["tese"];
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ShaderType {
   Fragment,
   Vertex,
+  Geometry,
+  Compute,
+  TessControl,
+  TessEvaluation,
+}
+
+impl ShaderType {
+  pub fn from_name(name: &str) -> Option<ShaderType> {
+    match name {
+      "vertex" => Some(ShaderType::Vertex),
+      "fragment" => Some(ShaderType::Fragment),
+      "geometry" => Some(ShaderType::Geometry),
+      "compute" => Some(ShaderType::Compute),
+      "tess-control" => Some(ShaderType::TessControl),
+      "tess-evaluation" => Some(ShaderType::TessEvaluation),
+      _ => None,
+    }
+  }
 }
 
 pub fn get_shader_type(file_path: &std::path::PathBuf) -> Option<ShaderType> {
-  let extension = file_path.extension().unwrap().to_str().unwrap();
+  let extension = file_path.extension()?.to_str()?;
 
   if FRAGMENT_SHADER_EXTENSIONS.contains(&extension) {
     return Some(ShaderType::Fragment);
   } else if VERTEX_SHADER_EXTENSIONS.contains(&extension) {
     return Some(ShaderType::Vertex);
+  } else if GEOMETRY_SHADER_EXTENSIONS.contains(&extension) {
+    return Some(ShaderType::Geometry);
+  } else if COMPUTE_SHADER_EXTENSIONS.contains(&extension) {
+    return Some(ShaderType::Compute);
+  } else if TESS_CONTROL_SHADER_EXTENSIONS.contains(&extension) {
+    return Some(ShaderType::TessControl);
+  } else if TESS_EVALUATION_SHADER_EXTENSIONS.contains(&extension) {
+    return Some(ShaderType::TessEvaluation);
   }
 
   return None;
@@ -0,0 +1,112 @@
+/// Minimal JSON value used by the backends that emit JSON files and by JSON diagnostics.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+  Null,
+  Bool(bool),
+  Number(f64),
+  String(String),
+  Array(Vec<Json>),
+  Object(Vec<(String, Json)>),
+}
+
+impl Json {
+  pub fn string(value: &str) -> Json {
+    Json::String(value.to_string())
+  }
+
+  pub fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(
+      fields
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect(),
+    )
+  }
+
+  /// Serializes the value with two space indentation.
+  pub fn to_pretty_string(&self) -> String {
+    let mut output = String::new();
+    self.write(&mut output, 0);
+    return output;
+  }
+
+  /// Serializes the value on a single line.
+  pub fn to_compact_string(&self) -> String {
+    return match self {
+      Json::Array(values) => format!(
+        "[{}]",
+        values
+          .iter()
+          .map(Json::to_compact_string)
+          .collect::<Vec<String>>()
+          .join(",")
+      ),
+      Json::Object(fields) => format!(
+        "{{{}}}",
+        fields
+          .iter()
+          .map(|(key, value)| format!("{}:{}", escape(key), value.to_compact_string()))
+          .collect::<Vec<String>>()
+          .join(",")
+      ),
+      value => value.to_pretty_string(),
+    };
+  }
+
+  fn write(&self, output: &mut String, depth: usize) {
+    match self {
+      Json::Null => output.push_str("null"),
+      Json::Bool(value) => output.push_str(&value.to_string()),
+      Json::Number(value) if value.is_finite() => output.push_str(&value.to_string()),
+      Json::Number(_) => output.push_str("null"),
+      Json::String(value) => output.push_str(&escape(value)),
+      Json::Array(values) if values.is_empty() => output.push_str("[]"),
+      Json::Array(values) => {
+        output.push_str("[\n");
+
+        for (i, value) in values.iter().enumerate() {
+          output.push_str(&"  ".repeat(depth + 1));
+          value.write(output, depth + 1);
+          output.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+        }
+
+        output.push_str(&"  ".repeat(depth));
+        output.push(']');
+      }
+      Json::Object(fields) if fields.is_empty() => output.push_str("{}"),
+      Json::Object(fields) => {
+        output.push_str("{\n");
+
+        for (i, (key, value)) in fields.iter().enumerate() {
+          output.push_str(&"  ".repeat(depth + 1));
+          output.push_str(&escape(key));
+          output.push_str(": ");
+          value.write(output, depth + 1);
+          output.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+        }
+
+        output.push_str(&"  ".repeat(depth));
+        output.push('}');
+      }
+    }
+  }
+}
+
+fn escape(value: &str) -> String {
+  let mut output = String::from("\"");
+
+  for c in value.chars() {
+    match c {
+      '"' => output.push_str("\\\""),
+      '\\' => output.push_str("\\\\"),
+      '\n' => output.push_str("\\n"),
+      '\r' => output.push_str("\\r"),
+      '\t' => output.push_str("\\t"),
+      c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+      c => output.push(c),
+    }
+  }
+
+  output.push('"');
+  return output;
+}
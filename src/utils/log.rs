@@ -1,16 +1,60 @@
-use crate::log_with_color;
+use crate::utils::json::Json;
+use crate::{log, log_with_color, logln};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+/// Ordered from the most to the least verbose.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
 pub enum Level {
-  // INFO,
+  DEBUG,
+  #[default]
+  INFO,
   WARN,
   ERROR,
 }
 
+impl Level {
+  pub fn from_name(name: &str) -> Option<Level> {
+    match name {
+      "debug" => Some(Level::DEBUG),
+      "info" => Some(Level::INFO),
+      "warn" => Some(Level::WARN),
+      "error" => Some(Level::ERROR),
+      _ => None,
+    }
+  }
+}
+
+thread_local! {
+  static JSON_DIAGNOSTICS: Cell<bool> = const { Cell::new(false) };
+}
+
+// Least severe level that is printed
+static VERBOSITY: AtomicUsize = AtomicUsize::new(Level::INFO as usize);
+
+pub fn set_verbosity(level: Level) {
+  VERBOSITY.store(level as usize, Ordering::Relaxed);
+}
+
+/// Whether messages of `level` are printed with the current verbosity.
+pub fn enabled(level: Level) -> bool {
+  return level as usize >= VERBOSITY.load(Ordering::Relaxed);
+}
+
+/// Switches diagnostics to newline-delimited JSON objects, for editors and CI.
+pub fn set_json_diagnostics(enabled: bool) {
+  JSON_DIAGNOSTICS.with(|json| json.set(enabled));
+}
+
+/// Prints the colored prefix of a message. Callers check `enabled` first.
 pub fn print_level(level: Level) {
   match level {
-    // Level::INFO => {
-    //   log_with_color("[INFO]\t", "green");
-    // }
+    Level::DEBUG => {
+      log_with_color("[DEBUG]\t", "gray");
+    }
+    Level::INFO => {
+      log_with_color("[INFO]\t", "green");
+    }
     Level::WARN => {
       log_with_color("[WARN]\t", "yellow");
     }
@@ -19,3 +63,54 @@ pub fn print_level(level: Level) {
     }
   }
 }
+
+/// Part of a diagnostic message. Highlighted parts (file paths, identifiers) are shown in blue.
+pub enum Segment<'a> {
+  Text(&'a str),
+  Highlight(&'a str),
+}
+
+/// Prints a message, warning or error, either as a colored line or as a JSON object with `level`,
+/// `code`, `message` and the extra `fields`.
+pub fn print_diagnostic(level: Level, code: &str, segments: &[Segment], fields: Vec<(&str, Json)>) {
+  if !enabled(level) {
+    return;
+  }
+
+  if JSON_DIAGNOSTICS.with(|json| json.get()) {
+    let level_name = match level {
+      Level::DEBUG => "debug",
+      Level::INFO => "info",
+      Level::WARN => "warning",
+      Level::ERROR => "error",
+    };
+
+    let message = segments
+      .iter()
+      .map(|segment| match segment {
+        Segment::Text(text) | Segment::Highlight(text) => *text,
+      })
+      .collect::<String>();
+
+    let mut object = vec![
+      ("level", Json::string(level_name)),
+      ("code", Json::string(code)),
+      ("message", Json::String(message)),
+    ];
+    object.extend(fields);
+
+    logln(&Json::object(object).to_compact_string());
+    return;
+  }
+
+  print_level(level);
+
+  for segment in segments {
+    match segment {
+      Segment::Text(text) => log(text),
+      Segment::Highlight(text) => log_with_color(text, "blue"),
+    }
+  }
+
+  logln("");
+}
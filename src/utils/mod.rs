@@ -1,2 +1,6 @@
 pub mod get_shader_type;
+pub mod json;
 pub mod log;
+pub mod source;
+pub mod source_map;
+pub mod template;
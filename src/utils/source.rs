@@ -0,0 +1,120 @@
+/// Removes `//` and `/* */` comments. Newlines inside block comments are kept so line numbers
+/// in compiler errors still match the original file, and single line block comments become a
+/// space.
+pub fn strip_comments(source: &str) -> String {
+  let mut output = String::new();
+  let mut chars = source.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match (c, chars.peek()) {
+      ('/', Some('/')) => {
+        while let Some(c) = chars.peek() {
+          if *c == '\n' {
+            break;
+          }
+
+          chars.next();
+        }
+      }
+      ('/', Some('*')) => {
+        chars.next();
+        let mut spans_lines = false;
+
+        while let Some(c) = chars.next() {
+          if c == '*' && chars.peek() == Some(&'/') {
+            chars.next();
+            break;
+          }
+
+          if c == '\n' {
+            output.push('\n');
+            spans_lines = true;
+          }
+        }
+
+        // A comment separates tokens, `a/**/b` must not become `ab`
+        if !spans_lines {
+          output.push(' ');
+        }
+      }
+      _ => output.push(c),
+    }
+  }
+
+  // Drop the whitespace left at the end of lines that ended in a comment
+  let mut stripped = output
+    .lines()
+    .map(|line| line.trim_end())
+    .collect::<Vec<&str>>()
+    .join("\n");
+
+  // `lines` does not keep the newline that ends the last line
+  if output.ends_with('\n') {
+    stripped.push('\n');
+  }
+
+  return stripped;
+}
+
+/// Removes comments, blank lines and the whitespace that is not needed to keep tokens apart.
+/// Preprocessor directives stay on their own line. Also returns, for each output line, the line
+/// of `source` it came from.
+pub fn minify(source: &str) -> (String, Vec<usize>) {
+  let (lines, minified): (Vec<usize>, Vec<String>) = strip_comments(source)
+    .lines()
+    .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+    .enumerate()
+    .filter(|(_, words)| !words.is_empty())
+    .map(|(i, words)| {
+      // Directives keep their spacing, e.g. `#define SCALE (a - b)`
+      if words[0].starts_with('#') {
+        return (i, words.join(" "));
+      }
+
+      let mut line = String::from(words[0]);
+
+      for word in &words[1..] {
+        let previous = line.chars().last().unwrap();
+        let next = word.chars().next().unwrap();
+
+        if needs_space(previous, next) {
+          line.push(' ');
+        }
+
+        line.push_str(word);
+      }
+
+      (i, line)
+    })
+    .unzip();
+
+  return (minified.join("\n"), lines);
+}
+
+// Identifiers and numbers have to stay apart, and so do operators that would fuse into a
+// different one, e.g. `a - -b`
+fn needs_space(previous: char, next: char) -> bool {
+  let is_separator = |c: char| "()[]{},;".contains(c);
+  let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+
+  if is_separator(previous) || is_separator(next) {
+    return false;
+  }
+
+  return is_word(previous) == is_word(next);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stripping_comments_keeps_the_lines_and_the_final_newline() {
+    let source = "uniform float time; // seconds\n/* multi\nline */\nvoid main() {}\n";
+
+    assert_eq!(
+      strip_comments(source),
+      "uniform float time;\n\n\nvoid main() {}\n"
+    );
+  }
+}
@@ -0,0 +1,100 @@
+use crate::utils::json::Json;
+use std::path::{Component, Path};
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Source map v3 for a generated file whose lines were each copied from at most one line of a
+/// source, given as the source path and its zero based line number. Every mapped line gets a
+/// single segment pointing at the start of the original line.
+pub fn line_source_map(file: &str, lines: &[Option<(String, usize)>]) -> Json {
+  let mut sources: Vec<String> = Vec::new();
+  let mut mappings = Vec::new();
+
+  // Everything but the generated column is relative to the previous segment
+  let mut previous_source = 0;
+  let mut previous_line = 0;
+
+  for origin in lines {
+    let (source, line) = match origin {
+      Some(origin) => origin,
+      None => {
+        mappings.push(String::new());
+        continue;
+      }
+    };
+
+    let source_index = match sources.iter().position(|existing| existing == source) {
+      Some(index) => index,
+      None => {
+        sources.push(source.clone());
+        sources.len() - 1
+      }
+    };
+
+    let mut segment = vlq(0);
+    segment += &vlq(source_index as i64 - previous_source as i64);
+    segment += &vlq(*line as i64 - previous_line as i64);
+    segment += &vlq(0);
+    mappings.push(segment);
+
+    previous_source = source_index;
+    previous_line = *line;
+  }
+
+  return Json::object(vec![
+    ("version", Json::Number(3.0)),
+    ("file", Json::string(file)),
+    (
+      "sources",
+      Json::Array(sources.iter().map(|source| Json::string(source)).collect()),
+    ),
+    ("names", Json::Array(Vec::new())),
+    ("mappings", Json::String(mappings.join(";"))),
+  ]);
+}
+
+/// Path of `to` relative to the folder `from`, with `/` separators. Both must be absolute.
+pub fn relative_path(from: &Path, to: &Path) -> String {
+  let from = from.components().collect::<Vec<Component>>();
+  let to = to.components().collect::<Vec<Component>>();
+
+  let common = from
+    .iter()
+    .zip(to.iter())
+    .take_while(|(a, b)| a == b)
+    .count();
+
+  let mut parts = vec!["..".to_string(); from.len() - common];
+
+  for component in &to[common..] {
+    parts.push(component.as_os_str().to_str().unwrap().to_string());
+  }
+
+  return parts.join("/");
+}
+
+// Base64 VLQ: the sign goes in the lowest bit and each digit carries five bits, with the sixth
+// bit set when more digits follow
+fn vlq(value: i64) -> String {
+  let mut value = if value < 0 {
+    ((-value) << 1) | 1
+  } else {
+    value << 1
+  };
+  let mut output = String::new();
+
+  loop {
+    let mut digit = value & 31;
+    value >>= 5;
+
+    if value > 0 {
+      digit |= 32;
+    }
+
+    output.push(BASE64[digit as usize] as char);
+
+    if value == 0 {
+      return output;
+    }
+  }
+}
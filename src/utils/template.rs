@@ -0,0 +1,512 @@
+//! Renders the subset of Handlebars used by `--template`: `{{path}}`, the `#if`, `#unless`,
+//! `#each` and `#with` blocks with `{{else}}`, `{{! comments }}`, standalone lines and `~`
+//! whitespace control. Values are inserted unescaped, since the output is code and not HTML.
+
+use super::json::Json;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Helper {
+  If,
+  Unless,
+  Each,
+  With,
+}
+
+impl Helper {
+  fn from_name(name: &str) -> Option<Helper> {
+    return match name {
+      "if" => Some(Helper::If),
+      "unless" => Some(Helper::Unless),
+      "each" => Some(Helper::Each),
+      "with" => Some(Helper::With),
+      _ => None,
+    };
+  }
+
+  fn name(&self) -> &'static str {
+    return match self {
+      Helper::If => "if",
+      Helper::Unless => "unless",
+      Helper::Each => "each",
+      Helper::With => "with",
+    };
+  }
+}
+
+#[derive(Debug)]
+enum Tag {
+  Value(String),
+  Open(Helper, String),
+  Else,
+  Close(Helper),
+  Comment,
+}
+
+#[derive(Debug)]
+enum Token {
+  Text(String),
+  Tag {
+    tag: Tag,
+    line: usize,
+    trim_before: bool,
+    trim_after: bool,
+  },
+}
+
+#[derive(Debug)]
+enum Node {
+  Text(String),
+  Value(String),
+  Block {
+    helper: Helper,
+    path: String,
+    body: Vec<Node>,
+    inverse: Vec<Node>,
+  },
+}
+
+// Value a block is rendered with, and the `@` variables of `#each`
+struct Frame {
+  value: Json,
+  index: Option<usize>,
+  key: Option<String>,
+  last: bool,
+}
+
+/// Renders `template` with `context` as the root value. The error names the line of the
+/// template where it happened.
+pub fn render(template: &str, context: &Json) -> Result<String, String> {
+  let nodes = parse(tokenize(template)?)?;
+
+  let mut frames = vec![Frame {
+    value: context.clone(),
+    index: None,
+    key: None,
+    last: false,
+  }];
+  let mut output = String::new();
+  render_nodes(&nodes, &mut frames, &mut output);
+
+  return Ok(output);
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token>, String> {
+  // Text and tags alternate, so every tag has a text token on both sides
+  let mut tokens = Vec::new();
+  let mut rest = template;
+  let mut line = 1;
+
+  while let Some(start) = rest.find("{{") {
+    tokens.push(Token::Text(rest[..start].to_string()));
+    line += rest[..start].matches('\n').count();
+
+    let tag = &rest[start..];
+    let (open, close) = if tag.starts_with("{{{") {
+      ("{{{", "}}}")
+    } else if tag.starts_with("{{!--") || tag.starts_with("{{~!--") {
+      ("{{", "--}}")
+    } else {
+      ("{{", "}}")
+    };
+
+    let end = match tag[open.len()..].find(close) {
+      Some(end) => open.len() + end,
+      None => return Err(format!("line {}: unclosed `{}`", line, open)),
+    };
+
+    let content = &tag[open.len()..end];
+    let trim_before = content.starts_with('~');
+    let trim_after = content.ends_with('~');
+    let content = content.trim_start_matches('~').trim_end_matches('~').trim();
+
+    tokens.push(Token::Tag {
+      tag: parse_tag(content, line)?,
+      line,
+      trim_before,
+      trim_after,
+    });
+
+    line += content.matches('\n').count();
+    rest = &tag[end + close.len()..];
+  }
+
+  tokens.push(Token::Text(rest.to_string()));
+  strip_standalone_lines(&mut tokens);
+
+  return Ok(tokens);
+}
+
+fn parse_tag(content: &str, line: usize) -> Result<Tag, String> {
+  if content.starts_with('!') {
+    return Ok(Tag::Comment);
+  }
+
+  if content == "else" {
+    return Ok(Tag::Else);
+  }
+
+  let helper = |name: &str| {
+    Helper::from_name(name).ok_or_else(|| format!("line {}: unknown helper `{}`", line, name))
+  };
+
+  if let Some(block) = content.strip_prefix('#') {
+    let (name, path) = block.split_once(char::is_whitespace).unwrap_or((block, ""));
+
+    if path.trim().is_empty() {
+      return Err(format!("line {}: `#{}` needs a value", line, name));
+    }
+
+    return Ok(Tag::Open(helper(name)?, path.trim().to_string()));
+  }
+
+  if let Some(name) = content.strip_prefix('/') {
+    return Ok(Tag::Close(helper(name.trim())?));
+  }
+
+  if content.is_empty() {
+    return Err(format!("line {}: empty tag", line));
+  }
+
+  return Ok(Tag::Value(content.to_string()));
+}
+
+// Like Handlebars, a line with only a block tag or a comment is removed with its line break,
+// so templates can put them on lines of their own
+fn strip_standalone_lines(tokens: &mut [Token]) {
+  let text = |token: &Token| match token {
+    Token::Text(text) => text.clone(),
+    Token::Tag { .. } => String::new(),
+  };
+
+  let last = tokens.len() - 1;
+  let mut standalone = Vec::new();
+
+  for (i, token) in tokens.iter().enumerate() {
+    let Token::Tag { tag, .. } = token else {
+      continue;
+    };
+
+    if matches!(tag, Tag::Value(_)) {
+      continue;
+    }
+
+    let before = text(&tokens[i - 1]);
+    let after = text(&tokens[i + 1]);
+
+    let line_start = match before.rfind('\n') {
+      Some(position) => before[position + 1..].trim().is_empty(),
+      None => i - 1 == 0 && before.trim().is_empty(),
+    };
+    let line_end = match after.find('\n') {
+      Some(position) => after[..position].trim().is_empty(),
+      None => i + 1 == last && after.trim().is_empty(),
+    };
+
+    if line_start && line_end {
+      standalone.push(i);
+    }
+  }
+
+  // The end of the text before is removed first, the text can also follow another tag
+  for i in &standalone {
+    if let Token::Text(before) = &mut tokens[i - 1] {
+      match before.rfind('\n') {
+        Some(position) => before.truncate(position + 1),
+        None => before.clear(),
+      }
+    }
+  }
+
+  for i in &standalone {
+    if let Token::Text(after) = &mut tokens[i + 1] {
+      match after.find('\n') {
+        Some(position) => {
+          after.drain(..=position);
+        }
+        None => after.clear(),
+      }
+    }
+  }
+
+  for i in 0..tokens.len() {
+    let (trim_before, trim_after) = match &tokens[i] {
+      Token::Tag {
+        trim_before,
+        trim_after,
+        ..
+      } => (*trim_before, *trim_after),
+      Token::Text(_) => continue,
+    };
+
+    if let (true, Token::Text(before)) = (trim_before, &mut tokens[i - 1]) {
+      *before = before.trim_end().to_string();
+    }
+
+    if let (true, Token::Text(after)) = (trim_after, &mut tokens[i + 1]) {
+      *after = after.trim_start().to_string();
+    }
+  }
+}
+
+// A block whose closing tag has not been reached yet
+struct OpenBlock {
+  helper: Helper,
+  path: String,
+  body: Vec<Node>,
+  inverse: Vec<Node>,
+  in_else: bool,
+  line: usize,
+}
+
+fn parse(tokens: Vec<Token>) -> Result<Vec<Node>, String> {
+  let mut stack: Vec<OpenBlock> = Vec::new();
+  let mut nodes = Vec::new();
+
+  for token in tokens {
+    let node = match token {
+      Token::Text(text) if text.is_empty() => continue,
+      Token::Text(text) => Node::Text(text),
+      Token::Tag { tag, line, .. } => match tag {
+        Tag::Comment => continue,
+        Tag::Value(path) => Node::Value(path),
+        Tag::Open(helper, path) => {
+          stack.push(OpenBlock {
+            helper,
+            path,
+            body: Vec::new(),
+            inverse: Vec::new(),
+            in_else: false,
+            line,
+          });
+          continue;
+        }
+        Tag::Else => match stack.last_mut() {
+          Some(block) if !block.in_else => {
+            block.in_else = true;
+            continue;
+          }
+          _ => return Err(format!("line {}: `else` outside of a block", line)),
+        },
+        Tag::Close(helper) => match stack.pop() {
+          Some(block) if block.helper == helper => Node::Block {
+            helper,
+            path: block.path,
+            body: block.body,
+            inverse: block.inverse,
+          },
+          _ => return Err(format!("line {}: unexpected `/{}`", line, helper.name())),
+        },
+      },
+    };
+
+    match stack.last_mut() {
+      Some(block) if block.in_else => block.inverse.push(node),
+      Some(block) => block.body.push(node),
+      None => nodes.push(node),
+    }
+  }
+
+  if let Some(block) = stack.pop() {
+    return Err(format!(
+      "line {}: unclosed `#{}`",
+      block.line,
+      block.helper.name()
+    ));
+  }
+
+  return Ok(nodes);
+}
+
+fn render_nodes(nodes: &[Node], frames: &mut Vec<Frame>, output: &mut String) {
+  for node in nodes {
+    match node {
+      Node::Text(text) => output.push_str(text),
+      Node::Value(path) => output.push_str(&to_text(&lookup(path, frames))),
+      Node::Block {
+        helper,
+        path,
+        body,
+        inverse,
+      } => {
+        let value = lookup(path, frames);
+
+        match helper {
+          Helper::If if is_truthy(&value) => render_nodes(body, frames, output),
+          Helper::Unless if !is_truthy(&value) => render_nodes(body, frames, output),
+          Helper::If | Helper::Unless => render_nodes(inverse, frames, output),
+          Helper::With if is_truthy(&value) => {
+            frames.push(Frame {
+              value,
+              index: None,
+              key: None,
+              last: false,
+            });
+            render_nodes(body, frames, output);
+            frames.pop();
+          }
+          Helper::With => render_nodes(inverse, frames, output),
+          Helper::Each => {
+            let items = match value {
+              Json::Array(values) => values.into_iter().map(|value| (None, value)).collect(),
+              Json::Object(fields) => fields
+                .into_iter()
+                .map(|(key, value)| (Some(key), value))
+                .collect(),
+              _ => Vec::new(),
+            };
+
+            if items.is_empty() {
+              render_nodes(inverse, frames, output);
+            }
+
+            let count = items.len();
+
+            for (index, (key, value)) in items.into_iter().enumerate() {
+              frames.push(Frame {
+                value,
+                index: Some(index),
+                key,
+                last: index + 1 == count,
+              });
+              render_nodes(body, frames, output);
+              frames.pop();
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+// `this`, `name.field`, `../name` for the value outside of the block, `@root.name` and the
+// `@index`, `@key`, `@first` and `@last` variables of `#each`. Missing values are null.
+fn lookup(path: &str, frames: &[Frame]) -> Json {
+  let mut depth = frames.len() - 1;
+  let mut path = path;
+
+  while let Some(rest) = path.strip_prefix("../") {
+    depth = depth.saturating_sub(1);
+    path = rest;
+  }
+
+  let frame = &frames[depth];
+
+  match path {
+    "@index" => {
+      return frame
+        .index
+        .map_or(Json::Null, |index| Json::Number(index as f64))
+    }
+    "@key" => return frame.key.clone().map_or(Json::Null, Json::String),
+    "@first" => return Json::Bool(frame.index == Some(0)),
+    "@last" => return Json::Bool(frame.last),
+    _ => {}
+  }
+
+  let (mut value, path) = match path.strip_prefix("@root") {
+    Some(rest) => (&frames[0].value, rest.trim_start_matches('.')),
+    None => (&frame.value, path),
+  };
+
+  let path = path
+    .strip_prefix("this")
+    .map_or(path, |rest| rest.trim_start_matches('.'));
+
+  for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+    let next = match value {
+      Json::Object(fields) => fields
+        .iter()
+        .find(|(key, _)| key == segment)
+        .map(|(_, value)| value),
+      Json::Array(values) => segment.parse::<usize>().ok().and_then(|i| values.get(i)),
+      _ => None,
+    };
+
+    value = match next {
+      Some(next) => next,
+      None => return Json::Null,
+    };
+  }
+
+  return value.clone();
+}
+
+fn is_truthy(value: &Json) -> bool {
+  return match value {
+    Json::Null | Json::Bool(false) => false,
+    Json::Number(number) => *number != 0.0,
+    Json::String(text) => !text.is_empty(),
+    Json::Array(values) => !values.is_empty(),
+    Json::Bool(true) | Json::Object(_) => true,
+  };
+}
+
+fn to_text(value: &Json) -> String {
+  return match value {
+    Json::Null => String::new(),
+    Json::String(text) => text.clone(),
+    value => value.to_compact_string(),
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn uniforms() -> Json {
+    return Json::object(vec![
+      ("name", Json::string("Wave")),
+      (
+        "uniforms",
+        Json::Array(vec![
+          Json::object(vec![
+            ("name", Json::string("time")),
+            ("arraySize", Json::Null),
+          ]),
+          Json::object(vec![
+            ("name", Json::string("lights")),
+            ("arraySize", Json::Number(4.0)),
+          ]),
+        ]),
+      ),
+    ]);
+  }
+
+  #[test]
+  fn renders_values_and_each_blocks() {
+    let template =
+      "{{#each uniforms}}{{@index}}:{{../name}}.{{name}}{{#unless @last}}, {{/unless}}{{/each}}";
+
+    assert_eq!(
+      render(template, &uniforms()).unwrap(),
+      "0:Wave.time, 1:Wave.lights"
+    );
+  }
+
+  #[test]
+  fn renders_the_else_branch_of_falsy_values() {
+    let template = "{{#each uniforms}}{{#if arraySize}}[{{arraySize}}]{{else}}-{{/if}}{{/each}}";
+
+    assert_eq!(render(template, &uniforms()).unwrap(), "-[4]");
+  }
+
+  #[test]
+  fn removes_standalone_block_lines() {
+    let template = "{\n  {{! one per line }}\n  {{#each uniforms}}\n  {{name}},\n  {{/each}}\n}\n";
+
+    assert_eq!(
+      render(template, &uniforms()).unwrap(),
+      "{\n  time,\n  lights,\n}\n"
+    );
+  }
+
+  #[test]
+  fn reports_unclosed_blocks() {
+    let template = "a\n{{#each uniforms}}\n{{name}}\n";
+
+    assert_eq!(
+      render(template, &uniforms()),
+      Err("line 2: unclosed `#each`".to_string())
+    );
+  }
+}
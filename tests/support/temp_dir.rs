@@ -0,0 +1,35 @@
+//! Temp folder for the tests that need shaders on disk. Shared by the unit tests, through a
+//! `#[path]` module in lib.rs, and by the integration tests, so neither owns a copy.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+/// Folder under the system temp folder, removed when dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+  pub fn new(name: &str) -> Self {
+    let path = std::env::temp_dir().join(format!("glsl-types-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    return TempDir(path);
+  }
+
+  pub fn write(&self, file_name: &str, contents: &str) -> PathBuf {
+    let path = self.0.join(file_name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, contents).unwrap();
+    return path;
+  }
+
+  pub fn path(&self) -> &Path {
+    return &self.0;
+  }
+}
+
+impl Drop for TempDir {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.0);
+  }
+}
@@ -0,0 +1,645 @@
+#![allow(clippy::needless_return)]
+
+use glsl_types::api::{self, ArraySize, Conflict, GeneratorError, Interpolation, Options};
+use std::path::Path;
+use temp_dir::TempDir;
+
+#[path = "support/temp_dir.rs"]
+mod temp_dir;
+
+fn render(file_name: &str, source: &str, options: &Options) -> String {
+  let shader = api::extract_shader(Path::new(file_name), source, options).unwrap();
+  return api::render_typescript(&shader, options);
+}
+
+// Writes the shaders to a temp folder and checks them against each other
+fn conflicts(name: &str, shaders: &[(&str, &str)]) -> Vec<Conflict> {
+  let dir = TempDir::new(name);
+  let files = shaders
+    .iter()
+    .map(|(file_name, source)| dir.write(file_name, source).to_str().unwrap().to_string())
+    .collect::<Vec<String>>();
+
+  return api::find_conflicts(&files, &Options::new());
+}
+
+#[test]
+fn emits_uniforms_with_their_glsl_types() {
+  let output = render(
+    "wave.frag",
+    "#version 300 es\nprecision highp float;\nuniform vec2 resolution;\nuniform float time;\nuniform mat4 model;\nout vec4 color;\nvoid main() {}\n",
+    &Options::new(),
+  );
+
+  assert!(output.starts_with("// DO NOT EDIT THIS FILE\n"));
+  assert!(output.contains("export const Wave = {"));
+  assert!(output.contains("    resolution: \"vec2\",\n"));
+  assert!(output.contains("    time: \"float\",\n"));
+  assert!(output.contains("    model: \"mat4\",\n"));
+  assert!(output.contains("  source: SOURCE\n"));
+}
+
+#[test]
+fn emits_sampler_uniforms() {
+  let output = render(
+    "textured.frag",
+    "#version 300 es\nprecision highp float;\nuniform sampler2D albedo;\nuniform samplerCube environment;\nvoid main() {}\n",
+    &Options::new(),
+  );
+
+  assert!(output.contains("    albedo: \"sampler2D\",\n"));
+  assert!(output.contains("    environment: \"samplerCube\",\n"));
+}
+
+#[test]
+fn emits_array_uniforms() {
+  let mut options = Options::new();
+  options.set_emit_interfaces(true);
+
+  let output = render(
+    "lights.frag",
+    "#version 300 es\nprecision highp float;\nuniform vec3 positions[8];\nvoid main() {}\n",
+    &options,
+  );
+
+  assert!(output.contains("    positions: \"vec3[8]\",\n"));
+  assert!(output.contains("export interface LightsUniforms {\n  positions: Float32Array;\n}"));
+}
+
+#[test]
+fn embeds_the_source() {
+  let source = "#version 300 es\nprecision highp float;\nuniform float time;\nvoid main() {}\n";
+  let output = render("time.frag", source, &Options::new());
+
+  assert!(output.contains(&format!("const SOURCE = /* glsl */ `{}`;", source)));
+}
+
+#[test]
+fn reports_uniforms_declared_with_different_array_sizes() {
+  let source =
+    "#version 300 es\nuniform vec3 positions[8];\nuniform vec3 positions[16];\nvoid main() {}\n";
+  let result = api::extract_shader(Path::new("bones.vert"), source, &Options::new());
+
+  assert!(matches!(
+    result,
+    Err(GeneratorError::UniformArraySizeMismatch { .. })
+  ));
+}
+
+#[test]
+fn reports_unsupported_extensions() {
+  let result = api::extract_shader(Path::new("shader.txt"), "void main() {}\n", &Options::new());
+
+  assert!(matches!(
+    result,
+    Err(GeneratorError::UnsupportedShaderType(_))
+  ));
+}
+
+#[test]
+fn reports_parse_errors() {
+  let result = api::extract_shader(
+    Path::new("broken.frag"),
+    "uniform float;;{\n",
+    &Options::new(),
+  );
+
+  assert!(matches!(
+    result,
+    Err(GeneratorError::CouldNotParseShader(_))
+  ));
+}
+
+#[test]
+fn emits_a_uniform_location_cache() {
+  let mut options = Options::new();
+  options.set_emit_location_cache(true);
+
+  let output = render(
+    "wave.frag",
+    "#version 300 es\nprecision highp float;\nuniform float time;\nuniform mat4 model;\nvoid main() {}\n",
+    &options,
+  );
+
+  assert!(output.contains("export interface WaveUniforms {\n"));
+  assert!(output.contains(
+    "export type WaveUniformLocations = {\n  [K in keyof WaveUniforms]: WebGLUniformLocation | null;\n};"
+  ));
+  assert!(output.contains("export function getWaveLocations(\n  gl: WebGL2RenderingContext,\n  program: WebGLProgram\n): WaveUniformLocations {\n  return {\n    time: gl.getUniformLocation(program, \"time\"),\n    model: gl.getUniformLocation(program, \"model\"),\n  };\n}\n"));
+}
+
+#[test]
+fn emits_a_threejs_material_factory() {
+  let mut options = Options::new();
+  options.set_emit_threejs(true);
+
+  let output = render(
+    "wave.frag",
+    "#version 300 es\nprecision highp float;\nuniform vec3 color;\nuniform mat4 model;\nuniform sampler2D albedo;\nvoid main() {}\n",
+    &options,
+  );
+
+  assert!(output.contains("/// <reference types=\"three\" />\nimport * as THREE from \"three\";\n"));
+  assert!(output.contains(
+    "export function createWaveMaterial(\n  uniforms: Omit<WaveUniforms, \"albedo\"> & { albedo: THREE.Texture },\n"
+  ));
+  assert!(output.contains("    fragmentShader: SOURCE,\n"));
+  assert!(
+    output.contains("      color: { value: new THREE.Vector3().fromArray(uniforms.color) },\n")
+  );
+  assert!(
+    output.contains("      model: { value: new THREE.Matrix4().fromArray(uniforms.model) },\n")
+  );
+  assert!(output.contains("      albedo: { value: uniforms.albedo },\n"));
+}
+
+#[test]
+fn emits_named_exports_for_uniforms_and_attributes() {
+  let mut options = Options::new();
+  options.set_emit_named_exports(true);
+
+  let output = render(
+    "skinned_mesh.vert",
+    "#version 300 es\nuniform mat4 modelMatrix;\nin vec3 position;\nin vec2 uv;\nout vec2 vUv;\nvoid main() {}\n",
+    &options,
+  );
+
+  assert!(output.contains(
+    "export const SKINNED_MESH_UNIFORM_MODEL_MATRIX = \"modelMatrix\";\nexport const SKINNED_MESH_ATTRIB_POSITION = \"position\";\nexport const SKINNED_MESH_ATTRIB_UV = \"uv\";\n"
+  ));
+  assert!(!output.contains("V_UV"));
+}
+
+#[test]
+fn applies_type_overrides() {
+  let mut options = Options::new();
+  options.set_type_override("mat4".to_string(), "Matrix4x4".to_string());
+  options.set_type_override("mat2x3".to_string(), "Matrix2x3".to_string());
+  options.set_type_override("Light".to_string(), "LightUniform".to_string());
+
+  let output = render(
+    "scene.vert",
+    "#version 300 es\nstruct Light { vec3 color; };\nuniform mat4 model;\nuniform mat2x3 skew;\nuniform Light light;\nuniform vec3 tint;\nvoid main() {}\n",
+    &options,
+  );
+
+  assert!(output.contains("    model: \"Matrix4x4\",\n"));
+  assert!(output.contains("    skew: \"Matrix2x3\",\n"));
+  assert!(output.contains("    light: \"LightUniform\",\n"));
+  assert!(output.contains("    tint: \"vec3\",\n"));
+}
+
+#[test]
+fn emits_uniform_doc_comments_as_js_doc() {
+  let mut options = Options::new();
+  options.set_emit_interfaces(true);
+
+  let output = render(
+    "model.vert",
+    "#version 300 es\n// The model-to-world\n// transform matrix\nuniform mat4 modelMatrix;\n// Detached\n\nuniform float time;\nvoid main() {}\n",
+    &options,
+  );
+
+  assert!(
+    output.contains("    /** The model-to-world transform matrix */\n    modelMatrix: \"mat4\",\n")
+  );
+  assert!(
+    output.contains("  /** The model-to-world transform matrix */\n  modelMatrix: Float32Array;\n")
+  );
+  assert!(!output.contains("Detached */"));
+}
+
+#[test]
+fn emits_the_required_extensions() {
+  let output = render(
+    "outline.frag",
+    "#version 100\n#extension GL_OES_standard_derivatives : enable\n#extension GL_EXT_frag_depth : require\n#extension GL_EXT_shader_texture_lod : enable\n#extension GL_EXT_shader_texture_lod : disable\n#extension all : warn\nprecision mediump float;\nvoid main() {}\n",
+    &Options::new(),
+  );
+
+  assert!(output
+    .contains("  requiredExtensions: [\"GL_OES_standard_derivatives\", \"GL_EXT_frag_depth\"],\n"));
+}
+
+#[test]
+fn emits_the_format_of_image_uniforms() {
+  let output = render(
+    "blur.comp",
+    "#version 430\nlayout(local_size_x = 8, local_size_y = 8) in;\nlayout(binding = 0, rgba32f) uniform readonly image2D source;\nlayout(binding = 1, r11f_g11f_b10f) uniform writeonly image2D target;\nuniform uimage3D voxels;\nvoid main() {}\n",
+    &Options::new(),
+  );
+
+  assert!(output.contains("    source: \"image2D\",\n"));
+  assert!(output.contains("    voxels: \"uimage3D\",\n"));
+  assert!(output.contains(
+    "  imageFormats: {\n    source: \"rgba32f\",\n    target: \"r11f_g11f_b10f\",\n  },\n"
+  ));
+}
+
+#[test]
+fn emits_subroutine_uniforms_and_their_functions() {
+  let output = render(
+    "lighting.frag",
+    "#version 400\nsubroutine vec3 Shade(vec3 normal);\nsubroutine (Shade) vec3 diffuse(vec3 normal) { return normal; }\nsubroutine (Shade) vec3 toon(vec3 normal) { return normal; }\nvec3 unrelated(vec3 normal) { return normal; }\nsubroutine uniform Shade shading;\nuniform float time;\nout vec4 color;\nvoid main() {}\n",
+    &Options::new(),
+  );
+
+  assert!(output.contains(
+    "  subroutines: {\n    shading: { type: \"Shade\", functions: [\"diffuse\", \"toon\"] },\n  },\n"
+  ));
+  assert!(!output.contains("    shading: \""));
+  assert!(output.contains("    time: \"float\",\n"));
+}
+
+#[test]
+fn declaration_files_declare_the_exports() {
+  let mut options = Options::new();
+  assert!(options.set_output_extension(".d.ts".to_string()));
+  assert!(!options.set_output_extension(".js".to_string()));
+  options.set_emit_named_exports(true);
+  options.set_emit_setters(true);
+
+  let output = render(
+    "wave.frag",
+    "#version 300 es\nprecision highp float;\nuniform float time;\nvoid main() {}\n",
+    &options,
+  );
+
+  assert!(!output.contains("const SOURCE"));
+  assert!(output.contains("export declare const Wave: {\n"));
+  assert!(output.contains("  source: string\n};"));
+  assert!(output.contains("export declare const WAVE_UNIFORM_TIME = \"time\";\n"));
+  assert!(output.contains("  uniforms: WaveUniforms\n): void;\n"));
+  assert!(!output.contains("gl.uniform1f"));
+}
+
+#[test]
+fn emits_the_tessellation_layouts() {
+  let control = render(
+    "terrain.tesc",
+    "#version 410\nlayout(vertices = 3) out;\nin vec3 vPosition[];\nout vec3 tcPosition[];\nvoid main() {}\n",
+    &Options::new(),
+  );
+
+  assert!(control.contains("  patchVertices: 3,\n"));
+
+  let evaluation = render(
+    "terrain.tese",
+    "#version 410\nlayout(triangles, fractional_even_spacing, cw) in;\nin vec3 tcPosition[];\nuniform mat4 viewProjection;\nvoid main() {}\n",
+    &Options::new(),
+  );
+
+  assert!(evaluation.contains(
+    "  domain: \"triangles\",\n  spacing: \"fractional_even_spacing\",\n  winding: \"cw\",\n  pointMode: false,\n"
+  ));
+  assert!(!evaluation.contains("primitiveIn"));
+}
+
+#[test]
+fn emits_atomic_counters_with_their_bindings() {
+  let output = render(
+    "cull.comp",
+    "#version 430\nlayout(local_size_x = 64) in;\nlayout(binding = 2, offset = 0) uniform atomic_uint visible;\nuniform atomic_uint culled;\nuniform float radius;\nvoid main() {}\n",
+    &Options::new(),
+  );
+
+  assert!(output.contains("  atomicCounters: {\n    visible: 2,\n    culled: 0,\n  },\n"));
+  assert!(!output.contains("    visible: \"atomic_uint\",\n"));
+  assert!(output.contains("    radius: \"float\",\n"));
+}
+
+#[test]
+fn keeps_uniform_doc_comments_when_stripping_comments() {
+  let mut options = Options::new();
+  options.set_strip_comments(true);
+  options.set_emit_interfaces(true);
+
+  let output = render(
+    "model.vert",
+    "#version 300 es\n// The model-to-world transform matrix\nuniform mat4 modelMatrix;\nvoid main() {}\n",
+    &options,
+  );
+
+  assert!(
+    output.contains("    /** The model-to-world transform matrix */\n    modelMatrix: \"mat4\",\n")
+  );
+  assert!(!output.contains("// The model-to-world"));
+}
+
+#[test]
+fn matching_shader_pairs_do_not_conflict() {
+  let conflicts = conflicts(
+    "matching-pair",
+    &[
+      (
+        "wave.vert",
+        "#version 300 es\nstruct Fog { vec3 color; float density; };\nuniform Fog fog;\nuniform float time;\nuniform vec3 lights[4];\nin vec3 position;\nout vec2 uv;\nvoid main() {}\n",
+      ),
+      (
+        "wave.frag",
+        "#version 300 es\nprecision highp float;\nstruct Fog { vec3 color; float density; };\nuniform Fog fog;\nuniform float time;\nuniform vec3 lights[4];\nin vec2 uv;\nout vec4 color;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  assert!(conflicts.is_empty());
+}
+
+#[test]
+fn reports_attributes_declared_with_different_types() {
+  let conflicts = conflicts(
+    "attribute-types",
+    &[
+      (
+        "mesh.vert",
+        "#version 300 es\nin vec3 position;\nin vec2 uv;\nvoid main() {}\n",
+      ),
+      (
+        "sprite.vert",
+        "#version 300 es\nin vec4 position;\nin vec2 uv;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  assert_eq!(conflicts.len(), 1);
+  match &conflicts[0] {
+    Conflict::AttributeType {
+      name,
+      first_file,
+      first_type,
+      second_file,
+      second_type,
+    } => {
+      assert_eq!(name, "position");
+      assert!(first_file.ends_with("mesh.vert"));
+      assert_eq!(first_type, "vec3");
+      assert!(second_file.ends_with("sprite.vert"));
+      assert_eq!(second_type, "vec4");
+    }
+    other => panic!("expected an attribute type conflict, got {:?}", other),
+  }
+}
+
+#[test]
+fn reports_varyings_that_do_not_match() {
+  let conflicts = conflicts(
+    "varyings",
+    &[
+      (
+        "lit.vert",
+        "#version 300 es\nin vec3 position;\nout vec3 normal;\nout vec2 uv;\nout float depth;\nvoid main() {}\n",
+      ),
+      (
+        "lit.frag",
+        "#version 300 es\nprecision highp float;\nin vec4 normal;\nout float depth;\nout vec4 color;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  assert_eq!(conflicts.len(), 3);
+  assert!(matches!(
+    &conflicts[0],
+    Conflict::VaryingType { name, vertex_type, fragment_type, .. }
+      if name == "normal" && vertex_type == "vec3" && fragment_type == "vec4"
+  ));
+  assert!(matches!(
+    &conflicts[1],
+    Conflict::UnreadVarying { name, .. } if name == "uv"
+  ));
+  assert!(matches!(
+    &conflicts[2],
+    Conflict::VaryingDirection { name, qualifier: "out", .. } if name == "depth"
+  ));
+}
+
+#[test]
+fn reports_varyings_interpolated_differently_by_the_stages() {
+  let conflicts = conflicts(
+    "interpolation",
+    &[
+      (
+        "ids.vert",
+        "#version 300 es\nin vec3 position;\nflat out int id;\nout vec2 uv;\nvoid main() {}\n",
+      ),
+      (
+        "ids.frag",
+        "#version 300 es\nprecision highp float;\nsmooth in int id;\nsmooth in vec2 uv;\nout vec4 color;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  // `uv` is smooth in both, that is the default
+  assert_eq!(conflicts.len(), 1);
+  assert!(matches!(
+    &conflicts[0],
+    Conflict::VaryingInterpolation {
+      name,
+      vertex_interpolation: Interpolation::Flat,
+      fragment_interpolation: Interpolation::Smooth,
+      ..
+    } if name == "id"
+  ));
+}
+
+#[test]
+fn reports_fragment_inputs_the_vertex_shader_does_not_write() {
+  let conflicts = conflicts(
+    "fragment-inputs",
+    &[
+      (
+        "quad.vert",
+        "#version 300 es\nin vec3 position;\nin vec2 uv2;\nout vec2 uv;\nvoid main() {}\n",
+      ),
+      (
+        "quad.frag",
+        "#version 300 es\nprecision highp float;\nin vec2 uv;\nin vec2 uv2;\nin vec3 normal;\nout vec4 color;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  assert_eq!(conflicts.len(), 2);
+  // An attribute of the vertex shader, not one of its outputs
+  assert!(matches!(
+    &conflicts[0],
+    Conflict::VaryingDirection { name, qualifier: "in", .. } if name == "uv2"
+  ));
+  match &conflicts[1] {
+    Conflict::UnwrittenVarying {
+      name,
+      vertex_file,
+      fragment_file,
+      fragment_type,
+    } => {
+      assert_eq!(name, "normal");
+      assert!(vertex_file.ends_with("quad.vert"));
+      assert!(fragment_file.ends_with("quad.frag"));
+      assert_eq!(fragment_type, "vec3");
+    }
+    other => panic!("expected an unwritten varying, got {:?}", other),
+  }
+}
+
+#[test]
+fn reports_stages_declaring_different_versions() {
+  let conflicts = conflicts(
+    "versions",
+    &[
+      ("sky.vert", "#version 300 es\nvoid main() {}\n"),
+      (
+        "sky.frag",
+        "#version 310 es\nprecision highp float;\nout vec4 color;\nvoid main() {}\n",
+      ),
+      ("flat.vert", "#version 300 es\nvoid main() {}\n"),
+      ("flat.frag", "precision highp float;\nvoid main() {}\n"),
+    ],
+  );
+
+  assert_eq!(conflicts.len(), 2);
+  match &conflicts[0] {
+    Conflict::Version {
+      vertex_file,
+      vertex_version,
+      fragment_file,
+      fragment_version,
+    } => {
+      assert!(vertex_file.ends_with("sky.vert"));
+      assert_eq!(vertex_version.as_deref(), Some("300 es"));
+      assert!(fragment_file.ends_with("sky.frag"));
+      assert_eq!(fragment_version.as_deref(), Some("310 es"));
+    }
+    other => panic!("expected a version conflict, got {:?}", other),
+  }
+  assert!(matches!(
+    &conflicts[1],
+    Conflict::Version {
+      fragment_version: None,
+      ..
+    }
+  ));
+}
+
+#[test]
+fn reports_uniform_types_that_differ_between_the_stages() {
+  let conflicts = conflicts(
+    "uniform-types",
+    &[
+      (
+        "tinted.vert",
+        "#version 300 es\nuniform vec3 tint;\nuniform float time;\nvoid main() {}\n",
+      ),
+      (
+        "tinted.frag",
+        "#version 300 es\nprecision highp float;\nuniform vec4 tint;\nuniform float time;\nout vec4 color;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  assert_eq!(conflicts.len(), 1);
+  match &conflicts[0] {
+    Conflict::UniformType {
+      name,
+      vertex_file,
+      vertex_type,
+      fragment_file,
+      fragment_type,
+    } => {
+      assert_eq!(name, "tint");
+      assert!(vertex_file.ends_with("tinted.vert"));
+      assert_eq!(vertex_type, "vec3");
+      assert!(fragment_file.ends_with("tinted.frag"));
+      assert_eq!(fragment_type, "vec4");
+    }
+    other => panic!("expected a uniform type conflict, got {:?}", other),
+  }
+}
+
+#[test]
+fn reports_structs_declared_with_other_fields_in_each_stage() {
+  let conflicts = conflicts(
+    "struct-layouts",
+    &[
+      (
+        "lit.vert",
+        "#version 300 es\nstruct Light { vec3 position; float intensity; };\nuniform Light light;\nvoid main() {}\n",
+      ),
+      (
+        "lit.frag",
+        "#version 300 es\nprecision highp float;\nstruct Light { vec3 position; vec3 color; };\nuniform Light light;\nout vec4 color;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  assert_eq!(conflicts.len(), 1);
+  assert!(matches!(
+    &conflicts[0],
+    Conflict::UniformType { name, vertex_type, fragment_type, .. }
+      if name == "light"
+        && vertex_type == "Light { vec3 position; float intensity; }"
+        && fragment_type == "Light { vec3 position; vec3 color; }"
+  ));
+}
+
+#[test]
+fn reports_uniform_blocks_declared_with_other_members_in_each_stage() {
+  let conflicts = conflicts(
+    "uniform-blocks",
+    &[
+      (
+        "model.vert",
+        "#version 300 es\nuniform Transform { mat4 model; mat4 view; };\nuniform Lights { vec3 positions[4]; };\nvoid main() {}\n",
+      ),
+      (
+        "model.frag",
+        "#version 300 es\nprecision highp float;\nuniform Transform { vec4 model; mat4 view; };\nuniform Lights { vec3 positions[4]; };\nout vec4 color;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  assert_eq!(conflicts.len(), 1);
+  assert!(matches!(
+    &conflicts[0],
+    Conflict::UniformBlockLayout { name, vertex_layout, fragment_layout, .. }
+      if name == "Transform"
+        && vertex_layout == "Transform { mat4 model; mat4 view; }"
+        && fragment_layout == "Transform { vec4 model; mat4 view; }"
+  ));
+}
+
+#[test]
+fn reports_uniform_array_sizes_that_differ_between_the_stages() {
+  let conflicts = conflicts(
+    "uniform-array-sizes",
+    &[
+      (
+        "lights.vert",
+        "#version 300 es\nuniform vec3 positions[8];\nuniform float weights[4];\nvoid main() {}\n",
+      ),
+      (
+        "lights.frag",
+        "#version 300 es\nprecision highp float;\nuniform vec3 positions[16];\nuniform float weights;\nout vec4 color;\nvoid main() {}\n",
+      ),
+    ],
+  );
+
+  assert_eq!(conflicts.len(), 2);
+  match &conflicts[0] {
+    Conflict::UniformArraySize {
+      name,
+      vertex_file,
+      vertex_size,
+      fragment_file,
+      fragment_size,
+    } => {
+      assert_eq!(name, "positions");
+      assert!(vertex_file.ends_with("lights.vert"));
+      assert_eq!(*vertex_size, Some(ArraySize::Sized(8)));
+      assert!(fragment_file.ends_with("lights.frag"));
+      assert_eq!(*fragment_size, Some(ArraySize::Sized(16)));
+    }
+    other => panic!("expected a uniform array size conflict, got {:?}", other),
+  }
+  assert!(matches!(
+    &conflicts[1],
+    Conflict::UniformArraySize { name, fragment_size: None, .. } if name == "weights"
+  ));
+}